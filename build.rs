@@ -0,0 +1,61 @@
+//! Checks the format of `PROTOCOL_VERSION` and exposes it as `MCP_PROTOCOL_VERSION`,
+//! and generates the gRPC service when the `grpc` feature is enabled
+
+use std::fs;
+
+const SOURCE: &str = "src/protocol/mod.rs";
+const DECLARATION: &str = "pub const PROTOCOL_VERSION: &str = \"";
+
+fn main() {
+    #[cfg(feature = "grpc")]
+    compile_protos();
+
+    println!("cargo:rerun-if-changed={}", SOURCE);
+
+    let source = match fs::read_to_string(SOURCE) {
+        Ok(source) => source,
+        Err(e) => {
+            println!("cargo:warning=could not read {}: {}", SOURCE, e);
+            return;
+        }
+    };
+    let Some(version) = source
+        .lines()
+        .find_map(|line| line.trim().strip_prefix(DECLARATION))
+        .and_then(|rest| rest.split('"').next())
+    else {
+        println!("cargo:warning=PROTOCOL_VERSION not found in {}", SOURCE);
+        return;
+    };
+
+    if !is_date(version) {
+        println!(
+            "cargo:warning=PROTOCOL_VERSION {:?} does not match YYYY-MM-DD",
+            version
+        );
+    }
+    println!("cargo:rustc-env=MCP_PROTOCOL_VERSION={}", version);
+}
+
+/// Generates the code for `proto/mcp.proto`
+///
+/// Uses the vendored `protoc` unless `PROTOC` points at another one.
+#[cfg(feature = "grpc")]
+fn compile_protos() {
+    if std::env::var_os("PROTOC").is_none() {
+        let protoc = protoc_bin_vendored::protoc_bin_path().expect("no vendored protoc");
+        std::env::set_var("PROTOC", protoc);
+    }
+    tonic_prost_build::compile_protos("proto/mcp.proto")
+        .expect("failed to compile proto/mcp.proto");
+}
+
+/// Whether `version` matches `^\d{4}-\d{2}-\d{2}$`
+fn is_date(version: &str) -> bool {
+    let bytes = version.as_bytes();
+    bytes.len() == 10
+        && bytes.iter().enumerate().all(|(i, b)| match i {
+            4 | 7 => *b == b'-',
+            _ => b.is_ascii_digit(),
+        })
+}
@@ -1,11 +1,10 @@
 use mcprotocol_rs::{
     transport::{ClientTransportFactory, TransportConfig, TransportType},
-    ClientCapabilities, ImplementationInfo, Message, Method, Notification, Request, RequestId,
-    Result, PROTOCOL_VERSION,
+    ClientCapabilities, ImplementationInfo, InitializeResult, Message, Method, Notification,
+    Request, RequestId, Result, PROTOCOL_VERSION,
 };
 use serde_json::json;
 use std::{collections::HashSet, env};
-use tokio;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -48,6 +47,7 @@ async fn main() -> Result<()> {
                 roots: None,
                 sampling: None,
                 experimental: None,
+                tool_streaming: None,
             },
             "clientInfo": ImplementationInfo {
                 name: "Example Client".to_string(),
@@ -79,12 +79,19 @@ async fn main() -> Result<()> {
                     }
 
                     if let Some(result) = response.result {
+                        // 解析初始化结果，缺少 serverInfo 时失败
+                        // Parse initialize result, failing if serverInfo is missing
+                        let result: InitializeResult = match serde_json::from_value(result) {
+                            Ok(result) => result,
+                            Err(e) => {
+                                eprintln!("Invalid initialize result: {}", e);
+                                return Ok(());
+                            }
+                        };
+
                         // 检查服务器版本和能力
                         // Check server version and capabilities
-                        let server_version = result
-                            .get("protocolVersion")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("unknown");
+                        let server_version = result.protocol_version.as_str();
 
                         if server_version != PROTOCOL_VERSION {
                             eprintln!(
@@ -94,7 +101,10 @@ async fn main() -> Result<()> {
                             return Ok(());
                         }
 
-                        eprintln!("Server initialized with version: {}", server_version);
+                        eprintln!(
+                            "Server {} initialized with version: {}",
+                            result.server_info.name, server_version
+                        );
 
                         // 发送初始化完成通知
                         // Send initialized notification
@@ -2,17 +2,17 @@ use mcprotocol_rs::{
     error_codes,
     protocol::ServerCapabilities,
     transport::{ServerTransportFactory, TransportConfig, TransportType},
-    ImplementationInfo, Message, Response, ResponseError, Result, PROTOCOL_VERSION,
+    DefaultErrorHandler, ErrorAction, ErrorHandler, ImplementationInfo, InitializeParams,
+    InitializeResult, Message, RequestId, Response, ResponseError, Result, SessionIdTracker,
+    PROTOCOL_VERSION,
 };
 use serde_json::json;
-use std::collections::HashSet;
-use tokio;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     // 跟踪会话中使用的请求 ID
     // Track request IDs used in the session
-    let mut session_ids = HashSet::new();
+    let mut session_ids = SessionIdTracker::default();
 
     // 配置 Stdio 服务器
     // Configure Stdio server
@@ -44,7 +44,7 @@ async fn main() -> Result<()> {
                     Message::Request(request) => {
                         // 验证请求 ID 的唯一性
                         // Validate request ID uniqueness
-                        if !request.validate_id_uniqueness(&mut session_ids) {
+                        if !session_ids.insert(&request.id) {
                             let error = ResponseError {
                                 code: error_codes::INVALID_REQUEST,
                                 message: "Request ID has already been used".to_string(),
@@ -61,53 +61,61 @@ async fn main() -> Result<()> {
                                 // Handle initialize request
                                 eprintln!("Received initialize request");
 
-                                // 解析客户端能力和版本
-                                // Parse client capabilities and version
-                                if let Some(params) = request.params {
-                                    let client_version = params
-                                        .get("protocolVersion")
-                                        .and_then(|v| v.as_str())
-                                        .unwrap_or("unknown");
-
-                                    // 版本检查
-                                    // Version check
-                                    if client_version != PROTOCOL_VERSION {
-                                        // 发送错误响应
-                                        // Send error response
-                                        let error = ResponseError {
-                                            code: error_codes::INVALID_REQUEST,
-                                            message: "Unsupported protocol version".to_string(),
-                                            data: Some(json!({
-                                                "supported": [PROTOCOL_VERSION],
-                                                "requested": client_version
-                                            })),
-                                        };
-                                        let response = Response::error(error, request.id);
-                                        server.send(Message::Response(response)).await?;
-                                        continue;
-                                    }
+                                // 解析初始化参数，缺少 clientInfo 时返回 INVALID_PARAMS
+                                // Parse initialize params, rejecting a missing clientInfo with INVALID_PARAMS
+                                let params =
+                                    match InitializeParams::from_params(request.params.as_ref()) {
+                                        Ok(params) => params,
+                                        Err(e) => {
+                                            let response =
+                                                Response::error(ResponseError::from(e), request.id);
+                                            server.send(Message::Response(response)).await?;
+                                            continue;
+                                        }
+                                    };
+                                eprintln!(
+                                    "Client: {} {}",
+                                    params.client_info.name, params.client_info.version
+                                );
 
-                                    // 发送成功响应
-                                    // Send success response
-                                    let response = Response::success(
-                                        json!({
-                                            "protocolVersion": PROTOCOL_VERSION,
-                                            "capabilities": ServerCapabilities {
-                                                prompts: None,
-                                                resources: None,
-                                                tools: None,
-                                                logging: Some(json!({})),
-                                                experimental: None,
-                                            },
-                                            "serverInfo": ImplementationInfo {
-                                                name: "Example Server".to_string(),
-                                                version: "1.0.0".to_string(),
-                                            }
-                                        }),
-                                        request.id,
-                                    );
+                                // 版本检查
+                                // Version check
+                                if params.protocol_version != PROTOCOL_VERSION {
+                                    // 发送错误响应
+                                    // Send error response
+                                    let error = ResponseError {
+                                        code: error_codes::INVALID_REQUEST,
+                                        message: "Unsupported protocol version".to_string(),
+                                        data: Some(json!({
+                                            "supported": [PROTOCOL_VERSION],
+                                            "requested": params.protocol_version
+                                        })),
+                                    };
+                                    let response = Response::error(error, request.id);
                                     server.send(Message::Response(response)).await?;
+                                    continue;
                                 }
+
+                                // 发送成功响应
+                                // Send success response
+                                let result = InitializeResult {
+                                    protocol_version: PROTOCOL_VERSION.to_string(),
+                                    capabilities: ServerCapabilities {
+                                        prompts: None,
+                                        resources: None,
+                                        tools: None,
+                                        logging: Some(json!({})),
+                                        experimental: None,
+                                        tool_streaming: None,
+                                    },
+                                    server_info: ImplementationInfo {
+                                        name: "Example Server".to_string(),
+                                        version: "1.0.0".to_string(),
+                                    },
+                                };
+                                let response =
+                                    Response::success(serde_json::to_value(result)?, request.id);
+                                server.send(Message::Response(response)).await?;
                             }
                             "shutdown" => {
                                 if !initialized {
@@ -163,8 +171,17 @@ async fn main() -> Result<()> {
                 }
             }
             Err(e) => {
+                // 由默认错误处理器决定：回复解析错误、跳过或停止
+                // Let the default error handler decide: answer parse errors, skip or stop
                 eprintln!("Error receiving message: {}", e);
-                break;
+                match DefaultErrorHandler.on_receive_error(&e).await {
+                    ErrorAction::Continue => {}
+                    ErrorAction::SendErrorResponse(error) => {
+                        let response = Response::error(error, RequestId::Null);
+                        server.send(Message::Response(response)).await?;
+                    }
+                    ErrorAction::Shutdown => break,
+                }
             }
         }
     }
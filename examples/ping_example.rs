@@ -70,7 +70,7 @@ async fn run_server() -> Result<()> {
 
     // 等待退出信号或超时
     // Wait for exit signal or timeout
-    let (tx, mut rx) = tokio::sync::oneshot::channel::<()>();
+    let (_tx, rx) = tokio::sync::oneshot::channel::<()>();
 
     let exit_signal = async move {
         rx.await.ok();
@@ -139,7 +139,7 @@ async fn run_client() -> Result<()> {
         // 发送 ping 请求
         // Send ping request
         let request_id = RequestId::String(format!("ping-{}", ping_count + 1));
-        let ping_request = Request::new(Method::Ping, None, request_id.clone());
+        let ping_request = Request::ping(request_id.clone());
 
         // 验证请求 ID 的唯一性
         // Validate request ID uniqueness
@@ -249,5 +249,6 @@ fn request_id_to_string(id: &RequestId) -> String {
     match id {
         RequestId::String(s) => s.clone(),
         RequestId::Number(n) => n.to_string(),
+        RequestId::Null => "null".to_string(),
     }
 }
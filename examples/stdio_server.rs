@@ -1,17 +1,17 @@
 use mcprotocol_rs::message;
 use mcprotocol_rs::{
-    protocol::{Message, Response},
+    protocol::{Message, RequestId, Response, SessionIdTracker},
+    server::{DefaultErrorHandler, ErrorAction, ErrorHandler},
     transport::{ServerTransportFactory, TransportConfig, TransportType},
     Result,
 };
 use serde_json::json;
-use std::collections::HashSet;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     // 跟踪会话中使用的请求 ID
     // Track request IDs used in the session
-    let mut session_ids = HashSet::new();
+    let mut session_ids = SessionIdTracker::default();
 
     // 配置 Stdio 服务器
     // Configure Stdio server
@@ -46,7 +46,7 @@ async fn main() -> Result<()> {
                     Message::Request(request) => {
                         // 验证请求 ID 的唯一性
                         // Validate request ID uniqueness
-                        if !request.validate_id_uniqueness(&mut session_ids) {
+                        if !session_ids.insert(&request.id) {
                             let error = Message::Response(Response::error(
                                 message::ResponseError {
                                     code: message::error_codes::INVALID_REQUEST,
@@ -104,8 +104,20 @@ async fn main() -> Result<()> {
                 }
             }
             Err(e) => {
+                // 由默认错误处理器决定：回复解析错误、跳过或停止
+                // Let the default error handler decide: answer parse errors, skip or stop
                 eprintln!("Error receiving message: {}", e);
-                break;
+                match DefaultErrorHandler.on_receive_error(&e).await {
+                    ErrorAction::Continue => {}
+                    ErrorAction::SendErrorResponse(error) => {
+                        let error = Message::Response(Response::error(error, RequestId::Null));
+                        if let Err(e) = server.send(error).await {
+                            eprintln!("Error sending error response: {}", e);
+                            break;
+                        }
+                    }
+                    ErrorAction::Shutdown => break,
+                }
             }
         }
     }
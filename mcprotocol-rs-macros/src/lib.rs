@@ -0,0 +1,159 @@
+//! Derive macros for `mcprotocol-rs`
+//!
+//! Use them through the `macros` feature of the main crate rather than depending
+//! on this crate directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, LitBool, LitStr};
+
+/// Derives `mcprotocol_rs::PromptParams` and `TryFrom<serde_json::Value>`
+///
+/// Every named field becomes a prompt parameter. Fields may carry
+/// `#[param(name = "...", type = "string", required = true)]`; the name defaults
+/// to the field name, the type is unconstrained when omitted and parameters are
+/// optional unless marked required.
+#[proc_macro_derive(PromptParams, attributes(param))]
+pub fn derive_prompt_params(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+struct Param {
+    field: syn::Ident,
+    name: String,
+    ty: Option<String>,
+    required: bool,
+}
+
+const JSON_TYPES: &[&str] = &[
+    "string", "number", "integer", "boolean", "object", "array", "null",
+];
+
+fn parse_param(field: &syn::Field) -> syn::Result<Param> {
+    let ident = field.ident.clone().expect("named field");
+    let mut param = Param {
+        name: ident.to_string().trim_start_matches("r#").to_string(),
+        field: ident,
+        ty: None,
+        required: false,
+    };
+
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("param")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                param.name = meta.value()?.parse::<LitStr>()?.value();
+            } else if meta.path.is_ident("type") {
+                let ty = meta.value()?.parse::<LitStr>()?;
+                if !JSON_TYPES.contains(&ty.value().as_str()) {
+                    return Err(syn::Error::new(ty.span(), "unknown JSON Schema type"));
+                }
+                param.ty = Some(ty.value());
+            } else if meta.path.is_ident("required") {
+                param.required = meta.value()?.parse::<LitBool>()?.value;
+            } else {
+                return Err(meta.error("expected `name`, `type` or `required`"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(param)
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "PromptParams requires a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "PromptParams can only be derived for structs",
+            ))
+        }
+    };
+    let params = fields
+        .iter()
+        .map(parse_param)
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let krate = quote!(::mcprotocol_rs);
+    let json = quote!(#krate::__private::serde_json);
+
+    let properties = params.iter().map(|p| {
+        let name = &p.name;
+        match &p.ty {
+            Some(ty) => {
+                quote!(properties.insert(#name.to_string(), #json::json!({ "type": #ty }));)
+            }
+            None => quote!(properties.insert(#name.to_string(), #json::json!({}));),
+        }
+    });
+    let required = params.iter().filter(|p| p.required).map(|p| &p.name);
+
+    let serialize = params.iter().map(|p| {
+        let (field, name, required) = (&p.field, &p.name, p.required);
+        quote! {
+            let value = #json::to_value(&self.#field).unwrap_or(#json::Value::Null);
+            if #required || !value.is_null() {
+                params.insert(#name.to_string(), value);
+            }
+        }
+    });
+
+    let deserialize = params.iter().map(|p| {
+        let (field, name) = (&p.field, &p.name);
+        quote! {
+            #field: #json::from_value(
+                object.get(#name).cloned().unwrap_or(#json::Value::Null),
+            )
+            .map_err(|e| #krate::Error::JsonRpc {
+                code: #krate::protocol::error_codes::INVALID_PARAMS,
+                message: format!("Invalid prompt parameter {}: {}", #name, e),
+            })?,
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics #krate::PromptParams for #ident #ty_generics #where_clause {
+            fn parameter_schema() -> #json::Value {
+                let mut properties = #json::Map::new();
+                #(#properties)*
+                #json::json!({
+                    "type": "object",
+                    "properties": properties,
+                    "required": [#(#required),*],
+                })
+            }
+
+            fn to_prompt_params(&self) -> #json::Value {
+                let mut params = #json::Map::new();
+                #(#serialize)*
+                #json::Value::Object(params)
+            }
+        }
+
+        impl #impl_generics ::core::convert::TryFrom<#json::Value> for #ident #ty_generics #where_clause {
+            type Error = #krate::Error;
+
+            fn try_from(value: #json::Value) -> #krate::Result<Self> {
+                <Self as #krate::PromptParams>::check(&value)?;
+                let object = value.as_object().cloned().unwrap_or_default();
+                Ok(Self {
+                    #(#deserialize)*
+                })
+            }
+        }
+    })
+}
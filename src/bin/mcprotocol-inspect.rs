@@ -0,0 +1,312 @@
+//! Connects to an MCP server and reports what it offers
+//!
+//! ```text
+//! mcprotocol-inspect [--transport http] --url URL [--token TOKEN] [--json]
+//! mcprotocol-inspect --transport stdio --server PATH [--json] [-- ARGS...]
+//! ```
+
+use colored::Colorize;
+use mcprotocol_rs::{
+    client::McpClient,
+    protocol::{ClientCapabilities, ImplementationInfo, InitializeResult, ServerCapabilities},
+    server_features::{ListToolsFilter, Prompt, Resource, Tool},
+    transport::{ClientTransportFactory, TransportConfig, TransportType},
+    Error, Result,
+};
+use serde_json::{json, Value};
+use tabled::{settings::Style, Table, Tabled};
+
+const USAGE: &str = "\
+Usage: mcprotocol-inspect [--transport http] --url URL [--token TOKEN] [--json]
+       mcprotocol-inspect --transport stdio --server PATH [--json] [-- ARGS...]";
+
+/// Command line options
+struct Options {
+    transport: TransportType,
+    json: bool,
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut transport = "http".to_string();
+        let mut url = None;
+        let mut token = None;
+        let mut server = None;
+        let mut server_args = Vec::new();
+        let mut json = false;
+
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| {
+                args.next()
+                    .ok_or_else(|| Error::Configuration(format!("{} needs a value", name)))
+            };
+            match arg.as_str() {
+                "--transport" => transport = value("--transport")?,
+                "--url" => url = Some(value("--url")?),
+                "--token" => token = Some(value("--token")?),
+                "--server" => server = Some(value("--server")?),
+                "--json" => json = true,
+                "--" => server_args.extend(args.by_ref()),
+                "-h" | "--help" => {
+                    println!("{}", USAGE);
+                    std::process::exit(0);
+                }
+                other => {
+                    return Err(Error::Configuration(format!(
+                        "Unknown argument: {}\n{}",
+                        other, USAGE
+                    )))
+                }
+            }
+        }
+
+        let transport = match transport.as_str() {
+            "http" => TransportType::Http {
+                base_url: url.ok_or_else(|| {
+                    Error::Configuration(format!("--url is required for http\n{}", USAGE))
+                })?,
+                auth_token: token,
+            },
+            "stdio" => TransportType::Stdio {
+                server_path: Some(server.ok_or_else(|| {
+                    Error::Configuration(format!("--server is required for stdio\n{}", USAGE))
+                })?),
+                server_args: Some(server_args),
+            },
+            other => {
+                return Err(Error::Configuration(format!(
+                    "Unknown transport: {}",
+                    other
+                )))
+            }
+        };
+        Ok(Self { transport, json })
+    }
+}
+
+/// Everything learned about the server
+///
+/// Listings the server does not support are `None`; listings that failed hold
+/// the error message.
+struct Report {
+    init: InitializeResult,
+    tools: Option<std::result::Result<Vec<Tool>, String>>,
+    resources: Option<std::result::Result<Vec<Resource>, String>>,
+    prompts: Option<std::result::Result<Vec<Prompt>, String>>,
+}
+
+impl Report {
+    async fn collect(client: &McpClient) -> Result<Self> {
+        let init = client
+            .initialize(
+                ImplementationInfo {
+                    name: "mcprotocol-inspect".to_string(),
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                },
+                ClientCapabilities::default(),
+            )
+            .await?;
+        let capabilities = &init.capabilities;
+
+        let mut tools = None;
+        if capabilities.supports_tools() {
+            tools = Some(client.list_tools(ListToolsFilter::default()).await);
+        }
+        let mut resources = None;
+        if capabilities.supports_resources() {
+            resources = Some(all_pages(|cursor| client.list_resources(cursor)).await);
+        }
+        let mut prompts = None;
+        if capabilities.supports_prompts() {
+            prompts = Some(all_pages(|cursor| client.list_prompts(cursor)).await);
+        }
+
+        Ok(Self {
+            init,
+            tools: tools.map(|r| r.map_err(|e| e.to_string())),
+            resources: resources.map(|r| r.map_err(|e| e.to_string())),
+            prompts: prompts.map(|r| r.map_err(|e| e.to_string())),
+        })
+    }
+
+    fn to_json(&self) -> Value {
+        fn listing<T: serde::Serialize>(
+            listing: &Option<std::result::Result<Vec<T>, String>>,
+        ) -> Value {
+            match listing {
+                None => Value::Null,
+                Some(Ok(items)) => json!(items),
+                Some(Err(error)) => json!({ "error": error }),
+            }
+        }
+
+        json!({
+            "serverInfo": self.init.server_info,
+            "protocolVersion": self.init.protocol_version,
+            "capabilities": self.init.capabilities,
+            "tools": listing(&self.tools),
+            "resources": listing(&self.resources),
+            "prompts": listing(&self.prompts),
+        })
+    }
+
+    fn print(&self) {
+        let info = &self.init.server_info;
+        println!(
+            "{} {} (protocol {})",
+            info.name.bold(),
+            info.version,
+            self.init.protocol_version
+        );
+
+        heading("Capabilities");
+        for (name, supported) in capability_flags(&self.init.capabilities) {
+            let mark = if supported { "yes".green() } else { "no".red() };
+            println!("  {:<16} {}", name, mark);
+        }
+
+        section("Tools", &self.tools, |tool: &Tool| ToolRow {
+            name: tool.name.clone(),
+            description: tool.description.clone(),
+            parameters: tool.parameters.to_string(),
+        });
+        section("Resources", &self.resources, |resource: &Resource| {
+            ResourceRow {
+                id: resource.id.clone(),
+                kind: resource.type_.clone(),
+                metadata: resource.metadata.to_string(),
+            }
+        });
+        section("Prompts", &self.prompts, |prompt: &Prompt| PromptRow {
+            name: prompt.name.clone(),
+            description: prompt.description.clone(),
+            arguments: prompt
+                .arguments
+                .iter()
+                .map(|argument| argument.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+        });
+    }
+}
+
+#[derive(Tabled)]
+struct ToolRow {
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Description")]
+    description: String,
+    #[tabled(rename = "Parameters")]
+    parameters: String,
+}
+
+#[derive(Tabled)]
+struct ResourceRow {
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "Type")]
+    kind: String,
+    #[tabled(rename = "Metadata")]
+    metadata: String,
+}
+
+#[derive(Tabled)]
+struct PromptRow {
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Description")]
+    description: String,
+    #[tabled(rename = "Arguments")]
+    arguments: String,
+}
+
+/// Every item of a paginated listing
+async fn all_pages<T, F, Fut>(mut page: F) -> Result<Vec<T>>
+where
+    F: FnMut(Option<String>) -> Fut,
+    Fut: std::future::Future<Output = Result<mcprotocol_rs::server_features::Page<T>>>,
+{
+    let mut items = Vec::new();
+    let mut cursor = None;
+    loop {
+        let next = page(cursor).await?;
+        items.extend(next.items);
+        match next.next_cursor {
+            Some(next_cursor) => cursor = Some(next_cursor),
+            None => return Ok(items),
+        }
+    }
+}
+
+fn capability_flags(capabilities: &ServerCapabilities) -> [(&'static str, bool); 7] {
+    [
+        ("prompts", capabilities.supports_prompts()),
+        ("resources", capabilities.supports_resources()),
+        (
+            "subscriptions",
+            capabilities.resources.as_ref().is_some_and(|r| r.subscribe),
+        ),
+        ("tools", capabilities.supports_tools()),
+        ("logging", capabilities.logging.is_some()),
+        ("tool streaming", capabilities.tool_streaming.is_some()),
+        ("experimental", capabilities.experimental.is_some()),
+    ]
+}
+
+fn heading(title: &str) {
+    println!();
+    println!("{}", title.bold().cyan());
+}
+
+fn section<T, R: Tabled>(
+    title: &str,
+    listing: &Option<std::result::Result<Vec<T>, String>>,
+    row: impl Fn(&T) -> R,
+) {
+    match listing {
+        None => {}
+        Some(Err(error)) => {
+            heading(title);
+            println!("  {}", error.red());
+        }
+        Some(Ok(items)) => {
+            heading(&format!("{} ({})", title, items.len()));
+            if !items.is_empty() {
+                let mut table = Table::new(items.iter().map(row));
+                table.with(Style::rounded());
+                println!("{}", table);
+            }
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    if let Err(e) = run().await {
+        eprintln!("{} {}", "error:".red().bold(), e);
+        std::process::exit(1);
+    }
+}
+
+async fn run() -> Result<()> {
+    let options = Options::parse(std::env::args().skip(1))?;
+    let transport = ClientTransportFactory.create(TransportConfig {
+        transport_type: options.transport,
+        parameters: None,
+    })?;
+    let mut client = McpClient::connect(transport).await?;
+
+    let report = Report::collect(&client).await;
+    // The report is printed even if the server does not shut down cleanly
+    if let Err(e) = client.shutdown().await {
+        eprintln!("{} shutdown failed: {}", "warning:".yellow().bold(), e);
+    }
+    let report = report?;
+
+    if options.json {
+        println!("{}", serde_json::to_string_pretty(&report.to_json())?);
+    } else {
+        report.print();
+    }
+    Ok(())
+}
@@ -0,0 +1,1411 @@
+use futures::{Stream, TryStreamExt};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, mpsc, oneshot};
+use tokio::task::JoinHandle;
+
+mod router;
+
+pub use router::{NotificationHistory, NotificationRouter, SequencedNotification};
+
+use crate::{
+    client_features::ClientConfig,
+    protocol::{
+        CapabilitiesChangedParams, ClientCapabilities, ImplementationInfo, InitializeParams,
+        InitializeResult, Message, Method, Notification, PrettyMessage, Request, RequestId,
+        Response, ServerCapabilities, PROTOCOL_VERSION,
+    },
+    server_features::{
+        content_bytes, BulkGetRequest, BulkGetResult, GetPromptResult, GetResourceResult,
+        ListResourceTemplatesResult, ListToolsFilter, Page, ProgressNotification, Prompt,
+        ReadResourcesRequest, ReadResourcesResult, Resource, ResourceOperation, ResourceStream,
+        ResourceTemplate, ResourceTransactionRequest, SchemaViolation, Tool, ToolResult,
+        ToolResultChunk, ValidateToolResult,
+    },
+    transport::Transport,
+    Error, Result,
+};
+
+/// Capacity of each resource subscription channel
+const SUBSCRIPTION_CAPACITY: usize = 16;
+
+/// Requests awaiting a response, keyed by request ID
+type PendingRequests = Arc<Mutex<HashMap<RequestId, oneshot::Sender<Response>>>>;
+
+/// Response awaited for a request that was sent
+struct PendingResponse {
+    id: RequestId,
+    receiver: oneshot::Receiver<Response>,
+}
+
+/// Active resource subscriptions, keyed by resource ID
+type Subscriptions = Arc<Mutex<HashMap<String, broadcast::Sender<Resource>>>>;
+
+/// Streamed tool executions in progress, keyed by request ID
+type ToolStreams = Arc<Mutex<HashMap<RequestId, mpsc::UnboundedSender<ToolResultChunk>>>>;
+
+/// Progress callbacks of requests in flight, keyed by request ID (the progress token)
+type ProgressCallbacks =
+    Arc<Mutex<HashMap<RequestId, Box<dyn Fn(ProgressNotification) + Send + 'static>>>>;
+
+/// High-level MCP client
+///
+/// Wraps a transport, correlates responses with the requests that produced them and
+/// routes incoming notifications to interested listeners.
+pub struct McpClient {
+    config: ClientConfig,
+    transport: Arc<dyn Transport>,
+    next_id: AtomicI64,
+    pending: PendingRequests,
+    subscriptions: Subscriptions,
+    tool_streams: ToolStreams,
+    progress_callbacks: ProgressCallbacks,
+    streaming_enabled: AtomicBool,
+    server_capabilities: Arc<Mutex<Option<ServerCapabilities>>>,
+    router: Arc<NotificationRouter>,
+    reader: JoinHandle<()>,
+}
+
+impl McpClient {
+    /// Initialize the transport and start processing incoming messages
+    pub async fn connect(transport: Box<dyn Transport>) -> Result<Self> {
+        Self::connect_with_router(transport, NotificationRouter::new()).await
+    }
+
+    /// Like `connect`, but deliver notifications through the given router
+    ///
+    /// Use a router created with `NotificationRouter::with_history` to let late
+    /// subscribers catch up on notifications received before they subscribed.
+    pub async fn connect_with_router(
+        transport: Box<dyn Transport>,
+        router: NotificationRouter,
+    ) -> Result<Self> {
+        Self::start(transport, router, ClientConfig::default()).await
+    }
+
+    /// Like `connect`, but with the given settings
+    pub async fn connect_with_config(
+        transport: Box<dyn Transport>,
+        config: ClientConfig,
+    ) -> Result<Self> {
+        Self::start(transport, NotificationRouter::new(), config).await
+    }
+
+    async fn start(
+        mut transport: Box<dyn Transport>,
+        router: NotificationRouter,
+        config: ClientConfig,
+    ) -> Result<Self> {
+        transport.initialize().await?;
+        let transport: Arc<dyn Transport> = Arc::from(transport);
+
+        let pending = PendingRequests::default();
+        let subscriptions = Subscriptions::default();
+        let tool_streams = ToolStreams::default();
+        let progress_callbacks = ProgressCallbacks::default();
+        let server_capabilities = Arc::new(Mutex::new(None));
+        let router = Arc::new(router);
+        let reader = tokio::spawn(Self::read_loop(
+            Arc::clone(&transport),
+            Arc::clone(&pending),
+            Listeners {
+                subscriptions: Arc::clone(&subscriptions),
+                tool_streams: Arc::clone(&tool_streams),
+                progress_callbacks: Arc::clone(&progress_callbacks),
+                server_capabilities: Arc::clone(&server_capabilities),
+            },
+            Arc::clone(&router),
+        ));
+
+        Ok(Self {
+            config,
+            transport,
+            next_id: AtomicI64::new(1),
+            pending,
+            subscriptions,
+            tool_streams,
+            progress_callbacks,
+            streaming_enabled: AtomicBool::new(false),
+            server_capabilities,
+            router,
+            reader,
+        })
+    }
+
+    /// Router delivering every notification received from the server
+    pub fn notifications(&self) -> &NotificationRouter {
+        &self.router
+    }
+
+    /// Perform the initialize handshake
+    ///
+    /// Sends `initialize`, validates the result (which must include `serverInfo`) and
+    /// then sends the `initialized` notification. Tool result streaming is enabled
+    /// when both sides advertise `toolStreaming`.
+    pub async fn initialize(
+        &self,
+        client_info: ImplementationInfo,
+        capabilities: ClientCapabilities,
+    ) -> Result<InitializeResult> {
+        let client_streams = capabilities.tool_streaming.is_some();
+        let params = InitializeParams {
+            protocol_version: PROTOCOL_VERSION.to_string(),
+            capabilities,
+            client_info,
+        };
+        let result = self
+            .request(Method::Initialize, Some(serde_json::to_value(params)?))
+            .await?;
+        let result: InitializeResult = serde_json::from_value(result)
+            .map_err(|e| Error::Protocol(format!("Invalid initialize result: {}", e)))?;
+        self.streaming_enabled.store(
+            client_streams && result.capabilities.tool_streaming.is_some(),
+            Ordering::SeqCst,
+        );
+        *self.server_capabilities.lock().unwrap() = Some(result.capabilities.clone());
+
+        self.notify(Method::Initialized, None).await?;
+        Ok(result)
+    }
+
+    /// Capabilities the server advertised during `initialize`
+    ///
+    /// `None` until the handshake has completed. Replaced by the capabilities in
+    /// each `notifications/capabilities/changed` from the server. Use the
+    /// `supports_*` methods to query individual features.
+    pub fn server_capabilities(&self) -> Option<ServerCapabilities> {
+        self.server_capabilities.lock().unwrap().clone()
+    }
+
+    /// Whether tool results are streamed, as negotiated by `initialize`
+    pub fn streaming_enabled(&self) -> bool {
+        self.streaming_enabled.load(Ordering::SeqCst)
+    }
+
+    /// Send a request and wait for its result
+    ///
+    /// Error responses are returned as `Error::JsonRpc`.
+    pub async fn request(&self, method: Method, params: Option<Value>) -> Result<Value> {
+        let response = self
+            .send_request(self.next_request_id(), method, params)
+            .await?;
+        Self::await_result(response).await
+    }
+
+    /// Send `ping` and measure the round trip
+    ///
+    /// Fails with `Error::JsonRpc` if the server answers with an error and with
+    /// `Error::Transport` if no answer arrives within `ClientConfig::ping_timeout`.
+    /// Several pings may be in flight at once; each uses its own request ID.
+    pub async fn ping(&self) -> Result<Duration> {
+        let id = self.next_request_id();
+        let started = Instant::now();
+        let response = self.send_request(id.clone(), Method::Ping, None).await?;
+
+        match tokio::time::timeout(self.config.ping_timeout, Self::await_result(response)).await {
+            Ok(result) => result.map(|_| started.elapsed()),
+            Err(_) => {
+                self.pending.lock().unwrap().remove(&id);
+                Err(Error::Transport(format!(
+                    "Ping timed out after {:?}",
+                    self.config.ping_timeout
+                )))
+            }
+        }
+    }
+
+    fn next_request_id(&self) -> RequestId {
+        RequestId::Number(self.next_id.fetch_add(1, Ordering::SeqCst))
+    }
+
+    /// Send a request, returning a handle to await its response
+    async fn send_request(
+        &self,
+        id: RequestId,
+        method: Method,
+        params: Option<Value>,
+    ) -> Result<PendingResponse> {
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().unwrap().insert(id.clone(), tx);
+
+        let message = Message::Request(Request::new(method, params, id.clone()));
+        tracing::trace!(message = %PrettyMessage(&message), "sending request");
+        if let Err(e) = self.transport.send(message).await {
+            self.pending.lock().unwrap().remove(&id);
+            return Err(e);
+        }
+        Ok(PendingResponse { id, receiver: rx })
+    }
+
+    /// Wait for a response and turn it into a result
+    ///
+    /// Fails with `Error::Protocol` if the response is malformed or answers a
+    /// different request. Error responses with a `null` ID, sent when the server
+    /// could not read the request, are returned as `Error::JsonRpc`.
+    async fn await_result(pending: PendingResponse) -> Result<Value> {
+        let response = pending
+            .receiver
+            .await
+            .map_err(|_| Error::Transport("Connection closed before response".into()))?;
+        response.validate()?;
+        if response.id != pending.id && !(response.id.is_null() && response.error.is_some()) {
+            return Err(Error::Protocol(format!(
+                "id mismatch: expected {:?}, got {:?}",
+                pending.id, response.id
+            )));
+        }
+
+        match response.error {
+            Some(error) => Err(Error::JsonRpc {
+                code: error.code,
+                message: error.message,
+            }),
+            None => Ok(response.result.unwrap_or(Value::Null)),
+        }
+    }
+
+    /// Send a notification
+    pub async fn notify(&self, method: Method, params: Option<Value>) -> Result<()> {
+        self.transport
+            .send(Message::Notification(Notification::new(method, params)))
+            .await
+    }
+
+    /// List the tools matching `filter` with `tools/list`, following every page
+    ///
+    /// The default filter lists every tool.
+    pub async fn list_tools(&self, filter: ListToolsFilter) -> Result<Vec<Tool>> {
+        self.paginate(Method::ListTools, "tools", json!({ "filter": filter }))
+            .try_collect()
+            .await
+    }
+
+    /// Fetch one page of the tools matching `filter`, starting at `cursor`
+    pub async fn list_tools_page(
+        &self,
+        filter: ListToolsFilter,
+        cursor: Option<String>,
+    ) -> Result<Page<Tool>> {
+        self.list_page(
+            Method::ListTools,
+            "tools",
+            json!({ "filter": filter }),
+            cursor,
+        )
+        .await
+    }
+
+    /// Fetch one page of resources with `resources/list`, starting at `cursor`
+    pub async fn list_resources(&self, cursor: Option<String>) -> Result<Page<Resource>> {
+        self.list_page(Method::ListResources, "resources", json!({}), cursor)
+            .await
+    }
+
+    /// Fetch one page of prompts with `prompts/list`, starting at `cursor`
+    pub async fn list_prompts(&self, cursor: Option<String>) -> Result<Page<Prompt>> {
+        self.list_page(Method::ListPrompts, "prompts", json!({}), cursor)
+            .await
+    }
+
+    /// Render a prompt into messages with `prompts/get`
+    pub async fn get_prompt(&self, id: &str, arguments: Option<Value>) -> Result<GetPromptResult> {
+        let mut params = json!({ "id": id });
+        if let Some(arguments) = arguments {
+            params["arguments"] = arguments;
+        }
+        let result = self.request(Method::GetPrompt, Some(params)).await?;
+        Ok(serde_json::from_value(result)?)
+    }
+
+    /// Add a prompt with `prompts/create`
+    pub async fn create_prompt(&self, prompt: Prompt) -> Result<()> {
+        self.request(Method::CreatePrompt, Some(json!({ "prompt": prompt })))
+            .await?;
+        Ok(())
+    }
+
+    /// Remove a prompt with `prompts/delete`
+    pub async fn delete_prompt(&self, id: &str) -> Result<()> {
+        self.request(Method::DeletePrompt, Some(json!({ "id": id })))
+            .await?;
+        Ok(())
+    }
+
+    /// Every tool, fetched page by page as the stream is consumed
+    pub fn list_all_tools_stream(&self) -> impl Stream<Item = Result<Tool>> + '_ {
+        self.paginate(Method::ListTools, "tools", json!({}))
+    }
+
+    /// Every resource, fetched page by page as the stream is consumed
+    ///
+    /// A page is only requested once every item of the previous one has been
+    /// taken; an error ends the stream.
+    pub fn list_all_resources_stream(&self) -> impl Stream<Item = Result<Resource>> + '_ {
+        self.paginate(Method::ListResources, "resources", json!({}))
+    }
+
+    /// Every prompt, fetched page by page as the stream is consumed
+    pub fn list_all_prompts_stream(&self) -> impl Stream<Item = Result<Prompt>> + '_ {
+        self.paginate(Method::ListPrompts, "prompts", json!({}))
+    }
+
+    /// Send a `*/list` request for the page at `cursor`, reading the items under `key`
+    async fn list_page<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        key: &str,
+        mut params: Value,
+        cursor: Option<String>,
+    ) -> Result<Page<T>> {
+        if let Some(cursor) = cursor {
+            params["cursor"] = Value::String(cursor);
+        }
+        let mut result = self.request(method.clone(), Some(params)).await?;
+        let items = result
+            .get_mut(key)
+            .map(Value::take)
+            .ok_or_else(|| Error::Protocol(format!("{} result has no {}", method, key)))?;
+        let next_cursor = match result.get("nextCursor") {
+            None | Some(Value::Null) => None,
+            Some(Value::String(cursor)) => Some(cursor.clone()),
+            Some(other) => {
+                return Err(Error::Protocol(format!("Invalid nextCursor: {}", other)));
+            }
+        };
+        Ok(Page {
+            items: serde_json::from_value(items)?,
+            next_cursor,
+        })
+    }
+
+    /// Lazily yield the items of every page of a `*/list` request
+    fn paginate<'a, T: DeserializeOwned + 'a>(
+        &'a self,
+        method: Method,
+        key: &'static str,
+        params: Value,
+    ) -> impl Stream<Item = Result<T>> + 'a {
+        async_stream::try_stream! {
+            let mut cursor = None;
+            loop {
+                let page: Page<T> = self
+                    .list_page(method.clone(), key, params.clone(), cursor)
+                    .await?;
+                for item in page.items {
+                    yield item;
+                }
+                cursor = match page.next_cursor {
+                    Some(next) => Some(next),
+                    None => break,
+                };
+            }
+        }
+    }
+
+    /// Fetch a single tool definition with `tools/get`
+    pub async fn get_tool(&self, id: &str) -> Result<Tool> {
+        let result = self
+            .request(Method::GetTool, Some(json!({ "id": id })))
+            .await?;
+        let tool = result
+            .get("tool")
+            .cloned()
+            .ok_or_else(|| Error::Protocol("tools/get result has no tool".into()))?;
+        Ok(serde_json::from_value(tool)?)
+    }
+
+    /// Check parameters for a tool with `tools/validate`, without executing it
+    ///
+    /// Returns the schema violations, which are empty when the parameters are valid.
+    pub async fn validate_tool(&self, id: &str, params: Value) -> Result<Vec<SchemaViolation>> {
+        let result = self
+            .request(
+                Method::ValidateTool,
+                Some(json!({ "id": id, "params": params })),
+            )
+            .await?;
+        let result: ValidateToolResult = serde_json::from_value(result)?;
+        Ok(result.violations)
+    }
+
+    /// Execute a tool with `tools/execute`
+    ///
+    /// A tool that ran but failed comes back as a result with `is_error` set, not
+    /// as an `Err`.
+    pub async fn execute_tool(&self, id: &str, params: Option<Value>) -> Result<ToolResult> {
+        let params = json!({ "id": id, "params": params.unwrap_or_else(|| json!({})) });
+        let result = self.request(Method::ExecuteTool, Some(params)).await?;
+        Ok(serde_json::from_value(result)?)
+    }
+
+    /// Execute a tool with `tools/execute/stream`, yielding its result chunk by chunk
+    ///
+    /// Fails with `Error::Protocol` unless streaming was negotiated by `initialize`;
+    /// check `streaming_enabled` and fall back to `execute_tool`.
+    pub async fn execute_tool_stream(
+        &self,
+        id: &str,
+        params: Option<Value>,
+    ) -> Result<impl Stream<Item = Result<ToolResultChunk>>> {
+        if !self.streaming_enabled() {
+            return Err(Error::Protocol("tool streaming was not negotiated".into()));
+        }
+        let request_id = self.next_request_id();
+        let (chunk_tx, mut chunks) = mpsc::unbounded_channel();
+        self.tool_streams
+            .lock()
+            .unwrap()
+            .insert(request_id.clone(), chunk_tx);
+
+        let params = json!({ "id": id, "params": params.unwrap_or_else(|| json!({})) });
+        let response = match self
+            .send_request(request_id.clone(), Method::ExecuteToolStream, Some(params))
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => {
+                self.tool_streams.lock().unwrap().remove(&request_id);
+                return Err(e);
+            }
+        };
+
+        Ok(async_stream::stream! {
+            // The sender is dropped once the final response arrives
+            while let Some(chunk) = chunks.recv().await {
+                yield Ok(chunk);
+            }
+            if let Err(e) = Self::await_result(response).await {
+                yield Err(e);
+            }
+        })
+    }
+
+    /// Execute a tool with `tools/execute`, reporting its progress to `on_progress`
+    ///
+    /// The request ID is sent as `_meta.progressToken`, and every `$/progress`
+    /// notification carrying that token is passed to `on_progress` until the
+    /// result arrives. Progress notifications arriving after the result are
+    /// ignored; they are still delivered through `notifications()`.
+    pub async fn execute_tool_with_progress(
+        &self,
+        id: &str,
+        params: Option<Value>,
+        on_progress: impl Fn(ProgressNotification) + Send + 'static,
+    ) -> Result<Value> {
+        let request_id = self.next_request_id();
+        self.progress_callbacks
+            .lock()
+            .unwrap()
+            .insert(request_id.clone(), Box::new(on_progress));
+
+        let params = json!({
+            "id": id,
+            "params": params.unwrap_or_else(|| json!({})),
+            "_meta": { "progressToken": request_id },
+        });
+        let result = match self
+            .send_request(request_id.clone(), Method::ExecuteTool, Some(params))
+            .await
+        {
+            Ok(response) => Self::await_result(response).await,
+            Err(e) => Err(e),
+        };
+        // The read loop deregisters on the response; this covers send failures
+        self.progress_callbacks.lock().unwrap().remove(&request_id);
+        result
+    }
+
+    /// List the URI templates the server supports with `resources/templates/list`
+    pub async fn list_resource_templates(&self) -> Result<Vec<ResourceTemplate>> {
+        let result = self.request(Method::ListResourceTemplates, None).await?;
+        let result: ListResourceTemplatesResult = serde_json::from_value(result)?;
+        Ok(result.templates)
+    }
+
+    /// Fetch several resources in one round trip with `resources/bulk_get`
+    ///
+    /// Resources whose entity tag still matches `if_none_match` come back without
+    /// their content; per-resource failures are reported in `BulkGetResult::errors`.
+    pub async fn bulk_get_resources(
+        &self,
+        ids: &[&str],
+        if_none_match: Option<HashMap<String, String>>,
+    ) -> Result<BulkGetResult> {
+        let params = BulkGetRequest {
+            ids: ids.iter().map(|id| id.to_string()).collect(),
+            if_none_match,
+        };
+        let result = self
+            .request(
+                Method::BulkGetResources,
+                Some(serde_json::to_value(params)?),
+            )
+            .await?;
+        Ok(serde_json::from_value(result)?)
+    }
+
+    /// Read the contents of several resources in one round trip with `resources/read`
+    ///
+    /// Per-resource failures are reported in `ReadResourcesResult::errors`.
+    pub async fn read_resources(&self, ids: &[String]) -> Result<ReadResourcesResult> {
+        let params = ReadResourcesRequest { ids: ids.to_vec() };
+        let result = self
+            .request(Method::ReadResources, Some(serde_json::to_value(params)?))
+            .await?;
+        Ok(serde_json::from_value(result)?)
+    }
+
+    /// Apply several resource changes as one unit with `resources/transaction`
+    ///
+    /// Fails with `METHOD_NOT_FOUND` unless the server advertises resource transactions.
+    pub async fn resource_transaction(&self, operations: Vec<ResourceOperation>) -> Result<()> {
+        let params = ResourceTransactionRequest { operations };
+        self.request(
+            Method::ResourceTransaction,
+            Some(serde_json::to_value(params)?),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Fetch a resource together with its text or binary contents
+    pub async fn get_resource(&self, id: &str) -> Result<GetResourceResult> {
+        let result = self
+            .request(Method::GetResource, Some(json!({ "id": id })))
+            .await?;
+        Ok(serde_json::from_value(result)?)
+    }
+
+    /// Read the content of a resource as an `AsyncRead`
+    ///
+    /// The content is fetched with `resources/get` and buffered, since responses
+    /// arrive as single messages; over HTTP, `HttpClient::stream_resource` streams
+    /// it instead. Binary contents are decoded from base64.
+    pub async fn stream_resource(&self, id: &str) -> Result<ResourceStream> {
+        let result = self.get_resource(id).await?;
+        let bytes = match result.contents.first() {
+            Some(contents) => contents.to_bytes()?,
+            None => content_bytes(result.resource.content.as_ref())?,
+        };
+        Ok(Box::pin(std::io::Cursor::new(bytes)))
+    }
+
+    /// Subscribe to updates of a resource
+    ///
+    /// The returned receiver yields the resource each time the server reports it changed.
+    pub async fn subscribe_resource(&self, id: &str) -> Result<broadcast::Receiver<Resource>> {
+        if let Some(sender) = self.subscriptions.lock().unwrap().get(id) {
+            return Ok(sender.subscribe());
+        }
+
+        self.request(Method::SubscribeResource, Some(json!({ "id": id })))
+            .await?;
+
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        let sender = subscriptions
+            .entry(id.to_string())
+            .or_insert_with(|| broadcast::channel(SUBSCRIPTION_CAPACITY).0);
+        Ok(sender.subscribe())
+    }
+
+    /// Unsubscribe from updates of a resource
+    ///
+    /// Receivers returned by `subscribe_resource` observe the channel closing.
+    pub async fn unsubscribe_resource(&self, id: &str) -> Result<()> {
+        self.request(Method::UnsubscribeResource, Some(json!({ "id": id })))
+            .await?;
+        self.subscriptions.lock().unwrap().remove(id);
+        Ok(())
+    }
+
+    /// Re-send subscribe requests for all active subscriptions
+    ///
+    /// Neither the client nor its transports reconnect on their own, so nothing
+    /// calls this automatically. After re-establishing a dropped connection, call
+    /// it yourself so the server resumes sending updates to existing receivers;
+    /// until then they stay open but receive nothing. Stops at the first subscription the server
+    /// rejects; the remaining ones are not re-sent.
+    pub async fn resubscribe_resources(&self) -> Result<()> {
+        let ids: Vec<String> = self.subscriptions.lock().unwrap().keys().cloned().collect();
+        for id in ids {
+            self.request(Method::SubscribeResource, Some(json!({ "id": id })))
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// End the session following the MCP lifecycle
+    ///
+    /// Sends `shutdown` and waits up to `ClientConfig::shutdown_timeout` for the
+    /// answer, then sends `exit` and closes the transport. `exit` is sent and the
+    /// transport closed even if the server fails or never answers; the first
+    /// error is returned, and a missing answer is an `Error::Transport`.
+    pub async fn shutdown(&mut self) -> Result<()> {
+        let id = self.next_request_id();
+        let answered = match self.send_request(id.clone(), Method::Shutdown, None).await {
+            Ok(response) => {
+                match tokio::time::timeout(
+                    self.config.shutdown_timeout,
+                    Self::await_result(response),
+                )
+                .await
+                {
+                    Ok(result) => result.map(|_| ()),
+                    Err(_) => {
+                        self.pending.lock().unwrap().remove(&id);
+                        Err(Error::Transport(format!(
+                            "Shutdown timed out after {:?}",
+                            self.config.shutdown_timeout
+                        )))
+                    }
+                }
+            }
+            Err(e) => Err(e),
+        };
+
+        let exited = self.notify(Method::Exit, None).await;
+        let closed = self.close().await;
+        answered.and(exited).and(closed)
+    }
+
+    /// Stop processing incoming messages and close the transport
+    pub async fn close(&mut self) -> Result<()> {
+        self.reader.abort();
+        let _ = (&mut self.reader).await;
+        self.pending.lock().unwrap().clear();
+        self.subscriptions.lock().unwrap().clear();
+        self.tool_streams.lock().unwrap().clear();
+        self.progress_callbacks.lock().unwrap().clear();
+
+        Arc::get_mut(&mut self.transport)
+            .ok_or_else(|| Error::Transport("Transport is still in use".into()))?
+            .close()
+            .await
+    }
+
+    /// Remove the request a response answers from the pending map
+    ///
+    /// Responses whose ID matches no pending request, such as a pong arriving after
+    /// its ping timed out, answer nothing and are dropped. The exception is an error
+    /// with a `null` ID, which the server sends when it could not read a request: it
+    /// goes to the only pending request, if there is exactly one, so its caller fails
+    /// instead of waiting forever.
+    fn take_pending(
+        pending: &PendingRequests,
+        response: &Response,
+    ) -> Option<oneshot::Sender<Response>> {
+        let mut pending = pending.lock().unwrap();
+        if let Some(tx) = pending.remove(&response.id) {
+            return Some(tx);
+        }
+        if !response.id.is_null() || response.error.is_none() || pending.len() != 1 {
+            return None;
+        }
+        let only = pending.keys().next().cloned()?;
+        pending.remove(&only)
+    }
+
+    /// Receive messages until the transport fails, dispatching each one
+    async fn read_loop(
+        transport: Arc<dyn Transport>,
+        pending: PendingRequests,
+        listeners: Listeners,
+        router: Arc<NotificationRouter>,
+    ) {
+        while let Ok(message) = transport.receive().await {
+            tracing::trace!(message = %PrettyMessage(&message), "received message");
+            // Batched responses and notifications are handled one by one
+            let messages = match message {
+                Message::Batch(batch) => batch,
+                message => vec![message],
+            };
+            for message in messages {
+                match message {
+                    Message::Response(response) => {
+                        // Deregister first so late notifications are not mistaken for
+                        // ones belonging to this request
+                        listeners.tool_streams.lock().unwrap().remove(&response.id);
+                        listeners
+                            .progress_callbacks
+                            .lock()
+                            .unwrap()
+                            .remove(&response.id);
+                        if let Some(tx) = Self::take_pending(&pending, &response) {
+                            let _ = tx.send(response);
+                        } else {
+                            tracing::warn!(id = ?response.id, "dropping response to unknown request");
+                        }
+                    }
+                    Message::Notification(notification) => {
+                        listeners.handle_notification(&notification);
+                        router.route(notification);
+                    }
+                    Message::Request(_) => {
+                        // Server-initiated requests are not handled by this client
+                    }
+                    Message::Batch(_) => {
+                        tracing::trace!("ignoring nested batch");
+                    }
+                }
+            }
+        }
+
+        // Wake up callers still waiting for a response
+        pending.lock().unwrap().clear();
+        listeners.tool_streams.lock().unwrap().clear();
+        listeners.progress_callbacks.lock().unwrap().clear();
+    }
+}
+
+/// Per-request and per-resource listeners fed by the read loop
+struct Listeners {
+    subscriptions: Subscriptions,
+    tool_streams: ToolStreams,
+    progress_callbacks: ProgressCallbacks,
+    server_capabilities: Arc<Mutex<Option<ServerCapabilities>>>,
+}
+
+impl Listeners {
+    /// Route a notification to its listeners
+    fn handle_notification(&self, notification: &Notification) {
+        if notification.method == Method::CapabilitiesChanged.to_string() {
+            let changed = notification.params.clone().and_then(|params| {
+                serde_json::from_value::<CapabilitiesChangedParams>(params).ok()
+            });
+            if let Some(changed) = changed {
+                *self.server_capabilities.lock().unwrap() = Some(changed.capabilities);
+            }
+        }
+
+        if notification.method == Method::Progress.to_string() {
+            let progress = notification
+                .params
+                .clone()
+                .and_then(|params| serde_json::from_value::<ProgressNotification>(params).ok());
+            if let Some(progress) = progress {
+                if let Some(callback) = self
+                    .progress_callbacks
+                    .lock()
+                    .unwrap()
+                    .get(&progress.progress_token)
+                {
+                    callback(progress);
+                }
+            }
+        }
+
+        if notification.method == Method::ToolResultChunk.to_string() {
+            let params = notification.params.as_ref();
+            let request_id = params
+                .and_then(|p| p.get("requestId"))
+                .and_then(|id| serde_json::from_value::<RequestId>(id.clone()).ok());
+            let chunk = params
+                .and_then(|p| p.get("chunk"))
+                .and_then(|chunk| serde_json::from_value::<ToolResultChunk>(chunk.clone()).ok());
+            if let (Some(request_id), Some(chunk)) = (request_id, chunk) {
+                if let Some(sender) = self.tool_streams.lock().unwrap().get(&request_id) {
+                    let _ = sender.send(chunk);
+                }
+            }
+        }
+
+        if notification.method == Method::ResourceUpdated.to_string() {
+            let resource = notification
+                .params
+                .clone()
+                .and_then(|params| serde_json::from_value::<Resource>(params).ok());
+            if let Some(resource) = resource {
+                if let Some(sender) = self.subscriptions.lock().unwrap().get(&resource.id) {
+                    let _ = sender.send(resource);
+                }
+            }
+        }
+    }
+}
+
+impl Drop for McpClient {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{error_codes, ResponseError, ToolStreamingCapability};
+    use crate::transport::MemoryTransport;
+
+    fn resource(id: &str, content: &str) -> Resource {
+        Resource {
+            id: id.to_string(),
+            type_: "file".to_string(),
+            metadata: json!({}),
+            content: Some(json!(content)),
+        }
+    }
+
+    /// Answer every request on the server side with an empty result
+    fn spawn_server(server: MemoryTransport) -> Arc<MemoryTransport> {
+        let server = Arc::new(server);
+        let responder = Arc::clone(&server);
+        tokio::spawn(async move {
+            while let Ok(Message::Request(request)) = responder.receive().await {
+                let response = Response::success(json!({}), request.id);
+                if responder.send(Message::Response(response)).await.is_err() {
+                    break;
+                }
+            }
+        });
+        server
+    }
+
+    fn updated(resource: &Resource) -> Message {
+        Message::Notification(Notification::new(
+            Method::ResourceUpdated,
+            Some(serde_json::to_value(resource).unwrap()),
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_request_returns_result() {
+        let (client, server) = MemoryTransport::pair();
+        let _server = spawn_server(server);
+        let client = McpClient::connect(Box::new(client)).await.unwrap();
+
+        let result = client.request(Method::Ping, None).await.unwrap();
+        assert_eq!(result, json!({}));
+    }
+
+    #[tokio::test]
+    async fn test_error_response_is_returned_as_error() {
+        let (client, server) = MemoryTransport::pair();
+        tokio::spawn(async move {
+            if let Ok(Message::Request(request)) = server.receive().await {
+                let error = crate::protocol::ResponseError {
+                    code: crate::error_codes::METHOD_NOT_FOUND,
+                    message: "Method not found".to_string(),
+                    data: None,
+                };
+                let _ = server
+                    .send(Message::Response(Response::error(error, request.id)))
+                    .await;
+            }
+        });
+        let client = McpClient::connect(Box::new(client)).await.unwrap();
+
+        let err = client.request(Method::ListTools, None).await.unwrap_err();
+        assert!(
+            matches!(err, Error::JsonRpc { code, .. } if code == crate::error_codes::METHOD_NOT_FOUND)
+        );
+    }
+
+    fn client_info() -> ImplementationInfo {
+        ImplementationInfo {
+            name: "TestClient".to_string(),
+            version: "1.0.0".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_malformed_responses_are_rejected() {
+        let (client, server) = MemoryTransport::pair();
+        let client = McpClient::connect(Box::new(client)).await.unwrap();
+
+        let request = tokio::spawn(async move {
+            let result = client.request(Method::ListTools, None).await;
+            (client, result)
+        });
+        let Ok(Message::Request(received)) = server.receive().await else {
+            panic!("expected request");
+        };
+        let mut response = Response::success(json!({}), received.id);
+        response.jsonrpc = "1.0".to_string();
+        server.send(Message::Response(response)).await.unwrap();
+        let (client, result) = request.await.unwrap();
+        assert!(matches!(result, Err(Error::Protocol(_))));
+
+        let request = tokio::spawn(async move { client.request(Method::ListTools, None).await });
+        let Ok(Message::Request(received)) = server.receive().await else {
+            panic!("expected request");
+        };
+        let mut response = Response::success(json!({}), received.id);
+        response.error = Some(ResponseError {
+            code: error_codes::INTERNAL_ERROR,
+            message: "and also failed".to_string(),
+            data: None,
+        });
+        server.send(Message::Response(response)).await.unwrap();
+        assert!(matches!(request.await.unwrap(), Err(Error::Protocol(_))));
+    }
+
+    #[tokio::test]
+    async fn test_mismatched_response_id_is_a_protocol_error() {
+        let (tx, receiver) = oneshot::channel();
+        let pending = PendingResponse {
+            id: RequestId::Number(1),
+            receiver,
+        };
+        tx.send(Response::success(json!({}), RequestId::Number(2)))
+            .unwrap();
+        let result = McpClient::await_result(pending).await;
+        assert!(matches!(result, Err(Error::Protocol(ref m)) if m.starts_with("id mismatch")));
+    }
+
+    #[tokio::test]
+    async fn test_unmatched_responses_are_dropped() {
+        let (client, server) = MemoryTransport::pair();
+        let client = McpClient::connect(Box::new(client)).await.unwrap();
+
+        let request = tokio::spawn(async move {
+            let result = client.request(Method::ListTools, None).await;
+            (client, result)
+        });
+        let Ok(Message::Request(received)) = server.receive().await else {
+            panic!("expected request");
+        };
+        // A late answer to some earlier request must not be taken for this one
+        let wrong_id = RequestId::String(format!("not-{:?}", received.id));
+        server
+            .send(Message::Response(Response::pong(wrong_id)))
+            .await
+            .unwrap();
+        server
+            .send(Message::Response(Response::success(
+                json!({ "tools": [] }),
+                received.id,
+            )))
+            .await
+            .unwrap();
+        let (client, result) = request.await.unwrap();
+        assert_eq!(result.unwrap(), json!({ "tools": [] }));
+        assert!(client.pending.lock().unwrap().is_empty());
+
+        // Errors the server could not attribute are surfaced as they are
+        let request = tokio::spawn(async move { client.request(Method::ListTools, None).await });
+        let Ok(Message::Request(_)) = server.receive().await else {
+            panic!("expected request");
+        };
+        let error = ResponseError {
+            code: error_codes::PARSE_ERROR,
+            message: "unreadable".to_string(),
+            data: None,
+        };
+        server
+            .send(Message::Response(Response::error(error, RequestId::Null)))
+            .await
+            .unwrap();
+        assert!(matches!(
+            request.await.unwrap(),
+            Err(Error::JsonRpc { code, .. }) if code == error_codes::PARSE_ERROR
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_ping_measures_round_trip() {
+        let (client, server) = MemoryTransport::pair();
+        let _server = spawn_server(server);
+        let client = McpClient::connect(Box::new(client)).await.unwrap();
+
+        let started = Instant::now();
+        let (first, second) = tokio::join!(client.ping(), client.ping());
+        let (first, second) = (first.unwrap(), second.unwrap());
+        assert!(first > Duration::ZERO && second > Duration::ZERO);
+        assert!(first <= started.elapsed() && second <= started.elapsed());
+        assert!(client.pending.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_sequence_stops_server() {
+        let (client, transport) = MemoryTransport::pair();
+        let server = crate::server::McpServer::builder()
+            .with_transport(Box::new(transport))
+            .build();
+        let server = tokio::spawn(server.run());
+
+        let mut client = McpClient::connect(Box::new(client)).await.unwrap();
+        client.shutdown().await.unwrap();
+        tokio::time::timeout(Duration::from_secs(5), server)
+            .await
+            .expect("server exits after `exit`")
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_exits_even_without_answer() {
+        let (client, server) = MemoryTransport::pair();
+        let mut client = McpClient::connect_with_config(
+            Box::new(client),
+            ClientConfig {
+                shutdown_timeout: Duration::from_millis(50),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let result = client.shutdown().await;
+        assert!(matches!(result, Err(Error::Transport(ref m)) if m.contains("timed out")));
+        let Ok(Message::Request(request)) = server.receive().await else {
+            panic!("expected shutdown");
+        };
+        assert_eq!(request.method, Method::Shutdown.to_string());
+        let Ok(Message::Notification(notification)) = server.receive().await else {
+            panic!("expected exit");
+        };
+        assert_eq!(notification.method, Method::Exit.to_string());
+        assert!(server.receive().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_ping_errors_and_timeouts() {
+        let (client, server) = MemoryTransport::pair();
+        let client = McpClient::connect_with_config(
+            Box::new(client),
+            ClientConfig {
+                ping_timeout: Duration::from_millis(50),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let ping = tokio::spawn(async move {
+            let result = client.ping().await;
+            (client, result)
+        });
+        let Ok(Message::Request(request)) = server.receive().await else {
+            panic!("expected ping");
+        };
+        let error = ResponseError {
+            code: error_codes::METHOD_NOT_FOUND,
+            message: "no ping here".to_string(),
+            data: None,
+        };
+        server
+            .send(Message::Response(Response::error(error, request.id)))
+            .await
+            .unwrap();
+        let (client, result) = ping.await.unwrap();
+        assert!(
+            matches!(result, Err(Error::JsonRpc { code, .. }) if code == error_codes::METHOD_NOT_FOUND)
+        );
+
+        // The server now stays silent
+        assert!(matches!(client.ping().await, Err(Error::Transport(_))));
+        assert!(client.pending.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_execute_tool_with_progress() {
+        let (client, server) = MemoryTransport::pair();
+        let server = tokio::spawn(async move {
+            let Ok(Message::Request(request)) = server.receive().await else {
+                panic!("expected tool execution");
+            };
+            let token = request.params.as_ref().unwrap()["_meta"]["progressToken"].clone();
+            let progress = |progress: u64| {
+                Message::Notification(Notification::new(
+                    Method::Progress,
+                    Some(json!({ "progressToken": token, "progress": progress, "total": 2 })),
+                ))
+            };
+            server.send(progress(1)).await.unwrap();
+            server.send(progress(2)).await.unwrap();
+            let response = Response::success(json!("done"), request.id);
+            server.send(Message::Response(response)).await.unwrap();
+            // Arrives after the result and must not reach the callback
+            server.send(progress(3)).await.unwrap();
+            server
+        });
+        let client = McpClient::connect(Box::new(client)).await.unwrap();
+        let mut routed = client.notifications().subscribe("$/progress");
+
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let recorder = Arc::clone(&reports);
+        let result = client
+            .execute_tool_with_progress("slow", None, move |progress| {
+                recorder.lock().unwrap().push(progress.progress);
+            })
+            .await
+            .unwrap();
+        assert_eq!(result, json!("done"));
+
+        for _ in 0..3 {
+            routed.recv().await.unwrap();
+        }
+        assert_eq!(*reports.lock().unwrap(), vec![1, 2]);
+        assert!(client.progress_callbacks.lock().unwrap().is_empty());
+        drop(server.await.unwrap());
+    }
+
+    /// Answer the initialize request with `result` and return the next message
+    async fn answer_initialize(server: MemoryTransport, result: Value) -> Option<Message> {
+        if let Ok(Message::Request(request)) = server.receive().await {
+            let response = Response::success(result, request.id);
+            server.send(Message::Response(response)).await.ok()?;
+        }
+        server.receive().await.ok()
+    }
+
+    #[tokio::test]
+    async fn test_initialize_sends_initialized_notification() {
+        let (client, server) = MemoryTransport::pair();
+        let server = tokio::spawn(answer_initialize(
+            server,
+            json!({
+                "protocolVersion": PROTOCOL_VERSION,
+                "capabilities": {},
+                "serverInfo": { "name": "TestServer", "version": "1.0.0" }
+            }),
+        ));
+        let client = McpClient::connect(Box::new(client)).await.unwrap();
+
+        let result = client
+            .initialize(client_info(), ClientCapabilities::default())
+            .await
+            .unwrap();
+        assert_eq!(result.server_info.name, "TestServer");
+        assert!(matches!(
+            server.await.unwrap(),
+            Some(Message::Notification(n)) if n.method == "initialized"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_server_capabilities_after_initialize() {
+        let (client, server) = MemoryTransport::pair();
+        let server = tokio::spawn(answer_initialize(
+            server,
+            json!({
+                "protocolVersion": PROTOCOL_VERSION,
+                "capabilities": { "resources": { "subscribe": true } },
+                "serverInfo": { "name": "TestServer", "version": "1.0.0" }
+            }),
+        ));
+        let client = McpClient::connect(Box::new(client)).await.unwrap();
+        assert!(client.server_capabilities().is_none());
+
+        client
+            .initialize(client_info(), ClientCapabilities::default())
+            .await
+            .unwrap();
+        server.await.unwrap();
+
+        let capabilities = client.server_capabilities().unwrap();
+        assert!(capabilities.supports_resource_subscribe());
+        assert!(!capabilities.supports_resources_list_changed());
+        assert!(!capabilities.supports_tools());
+    }
+
+    /// Negotiate streaming as requested, then answer one tool execution with two
+    /// text chunks when streaming and a failed result otherwise
+    ///
+    /// Returns the method of the execution request.
+    async fn answer_tool_execution(server: MemoryTransport, server_streams: bool) -> String {
+        let Ok(Message::Request(request)) = server.receive().await else {
+            panic!("expected initialize");
+        };
+        let capabilities = if server_streams {
+            json!({ "toolStreaming": { "formats": ["text"] } })
+        } else {
+            json!({})
+        };
+        let result = json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": capabilities,
+            "serverInfo": { "name": "TestServer", "version": "1.0.0" }
+        });
+        let response = Response::success(result, request.id);
+        server.send(Message::Response(response)).await.unwrap();
+        server.receive().await.unwrap(); // initialized
+
+        let Ok(Message::Request(request)) = server.receive().await else {
+            panic!("expected tool execution");
+        };
+        let result = if request.method == Method::ExecuteToolStream.to_string() {
+            for text in ["Hello, ", "world"] {
+                let chunk = json!({
+                    "requestId": request.id,
+                    "chunk": { "format": "text", "data": text }
+                });
+                let notification = Notification::new(Method::ToolResultChunk, Some(chunk));
+                server
+                    .send(Message::Notification(notification))
+                    .await
+                    .unwrap();
+            }
+            ToolResult::text("Hello, world")
+        } else {
+            ToolResult::error("greeting failed")
+        };
+        let response = Response::success(serde_json::to_value(result).unwrap(), request.id);
+        server.send(Message::Response(response)).await.unwrap();
+        request.method
+    }
+
+    async fn client_with_streaming(
+        client_streams: bool,
+        server_streams: bool,
+    ) -> (McpClient, JoinHandle<String>) {
+        let (client, server) = MemoryTransport::pair();
+        let server = tokio::spawn(answer_tool_execution(server, server_streams));
+        let client = McpClient::connect(Box::new(client)).await.unwrap();
+        let capabilities = ClientCapabilities {
+            tool_streaming: client_streams.then(|| ToolStreamingCapability {
+                formats: vec!["text".to_string()],
+            }),
+            ..Default::default()
+        };
+        client
+            .initialize(client_info(), capabilities)
+            .await
+            .unwrap();
+        assert_eq!(client.streaming_enabled(), client_streams && server_streams);
+        (client, server)
+    }
+
+    #[tokio::test]
+    async fn test_execute_tool_stream_yields_chunks_when_negotiated() {
+        use futures::StreamExt;
+
+        let (client, server) = client_with_streaming(true, true).await;
+        let chunks: Vec<Value> = client
+            .execute_tool_stream("greet", None)
+            .await
+            .unwrap()
+            .map(|chunk| chunk.unwrap())
+            .map(|chunk| json!({ "format": chunk.format, "data": chunk.data }))
+            .collect()
+            .await;
+        assert_eq!(server.await.unwrap(), "tools/execute/stream");
+        assert_eq!(
+            chunks,
+            vec![
+                json!({ "format": "text", "data": "Hello, " }),
+                json!({ "format": "text", "data": "world" }),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_tool_returns_typed_result_without_streaming() {
+        for (client_streams, server_streams) in [(true, false), (false, true)] {
+            let (client, server) = client_with_streaming(client_streams, server_streams).await;
+            assert!(matches!(
+                client.execute_tool_stream("greet", None).await,
+                Err(Error::Protocol(_))
+            ));
+
+            let result = client.execute_tool("greet", None).await.unwrap();
+            assert_eq!(server.await.unwrap(), "tools/execute");
+            assert_eq!(result, ToolResult::error("greeting failed"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_initialize_without_server_info_fails() {
+        let (client, server) = MemoryTransport::pair();
+        tokio::spawn(answer_initialize(
+            server,
+            json!({ "protocolVersion": PROTOCOL_VERSION, "capabilities": {} }),
+        ));
+        let client = McpClient::connect(Box::new(client)).await.unwrap();
+
+        let err = client
+            .initialize(client_info(), ClientCapabilities::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Protocol(_)));
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_receives_updates_for_subscribed_resource() {
+        let (client, server) = MemoryTransport::pair();
+        let server = spawn_server(server);
+        let client = McpClient::connect(Box::new(client)).await.unwrap();
+
+        let mut updates = client.subscribe_resource("a").await.unwrap();
+
+        server
+            .send(updated(&resource("b", "ignored")))
+            .await
+            .unwrap();
+        server.send(updated(&resource("a", "new"))).await.unwrap();
+
+        let update = updates.recv().await.unwrap();
+        assert_eq!(update.id, "a");
+        assert_eq!(update.content, Some(json!("new")));
+    }
+
+    #[tokio::test]
+    async fn test_notifications_are_routed_with_history() {
+        let (client, server) = MemoryTransport::pair();
+        let router = NotificationRouter::with_history(NotificationHistory { capacity: 8 });
+        let client = McpClient::connect_with_router(Box::new(client), router)
+            .await
+            .unwrap();
+
+        server.send(updated(&resource("a", "v1"))).await.unwrap();
+        let (_rx, history) = loop {
+            let (rx, history) = client
+                .notifications()
+                .subscribe_with_history("notifications/resources/updated", None);
+            if !history.is_empty() {
+                break (rx, history);
+            }
+            tokio::task::yield_now().await;
+        };
+        assert_eq!(history[0].sequence, 1);
+    }
+
+    #[tokio::test]
+    async fn test_unsubscribe_closes_receiver() {
+        let (client, server) = MemoryTransport::pair();
+        let _server = spawn_server(server);
+        let client = McpClient::connect(Box::new(client)).await.unwrap();
+
+        let mut updates = client.subscribe_resource("a").await.unwrap();
+        client.unsubscribe_resource("a").await.unwrap();
+
+        assert!(matches!(
+            updates.recv().await,
+            Err(broadcast::error::RecvError::Closed)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_stream_resource_reads_content() {
+        use tokio::io::AsyncReadExt;
+
+        let (client, server) = MemoryTransport::pair();
+        tokio::spawn(async move {
+            if let Ok(Message::Request(request)) = server.receive().await {
+                let result = serde_json::to_value(resource("notes", "hello")).unwrap();
+                let _ = server
+                    .send(Message::Response(Response::success(result, request.id)))
+                    .await;
+            }
+        });
+        let client = McpClient::connect(Box::new(client)).await.unwrap();
+
+        let mut stream = client.stream_resource("notes").await.unwrap();
+        let mut content = String::new();
+        stream.read_to_string(&mut content).await.unwrap();
+        assert_eq!(content, "hello");
+    }
+
+    #[tokio::test]
+    async fn test_close_closes_transport() {
+        let (client, server) = MemoryTransport::pair();
+        let server = spawn_server(server);
+        let mut client = McpClient::connect(Box::new(client)).await.unwrap();
+
+        client.close().await.unwrap();
+        assert!(server.receive().await.is_err());
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+
+use crate::protocol::Notification;
+
+/// Capacity of each per-method broadcast channel
+const CHANNEL_CAPACITY: usize = 64;
+
+/// Notification tagged with its position in the order notifications were routed
+#[derive(Debug, Clone)]
+pub struct SequencedNotification {
+    /// Monotonically increasing sequence number, starting at 1
+    pub sequence: u64,
+    /// The notification itself
+    pub notification: Notification,
+}
+
+/// History retention settings for a `NotificationRouter`
+#[derive(Debug, Clone, Copy)]
+pub struct NotificationHistory {
+    /// Number of notifications kept per method
+    pub capacity: usize,
+}
+
+#[derive(Default)]
+struct RouterState {
+    next_sequence: u64,
+    channels: HashMap<String, broadcast::Sender<SequencedNotification>>,
+    history: HashMap<String, VecDeque<SequencedNotification>>,
+}
+
+/// Fans incoming notifications out to subscribers by method
+///
+/// With a `NotificationHistory` configured, the router also keeps the most recent
+/// notifications of each method so that late subscribers can catch up.
+#[derive(Default)]
+pub struct NotificationRouter {
+    history: Option<NotificationHistory>,
+    state: Mutex<RouterState>,
+}
+
+impl NotificationRouter {
+    /// Create a router that keeps no history
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a router that keeps the last `history.capacity` notifications per method
+    pub fn with_history(history: NotificationHistory) -> Self {
+        Self {
+            history: Some(history),
+            state: Mutex::default(),
+        }
+    }
+
+    /// Subscribe to live notifications of a method
+    pub fn subscribe(&self, method: &str) -> broadcast::Receiver<SequencedNotification> {
+        Self::channel(&mut self.state.lock().unwrap(), method).subscribe()
+    }
+
+    /// Subscribe to a method and fetch the stored history
+    ///
+    /// Returns the stored notifications with a sequence greater than `from_sequence`
+    /// (all stored notifications when `None`), followed on the receiver by every
+    /// notification routed afterwards, without gaps or duplicates.
+    pub fn subscribe_with_history(
+        &self,
+        method: &str,
+        from_sequence: Option<u64>,
+    ) -> (
+        broadcast::Receiver<SequencedNotification>,
+        Vec<SequencedNotification>,
+    ) {
+        let mut state = self.state.lock().unwrap();
+        let history = state
+            .history
+            .get(method)
+            .map(|stored| {
+                stored
+                    .iter()
+                    .filter(|n| from_sequence.is_none_or(|from| n.sequence > from))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+        (Self::channel(&mut state, method).subscribe(), history)
+    }
+
+    /// Deliver a notification to subscribers of its method, returning its sequence number
+    pub fn route(&self, notification: Notification) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        state.next_sequence += 1;
+        let sequenced = SequencedNotification {
+            sequence: state.next_sequence,
+            notification,
+        };
+        let method = sequenced.notification.method.clone();
+
+        if let Some(history) = self.history {
+            let stored = state.history.entry(method.clone()).or_default();
+            stored.push_back(sequenced.clone());
+            while stored.len() > history.capacity {
+                stored.pop_front();
+            }
+        }
+
+        if let Some(sender) = state.channels.get(&method) {
+            let _ = sender.send(sequenced.clone());
+        }
+        sequenced.sequence
+    }
+
+    fn channel<'a>(
+        state: &'a mut RouterState,
+        method: &str,
+    ) -> &'a broadcast::Sender<SequencedNotification> {
+        state
+            .channels
+            .entry(method.to_string())
+            .or_insert_with(|| broadcast::channel(CHANNEL_CAPACITY).0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Method;
+    use serde_json::json;
+
+    fn updated(n: u64) -> Notification {
+        Notification::new(Method::ResourceUpdated, Some(json!({ "n": n })))
+    }
+
+    const UPDATED: &str = "notifications/resources/updated";
+
+    #[tokio::test]
+    async fn test_live_subscriber_receives_sequenced_notifications() {
+        let router = NotificationRouter::new();
+        let mut rx = router.subscribe(UPDATED);
+
+        router.route(Notification::new(Method::Initialized, None));
+        router.route(updated(1));
+
+        let received = rx.recv().await.unwrap();
+        assert_eq!(received.sequence, 2);
+        assert_eq!(received.notification.params, Some(json!({ "n": 1 })));
+    }
+
+    #[test]
+    fn test_history_is_bounded_per_method() {
+        let router = NotificationRouter::with_history(NotificationHistory { capacity: 2 });
+        for n in 1..=3 {
+            router.route(updated(n));
+        }
+        router.route(Notification::new(Method::Initialized, None));
+
+        let (_rx, history) = router.subscribe_with_history(UPDATED, None);
+        let sequences: Vec<u64> = history.iter().map(|n| n.sequence).collect();
+        assert_eq!(sequences, vec![2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_late_subscriber_resumes_from_sequence() {
+        let router = NotificationRouter::with_history(NotificationHistory { capacity: 10 });
+        let first = router.route(updated(1));
+        router.route(updated(2));
+
+        let (mut rx, history) = router.subscribe_with_history(UPDATED, Some(first));
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].sequence, first + 1);
+
+        router.route(updated(3));
+        assert_eq!(rx.recv().await.unwrap().sequence, first + 2);
+    }
+
+    #[test]
+    fn test_no_history_without_configuration() {
+        let router = NotificationRouter::new();
+        router.route(updated(1));
+
+        let (_rx, history) = router.subscribe_with_history(UPDATED, None);
+        assert!(history.is_empty());
+    }
+}
@@ -1,5 +1,6 @@
 use async_trait::async_trait;
 use serde_json::Value;
+use std::time::Duration;
 
 use crate::Result;
 
@@ -12,6 +13,22 @@ pub struct ClientConfig {
     pub version: String,
     /// Root directories for context
     pub roots: Vec<String>,
+    /// How long `McpClient::ping` waits for the server's answer
+    pub ping_timeout: Duration,
+    /// How long `McpClient::shutdown` waits for the answer to `shutdown`
+    pub shutdown_timeout: Duration,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            name: env!("CARGO_PKG_NAME").to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            roots: Vec::new(),
+            ping_timeout: Duration::from_secs(10),
+            shutdown_timeout: Duration::from_secs(5),
+        }
+    }
 }
 
 /// Represents an MCP client
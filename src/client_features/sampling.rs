@@ -1,7 +1,8 @@
 use async_trait::async_trait;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
 
+use crate::server_features::Content;
 use crate::Result;
 
 /// Represents a sampling request from the server
@@ -13,6 +14,105 @@ pub struct SamplingRequest {
     pub parameters: Option<Value>,
     /// Optional stop sequences
     pub stop: Option<Vec<String>>,
+    /// Optional preferences for choosing the model
+    #[serde(
+        rename = "modelPreferences",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub model_preferences: Option<ModelPreferences>,
+}
+
+/// A suggested model name
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelHint {
+    /// Full or partial model name, e.g. `claude-3` or `sonnet`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// The server's preferences for model selection
+///
+/// Priorities range from `0.0` (not important) to `1.0` (most important);
+/// values outside that range are rejected when deserializing.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelPreferences {
+    /// Model names to consider, in order of preference
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hints: Vec<ModelHint>,
+    /// How much to prioritize a low cost
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_priority"
+    )]
+    pub cost_priority: Option<f64>,
+    /// How much to prioritize a fast response
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_priority"
+    )]
+    pub speed_priority: Option<f64>,
+    /// How much to prioritize model capability
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_priority"
+    )]
+    pub intelligence_priority: Option<f64>,
+}
+
+fn deserialize_priority<'de, D>(deserializer: D) -> std::result::Result<Option<f64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let priority = Option::<f64>::deserialize(deserializer)?;
+    match priority {
+        Some(value) if !(0.0..=1.0).contains(&value) => Err(serde::de::Error::custom(format!(
+            "priority {} is outside 0.0..=1.0",
+            value
+        ))),
+        _ => Ok(priority),
+    }
+}
+
+/// Author of a `SamplingMessage`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageRole {
+    /// The user, i.e. input to the model
+    User,
+    /// The model
+    Assistant,
+}
+
+/// A role-tagged message, as used in sampling and returned by `prompts/get`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SamplingMessage {
+    /// Who the message is from
+    pub role: MessageRole,
+    /// What the message says
+    pub content: Content,
+}
+
+impl SamplingMessage {
+    /// A text message from the user
+    pub fn user(text: impl Into<String>) -> Self {
+        Self {
+            role: MessageRole::User,
+            content: Content::text(text),
+        }
+    }
+
+    /// A text message from the assistant
+    pub fn assistant(text: impl Into<String>) -> Self {
+        Self {
+            role: MessageRole::Assistant,
+            content: Content::text(text),
+        }
+    }
 }
 
 /// Represents a sampling response to the server
@@ -33,3 +133,64 @@ pub trait SamplingHandler: Send + Sync {
     /// Cancels an ongoing sampling operation
     async fn cancel(&self) -> Result<()>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_model_preferences_round_trip() {
+        let request: SamplingRequest = serde_json::from_value(json!({
+            "prompt": "Hello",
+            "parameters": null,
+            "stop": null,
+            "modelPreferences": {
+                "hints": [{ "name": "claude-3" }],
+                "costPriority": 0.2,
+                "intelligencePriority": 1.0
+            }
+        }))
+        .unwrap();
+
+        let preferences = request.model_preferences.clone().unwrap();
+        assert_eq!(preferences.hints[0].name.as_deref(), Some("claude-3"));
+        assert_eq!(preferences.cost_priority, Some(0.2));
+        assert_eq!(preferences.speed_priority, None);
+        assert_eq!(preferences.intelligence_priority, Some(1.0));
+
+        let value = serde_json::to_value(&request).unwrap();
+        assert_eq!(
+            value["modelPreferences"],
+            json!({
+                "hints": [{ "name": "claude-3" }],
+                "costPriority": 0.2,
+                "intelligencePriority": 1.0
+            })
+        );
+    }
+
+    #[test]
+    fn test_model_preferences_are_optional() {
+        let request: SamplingRequest =
+            serde_json::from_value(json!({ "prompt": "Hello", "parameters": null, "stop": null }))
+                .unwrap();
+        assert!(request.model_preferences.is_none());
+        assert!(serde_json::to_value(&request)
+            .unwrap()
+            .get("modelPreferences")
+            .is_none());
+    }
+
+    #[test]
+    fn test_out_of_range_priority_is_rejected() {
+        for preferences in [
+            json!({ "costPriority": 1.5 }),
+            json!({ "speedPriority": -0.1 }),
+            json!({ "intelligencePriority": 2 }),
+        ] {
+            let err = serde_json::from_value::<ModelPreferences>(preferences).unwrap_err();
+            assert!(err.to_string().contains("outside 0.0..=1.0"));
+        }
+    }
+}
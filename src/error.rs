@@ -11,6 +11,10 @@ pub enum Error {
     #[error("Transport error: {0}")]
     Transport(String),
 
+    /// A malformed message was skipped; the transport can keep being used
+    #[error("Parse error: {0}")]
+    Parse(String),
+
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
 
@@ -19,4 +23,55 @@ pub enum Error {
 
     #[error("HTTP error: {0}")]
     Http(#[from] reqwest::Error),
+
+    #[error("Configuration error: {0}")]
+    Configuration(String),
+
+    /// A transport was used before `initialize` succeeded
+    #[error("Transport not initialized")]
+    NotInitialized,
+}
+
+impl From<std::env::VarError> for Error {
+    fn from(error: std::env::VarError) -> Self {
+        Error::Protocol(error.to_string())
+    }
+}
+
+impl From<std::num::ParseIntError> for Error {
+    fn from(error: std::num::ParseIntError) -> Self {
+        Error::Protocol(error.to_string())
+    }
+}
+
+impl From<Error> for crate::protocol::ResponseError {
+    /// Converts an error into a JSON-RPC error object, keeping the code of
+    /// `Error::JsonRpc`, reporting `Error::Parse` as a parse error and everything
+    /// else as an internal error
+    fn from(error: Error) -> Self {
+        Self::from(&error)
+    }
+}
+
+impl From<&Error> for crate::protocol::ResponseError {
+    /// See `From<Error>`
+    fn from(error: &Error) -> Self {
+        match error {
+            Error::JsonRpc { code, message } => Self {
+                code: *code,
+                message: message.clone(),
+                data: None,
+            },
+            Error::Parse(message) => Self {
+                code: crate::protocol::error_codes::PARSE_ERROR,
+                message: message.clone(),
+                data: None,
+            },
+            other => Self {
+                code: crate::protocol::error_codes::INTERNAL_ERROR,
+                message: other.to_string(),
+                data: None,
+            },
+        }
+    }
 }
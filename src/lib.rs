@@ -1,14 +1,31 @@
+// Lets derive macros refer to `::mcprotocol_rs` from inside this crate too
+extern crate self as mcprotocol_rs;
+
+pub mod client;
 pub mod client_features;
 pub mod error;
 pub mod protocol;
+pub mod server;
 pub mod server_features;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod transport;
 
+pub use client::*;
 pub use client_features::*;
 pub use error::Error;
 pub use protocol::*;
+pub use server::*;
 pub use server_features::*;
 pub use transport::*;
 
+#[cfg(feature = "macros")]
+pub use mcprotocol_rs_macros::PromptParams;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use serde_json;
+}
+
 /// Result type for MCP operations
 pub type Result<T> = std::result::Result<T, Error>;
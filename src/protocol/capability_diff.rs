@@ -0,0 +1,157 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use super::{FeatureCapability, ResourceCapability, ServerCapabilities};
+
+/// Server feature whose capability can be advertised or withdrawn as a whole
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Capability {
+    /// `prompts/*` methods
+    Prompts,
+    /// `resources/*` methods
+    Resources,
+    /// `tools/*` methods
+    Tools,
+    /// `logging/*` methods
+    Logging,
+}
+
+impl Capability {
+    /// Every capability, in a fixed order
+    pub const ALL: [Capability; 4] = [
+        Capability::Prompts,
+        Capability::Resources,
+        Capability::Tools,
+        Capability::Logging,
+    ];
+
+    /// Whether `capabilities` advertises this capability
+    pub fn is_enabled(self, capabilities: &ServerCapabilities) -> bool {
+        match self {
+            Capability::Prompts => capabilities.prompts.is_some(),
+            Capability::Resources => capabilities.resources.is_some(),
+            Capability::Tools => capabilities.tools.is_some(),
+            Capability::Logging => capabilities.logging.is_some(),
+        }
+    }
+
+    /// Advertise this capability, without optional sub-capabilities, unless it
+    /// already is
+    pub fn enable(self, capabilities: &mut ServerCapabilities) {
+        let feature = FeatureCapability {
+            list_changed: false,
+        };
+        match self {
+            Capability::Prompts => {
+                capabilities.prompts.get_or_insert(feature);
+            }
+            Capability::Resources => {
+                capabilities.resources.get_or_insert(ResourceCapability {
+                    subscribe: false,
+                    list_changed: false,
+                    templates: false,
+                    bulk_get: false,
+                    transactions: false,
+                });
+            }
+            Capability::Tools => {
+                capabilities.tools.get_or_insert(feature);
+            }
+            Capability::Logging => {
+                capabilities.logging.get_or_insert_with(|| json!({}));
+            }
+        }
+    }
+
+    /// Stop advertising this capability
+    pub fn disable(self, capabilities: &mut ServerCapabilities) {
+        match self {
+            Capability::Prompts => capabilities.prompts = None,
+            Capability::Resources => capabilities.resources = None,
+            Capability::Tools => capabilities.tools = None,
+            Capability::Logging => capabilities.logging = None,
+        }
+    }
+
+    /// Whether `method` belongs to this capability
+    pub fn covers(self, method: &str) -> bool {
+        let prefix = match self {
+            Capability::Prompts => "prompts/",
+            Capability::Resources => "resources/",
+            Capability::Tools => "tools/",
+            Capability::Logging => "logging/",
+        };
+        method.starts_with(prefix)
+    }
+}
+
+/// Capabilities gained and lost between two `ServerCapabilities`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapabilityDiff {
+    /// Capabilities advertised only by the new set
+    #[serde(default)]
+    pub added: Vec<Capability>,
+    /// Capabilities advertised only by the old set
+    #[serde(default)]
+    pub removed: Vec<Capability>,
+}
+
+impl CapabilityDiff {
+    /// Compare the capabilities advertised by `old` and `new`
+    pub fn compute(old: &ServerCapabilities, new: &ServerCapabilities) -> Self {
+        let mut diff = Self::default();
+        for capability in Capability::ALL {
+            match (capability.is_enabled(old), capability.is_enabled(new)) {
+                (false, true) => diff.added.push(capability),
+                (true, false) => diff.removed.push(capability),
+                _ => {}
+            }
+        }
+        diff
+    }
+
+    /// Whether nothing was added or removed
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Parameters of `notifications/capabilities/changed`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapabilitiesChangedParams {
+    /// What changed
+    #[serde(flatten)]
+    pub diff: CapabilityDiff,
+    /// The capabilities now advertised, replacing those from `initialize`
+    pub capabilities: ServerCapabilities,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_diff() {
+        let mut old = ServerCapabilities::default();
+        Capability::Tools.enable(&mut old);
+        Capability::Prompts.enable(&mut old);
+        let mut new = old.clone();
+        Capability::Tools.disable(&mut new);
+        Capability::Logging.enable(&mut new);
+
+        let diff = CapabilityDiff::compute(&old, &new);
+        assert_eq!(diff.added, [Capability::Logging]);
+        assert_eq!(diff.removed, [Capability::Tools]);
+        assert!(CapabilityDiff::compute(&new, &new).is_empty());
+
+        let params = CapabilitiesChangedParams {
+            diff,
+            capabilities: new,
+        };
+        let value = serde_json::to_value(&params).unwrap();
+        assert_eq!(value["added"], json!(["logging"]));
+        assert_eq!(value["removed"], json!(["tools"]));
+        assert_eq!(value["capabilities"]["logging"], json!({}));
+    }
+}
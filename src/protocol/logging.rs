@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{Method, Notification};
+use crate::Result;
+
+/// Severity of a log message, as defined by RFC 5424
+///
+/// Levels are ordered from least to most severe, so `LogLevel::Warning <
+/// LogLevel::Error`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    /// Detailed debugging information
+    Debug,
+    /// Informational messages
+    Info,
+    /// Normal but significant events
+    Notice,
+    /// Warning conditions
+    Warning,
+    /// Error conditions
+    Error,
+    /// Critical conditions
+    Critical,
+    /// Action must be taken immediately
+    Alert,
+    /// The system is unusable
+    Emergency,
+}
+
+/// Parameters of `logging/setLevel`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SetLevelParams {
+    /// Least severe level the client wants to receive
+    pub level: LogLevel,
+}
+
+/// Parameters of `notifications/message`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoggingMessageParams {
+    /// Severity of the message
+    pub level: LogLevel,
+    /// Name of the component that logged the message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logger: Option<String>,
+    /// The message itself, any JSON value
+    pub data: Value,
+}
+
+impl LoggingMessageParams {
+    /// `notifications/message` carrying these parameters
+    pub fn to_notification(&self) -> Result<Notification> {
+        Ok(Notification::new(
+            Method::LogMessage,
+            Some(serde_json::to_value(self)?),
+        ))
+    }
+
+    /// Level of a `notifications/message`, or `None` for any other notification
+    pub fn level_of(notification: &Notification) -> Option<LogLevel> {
+        if notification.method != Method::LogMessage.to_string() {
+            return None;
+        }
+        serde_json::from_value(notification.params.as_ref()?.get("level")?.clone()).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_levels_are_ordered_by_severity() {
+        assert!(LogLevel::Debug < LogLevel::Info);
+        assert!(LogLevel::Warning < LogLevel::Error);
+        assert!(LogLevel::Alert < LogLevel::Emergency);
+        assert_eq!(
+            serde_json::to_value(SetLevelParams {
+                level: LogLevel::Warning
+            })
+            .unwrap(),
+            json!({ "level": "warning" })
+        );
+    }
+
+    #[test]
+    fn test_message_notification() {
+        let params = LoggingMessageParams {
+            level: LogLevel::Error,
+            logger: Some("db".to_string()),
+            data: json!({ "error": "connection lost" }),
+        };
+        let notification = params.to_notification().unwrap();
+        assert_eq!(notification.method, "notifications/message");
+        assert_eq!(
+            notification.params,
+            Some(json!({
+                "level": "error",
+                "logger": "db",
+                "data": { "error": "connection lost" }
+            }))
+        );
+        assert_eq!(
+            LoggingMessageParams::level_of(&notification),
+            Some(LogLevel::Error)
+        );
+        assert_eq!(
+            LoggingMessageParams::level_of(&Notification::tools_list_changed()),
+            None
+        );
+    }
+}
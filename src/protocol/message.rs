@@ -1,23 +1,27 @@
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{Map, Value};
+use std::collections::{HashSet, VecDeque};
 use std::fmt;
 
-use super::{ClientCapabilities, ImplementationInfo, RequestId, ServerCapabilities};
-use crate::Result;
+use super::{RequestId, RequestMeta, META_KEY};
+use crate::{Error, Result};
 
 /// Base JSON-RPC message
 /// 基础 JSON-RPC 消息
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum Message {
     Request(Request),
     Response(Response),
     Notification(Notification),
+    /// JSON-RPC batch: an array of messages sent and answered together
+    /// JSON-RPC 批处理：一起发送和应答的消息数组
+    Batch(Vec<Message>),
 }
 
 /// JSON-RPC request message
 /// JSON-RPC 请求消息
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Request {
     /// Protocol version (must be "2.0")
     /// 协议版本（必须为 "2.0"）
@@ -36,17 +40,22 @@ pub struct Request {
 
 /// JSON-RPC response message
 /// JSON-RPC 响应消息
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Response {
     /// Protocol version (must be "2.0")
     /// 协议版本（必须为 "2.0"）
     pub jsonrpc: String,
-    /// Request ID
-    /// 请求 ID
+    /// Request ID, `RequestId::Null` if the request ID could not be determined
+    /// 请求 ID；若无法确定请求 ID 则为 `RequestId::Null`
+    #[serde(deserialize_with = "RequestId::deserialize_nullable")]
     pub id: RequestId,
-    /// Response result
-    /// 响应结果
-    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Response result; a `null` result is kept as `Some(Value::Null)`
+    /// 响应结果；`null` 结果保留为 `Some(Value::Null)`
+    #[serde(
+        default,
+        deserialize_with = "deserialize_present",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub result: Option<Value>,
     /// Error if any
     /// 如果有错误
@@ -54,9 +63,17 @@ pub struct Response {
     pub error: Option<ResponseError>,
 }
 
+/// Deserializes a field that is present, even as `null`, to `Some`
+/// 将存在的字段（即使为 `null`）反序列化为 `Some`
+fn deserialize_present<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<Value>, D::Error> {
+    Value::deserialize(deserializer).map(Some)
+}
+
 /// JSON-RPC notification message (request without ID)
 /// JSON-RPC 通知消息（没有 ID 的请求）
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Notification {
     /// Protocol version (must be "2.0")
     /// 协议版本（必须为 "2.0"）
@@ -72,7 +89,7 @@ pub struct Notification {
 
 /// Error response
 /// 错误响应
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ResponseError {
     /// Error code
     /// 错误代码
@@ -80,9 +97,13 @@ pub struct ResponseError {
     /// Error message
     /// 错误消息
     pub message: String,
-    /// Additional error data
-    /// 附加错误数据
-    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Additional error data; `null` data is kept as `Some(Value::Null)`
+    /// 附加错误数据；`null` 数据保留为 `Some(Value::Null)`
+    #[serde(
+        default,
+        deserialize_with = "deserialize_present",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub data: Option<Value>,
 }
 
@@ -119,6 +140,8 @@ pub enum Method {
     Ping,
     #[serde(rename = "$/progress")]
     Progress,
+    #[serde(rename = "notifications/capabilities/changed")]
+    CapabilitiesChanged,
 
     // Server feature methods
     // 服务器功能方法
@@ -128,6 +151,12 @@ pub enum Method {
     GetPrompt,
     #[serde(rename = "prompts/execute")]
     ExecutePrompt,
+    #[serde(rename = "prompts/create")]
+    CreatePrompt,
+    #[serde(rename = "prompts/delete")]
+    DeletePrompt,
+    #[serde(rename = "notifications/prompts/list_changed")]
+    ListPromptsChanged,
 
     #[serde(rename = "resources/list")]
     ListResources,
@@ -143,6 +172,26 @@ pub enum Method {
     SubscribeResource,
     #[serde(rename = "resources/unsubscribe")]
     UnsubscribeResource,
+    #[serde(rename = "notifications/resources/updated")]
+    ResourceUpdated,
+    #[serde(rename = "resources/templates/list")]
+    ListResourceTemplates,
+    #[serde(rename = "resources/bulk_get")]
+    BulkGetResources,
+    #[serde(rename = "resources/read")]
+    ReadResources,
+    #[serde(rename = "resources/transaction")]
+    ResourceTransaction,
+    #[serde(rename = "notifications/resources/list_changed")]
+    ListResourcesChanged,
+
+    #[serde(rename = "completion/complete")]
+    Complete,
+
+    #[serde(rename = "logging/setLevel")]
+    SetLogLevel,
+    #[serde(rename = "notifications/message")]
+    LogMessage,
 
     #[serde(rename = "tools/list")]
     ListTools,
@@ -152,6 +201,14 @@ pub enum Method {
     ExecuteTool,
     #[serde(rename = "tools/cancel")]
     CancelTool,
+    #[serde(rename = "tools/validate")]
+    ValidateTool,
+    #[serde(rename = "tools/execute/stream")]
+    ExecuteToolStream,
+    #[serde(rename = "notifications/tools/chunk")]
+    ToolResultChunk,
+    #[serde(rename = "notifications/tools/list_changed")]
+    ListToolsChanged,
 
     // Client feature methods
     // 客户端功能方法
@@ -167,7 +224,13 @@ pub enum Method {
 impl Request {
     /// Creates a new request
     /// 创建一个新的请求
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is `RequestId::Null`, which is reserved for error responses.
+    /// 如果 `id` 为 `RequestId::Null` 则 panic，该值仅保留给错误响应使用。
     pub fn new(method: Method, params: Option<Value>, id: RequestId) -> Self {
+        assert!(!id.is_null(), "requests must not have a null ID");
         // ID is guaranteed to be string or number by type system
         // ID 已经通过类型系统保证是字符串或整数
         // ID uniqueness should be checked at session level
@@ -180,15 +243,147 @@ impl Request {
         }
     }
 
+    /// Creates a ping request in its canonical form, without params
+    /// 创建规范形式的 ping 请求，不带参数
+    pub fn ping(id: RequestId) -> Self {
+        Self::new(Method::Ping, None, id)
+    }
+
     /// Validates that the request ID is unique within the given session
     /// 验证请求 ID 在给定的会话中是唯一的
-    pub fn validate_id_uniqueness(&self, used_ids: &mut std::collections::HashSet<String>) -> bool {
+    ///
+    /// The ID is recorded as used, so ignoring the answer loses the only report of a
+    /// duplicate:
+    /// ID 会被记录为已使用，因此忽略返回值会丢失唯一的重复报告：
+    ///
+    /// ```compile_fail
+    /// #![deny(unused_must_use)]
+    /// use mcprotocol_rs::protocol::{Request, RequestId};
+    /// use std::collections::HashSet;
+    ///
+    /// let request = Request::ping(RequestId::Number(1));
+    /// request.validate_id_uniqueness(&mut HashSet::new());
+    /// ```
+    #[must_use]
+    pub fn validate_id_uniqueness(&self, used_ids: &mut HashSet<String>) -> bool {
         let id_str = match &self.id {
             RequestId::String(s) => s.clone(),
             RequestId::Number(n) => n.to_string(),
+            RequestId::Null => "null".to_string(),
         };
         used_ids.insert(id_str)
     }
+
+    /// Stores `meta` as `_meta` in the params
+    /// 将 `meta` 作为 `_meta` 存入参数
+    ///
+    /// Missing params become an object; non-object params are left untouched.
+    /// 缺失的参数会变为对象；非对象参数保持不变。
+    pub fn with_meta(mut self, meta: RequestMeta) -> Self {
+        if let Value::Object(map) = self.params.get_or_insert_with(|| Value::Object(Map::new())) {
+            let meta = serde_json::to_value(meta).unwrap_or_default();
+            map.insert(META_KEY.to_string(), meta);
+        }
+        self
+    }
+
+    /// Removes `_meta` from the params and returns it, if present and valid
+    /// 从参数中移除 `_meta` 并返回它（如果存在且有效）
+    ///
+    /// Invalid metadata is removed as well, so handlers never see `_meta`.
+    /// 无效的元数据同样会被移除，因此处理器不会看到 `_meta`。
+    pub fn extract_meta(&mut self) -> Option<RequestMeta> {
+        let meta = self.params.as_mut()?.as_object_mut()?.remove(META_KEY)?;
+        serde_json::from_value(meta).ok()
+    }
+
+    /// Converts the request into a notification with the same method and params, dropping the ID
+    /// 将请求转换为方法和参数相同的通知，并丢弃 ID
+    pub fn into_notification(self) -> Notification {
+        Notification {
+            jsonrpc: self.jsonrpc,
+            method: self.method,
+            params: self.params,
+        }
+    }
+}
+
+/// Default number of request IDs remembered by a `SessionIdTracker`
+/// `SessionIdTracker` 默认记住的请求 ID 数量
+pub const DEFAULT_SESSION_ID_CAPACITY: usize = 1024;
+
+/// Bounded set of recently used request IDs
+/// 最近使用的请求 ID 的有界集合
+///
+/// Unlike the unbounded set passed to `Request::validate_id_uniqueness`, the tracker
+/// only remembers the most recent `capacity` IDs, so long-lived sessions do not grow
+/// without limit. Duplicates of evicted IDs are no longer detected.
+/// 与传给 `Request::validate_id_uniqueness` 的无界集合不同，该跟踪器只记住最近的
+/// `capacity` 个 ID，因此长期会话不会无限增长。已淘汰 ID 的重复将不再被检测到。
+#[derive(Debug, Clone)]
+pub struct SessionIdTracker {
+    /// Maximum number of remembered IDs
+    /// 记住的 ID 的最大数量
+    capacity: usize,
+    /// IDs in insertion order, oldest first
+    /// 按插入顺序排列的 ID，最旧的在前
+    order: VecDeque<RequestId>,
+    /// IDs currently remembered
+    /// 当前记住的 ID
+    seen: HashSet<RequestId>,
+}
+
+impl SessionIdTracker {
+    /// Creates a tracker remembering at most `capacity` IDs
+    /// 创建最多记住 `capacity` 个 ID 的跟踪器
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            order: VecDeque::with_capacity(capacity),
+            seen: HashSet::with_capacity(capacity),
+        }
+    }
+
+    /// Records an ID, returning `false` if it was used recently
+    /// 记录一个 ID，如果它最近被使用过则返回 `false`
+    pub fn insert(&mut self, id: &RequestId) -> bool {
+        if self.seen.contains(id) {
+            return false;
+        }
+
+        if self.order.len() == self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.seen.remove(&oldest);
+            }
+        }
+        self.order.push_back(id.clone());
+        self.seen.insert(id.clone());
+        true
+    }
+
+    /// Whether the ID is among the remembered ones
+    /// ID 是否在记住的 ID 之中
+    pub fn contains(&self, id: &RequestId) -> bool {
+        self.seen.contains(id)
+    }
+
+    /// Number of remembered IDs
+    /// 记住的 ID 数量
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    /// Whether no IDs are remembered
+    /// 是否没有记住任何 ID
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+}
+
+impl Default for SessionIdTracker {
+    fn default() -> Self {
+        Self::new(DEFAULT_SESSION_ID_CAPACITY)
+    }
 }
 
 impl Response {
@@ -203,6 +398,12 @@ impl Response {
         }
     }
 
+    /// Creates the response to a ping, with an empty object as result
+    /// 创建 ping 的响应，结果为空对象
+    pub fn pong(id: RequestId) -> Self {
+        Self::success(Value::Object(Default::default()), id)
+    }
+
     /// Creates a new error response
     /// 创建一个新的错误响应
     pub fn error(error: ResponseError, id: RequestId) -> Self {
@@ -213,6 +414,28 @@ impl Response {
             error: Some(error),
         }
     }
+
+    /// Checks that `jsonrpc` is "2.0" and that exactly one of `result` and `error` is set
+    /// 检查 `jsonrpc` 为 "2.0"，且 `result` 与 `error` 恰好设置其一
+    pub fn validate(&self) -> Result<()> {
+        if self.jsonrpc != super::JSONRPC_VERSION {
+            return Err(Error::Protocol(format!(
+                "Invalid JSON-RPC version {:?} in response {:?}",
+                self.jsonrpc, self.id
+            )));
+        }
+        match (&self.result, &self.error) {
+            (Some(_), None) | (None, Some(_)) => Ok(()),
+            (Some(_), Some(_)) => Err(Error::Protocol(format!(
+                "Response {:?} has both result and error",
+                self.id
+            ))),
+            (None, None) => Err(Error::Protocol(format!(
+                "Response {:?} has neither result nor error",
+                self.id
+            ))),
+        }
+    }
 }
 
 impl Notification {
@@ -225,6 +448,104 @@ impl Notification {
             params,
         }
     }
+
+    /// Creates a `notifications/prompts/list_changed` notification
+    /// 创建 `notifications/prompts/list_changed` 通知
+    pub fn prompts_list_changed() -> Self {
+        Self::new(Method::ListPromptsChanged, None)
+    }
+
+    /// Creates a `notifications/resources/list_changed` notification
+    /// 创建 `notifications/resources/list_changed` 通知
+    pub fn resources_list_changed() -> Self {
+        Self::new(Method::ListResourcesChanged, None)
+    }
+
+    /// Creates a `notifications/tools/list_changed` notification
+    /// 创建 `notifications/tools/list_changed` 通知
+    pub fn tools_list_changed() -> Self {
+        Self::new(Method::ListToolsChanged, None)
+    }
+
+    /// Converts the notification into a request with the given ID, keeping method and params
+    /// 将通知转换为具有给定 ID 的请求，保留方法和参数
+    pub fn into_request(self, id: RequestId) -> Request {
+        Request {
+            jsonrpc: self.jsonrpc,
+            method: self.method,
+            params: self.params,
+            id,
+        }
+    }
+}
+
+impl Message {
+    /// Serializes the message as indented JSON
+    /// 将消息序列化为带缩进的 JSON
+    pub fn pretty_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Parses a message from UTF-8 encoded JSON
+    /// 从 UTF-8 编码的 JSON 解析消息
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Self::try_from(bytes)
+    }
+}
+
+impl TryFrom<Value> for Message {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self> {
+        Ok(serde_json::from_value(value)?)
+    }
+}
+
+impl TryFrom<Message> for Value {
+    type Error = Error;
+
+    fn try_from(message: Message) -> Result<Self> {
+        Ok(serde_json::to_value(message)?)
+    }
+}
+
+impl TryFrom<String> for Message {
+    type Error = Error;
+
+    fn try_from(json: String) -> Result<Self> {
+        Self::try_from(json.as_str())
+    }
+}
+
+impl TryFrom<&str> for Message {
+    type Error = Error;
+
+    fn try_from(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+impl TryFrom<&[u8]> for Message {
+    type Error = Error;
+
+    /// Invalid UTF-8 is reported like invalid JSON
+    /// 无效的 UTF-8 与无效的 JSON 一样报告
+    fn try_from(bytes: &[u8]) -> Result<Self> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+}
+
+/// Displays a message as pretty-printed JSON, for test output and trace logs
+/// 以美化 JSON 的形式显示消息，用于测试输出和跟踪日志
+pub struct PrettyMessage<'a>(pub &'a Message);
+
+impl fmt::Display for PrettyMessage<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match serde_json::to_string_pretty(self.0) {
+            Ok(json) => f.write_str(&json),
+            Err(_) => write!(f, "{:?}", self.0),
+        }
+    }
 }
 
 impl fmt::Display for Method {
@@ -240,6 +561,9 @@ impl fmt::Display for Method {
             Method::ListPrompts => write!(f, "prompts/list"),
             Method::GetPrompt => write!(f, "prompts/get"),
             Method::ExecutePrompt => write!(f, "prompts/execute"),
+            Method::CreatePrompt => write!(f, "prompts/create"),
+            Method::DeletePrompt => write!(f, "prompts/delete"),
+            Method::ListPromptsChanged => write!(f, "notifications/prompts/list_changed"),
             Method::ListResources => write!(f, "resources/list"),
             Method::GetResource => write!(f, "resources/get"),
             Method::CreateResource => write!(f, "resources/create"),
@@ -247,10 +571,24 @@ impl fmt::Display for Method {
             Method::DeleteResource => write!(f, "resources/delete"),
             Method::SubscribeResource => write!(f, "resources/subscribe"),
             Method::UnsubscribeResource => write!(f, "resources/unsubscribe"),
+            Method::CapabilitiesChanged => write!(f, "notifications/capabilities/changed"),
+            Method::ResourceUpdated => write!(f, "notifications/resources/updated"),
+            Method::ListResourceTemplates => write!(f, "resources/templates/list"),
+            Method::BulkGetResources => write!(f, "resources/bulk_get"),
+            Method::ReadResources => write!(f, "resources/read"),
+            Method::ResourceTransaction => write!(f, "resources/transaction"),
+            Method::ListResourcesChanged => write!(f, "notifications/resources/list_changed"),
+            Method::Complete => write!(f, "completion/complete"),
+            Method::SetLogLevel => write!(f, "logging/setLevel"),
+            Method::LogMessage => write!(f, "notifications/message"),
             Method::ListTools => write!(f, "tools/list"),
             Method::GetTool => write!(f, "tools/get"),
             Method::ExecuteTool => write!(f, "tools/execute"),
             Method::CancelTool => write!(f, "tools/cancel"),
+            Method::ValidateTool => write!(f, "tools/validate"),
+            Method::ExecuteToolStream => write!(f, "tools/execute/stream"),
+            Method::ToolResultChunk => write!(f, "notifications/tools/chunk"),
+            Method::ListToolsChanged => write!(f, "notifications/tools/list_changed"),
             Method::ListRoots => write!(f, "roots/list"),
             Method::GetRoot => write!(f, "roots/get"),
             Method::SamplingRequest => write!(f, "sampling/request"),
@@ -264,6 +602,89 @@ mod tests {
     use serde_json::json;
     use std::collections::HashSet;
 
+    #[test]
+    fn test_request_meta_round_trip() {
+        let meta = RequestMeta {
+            progress_token: Some(RequestId::Number(7)),
+            deadline: None,
+        };
+
+        let mut request =
+            Request::new(Method::ListTools, None, RequestId::Number(1)).with_meta(meta.clone());
+        assert_eq!(
+            request.params,
+            Some(json!({ "_meta": { "progressToken": 7 } }))
+        );
+        assert_eq!(request.extract_meta(), Some(meta.clone()));
+        assert_eq!(request.params, Some(json!({})));
+        assert_eq!(request.extract_meta(), None);
+
+        let mut request = Request::new(
+            Method::ExecuteTool,
+            Some(json!({ "id": "echo", "_meta": { "progressToken": [] } })),
+            RequestId::Number(2),
+        );
+        assert_eq!(request.extract_meta(), None);
+        assert_eq!(request.params, Some(json!({ "id": "echo" })));
+
+        let request =
+            Request::new(Method::ListTools, Some(json!([1])), RequestId::Number(3)).with_meta(meta);
+        assert_eq!(request.params, Some(json!([1])));
+    }
+
+    #[test]
+    fn test_list_changed_notifications() {
+        for (notification, method) in [
+            (
+                Notification::prompts_list_changed(),
+                "notifications/prompts/list_changed",
+            ),
+            (
+                Notification::resources_list_changed(),
+                "notifications/resources/list_changed",
+            ),
+            (
+                Notification::tools_list_changed(),
+                "notifications/tools/list_changed",
+            ),
+        ] {
+            assert_eq!(notification.method, method);
+            assert_eq!(
+                serde_json::to_value(&notification).unwrap(),
+                json!({ "jsonrpc": "2.0", "method": method })
+            );
+        }
+    }
+
+    #[test]
+    fn test_response_validate() {
+        let id = RequestId::Number(1);
+        assert!(Response::pong(id.clone()).validate().is_ok());
+        let error = ResponseError {
+            code: error_codes::INTERNAL_ERROR,
+            message: "boom".to_string(),
+            data: None,
+        };
+        assert!(Response::error(error.clone(), id.clone())
+            .validate()
+            .is_ok());
+
+        let both = Response {
+            error: Some(error),
+            ..Response::pong(id.clone())
+        };
+        assert!(matches!(both.validate(), Err(Error::Protocol(_))));
+
+        let old_version = Response {
+            jsonrpc: "1.0".to_string(),
+            ..Response::pong(id.clone())
+        };
+        assert!(matches!(old_version.validate(), Err(Error::Protocol(_))));
+
+        let empty: Response = serde_json::from_value(json!({"jsonrpc": "2.0", "id": 1})).unwrap();
+        assert!(matches!(empty.validate(), Err(Error::Protocol(_))));
+    }
+
     #[test]
     fn test_request_id_must_be_string_or_integer() {
         // test string id
@@ -310,6 +731,7 @@ mod tests {
         let id_str = match id {
             RequestId::String(s) => s.clone(),
             RequestId::Number(n) => n.to_string(),
+            RequestId::Null => "null".to_string(),
         };
         used_ids.insert(id_str)
     }
@@ -372,7 +794,7 @@ mod tests {
         // Create a request
         // 创建一个请求
         let request_id = RequestId::Number(42);
-        let request = Request::new(Method::Initialize, None, request_id.clone());
+        let _request = Request::new(Method::Initialize, None, request_id.clone());
 
         // Create success response
         // 创建成功响应
@@ -661,7 +1083,7 @@ mod tests {
         // Test server rejecting unsupported version
         // 测试服务器拒绝不支持的版本
         let unsupported_version = "1.0.0";
-        let client_request = Request::new(
+        let _client_request = Request::new(
             Method::Initialize,
             Some(json!({
                 "protocolVersion": unsupported_version
@@ -783,4 +1205,180 @@ mod tests {
         // Verify different ping requests have different IDs
         assert_ne!(ping_request.id, ping_request_2.id);
     }
+
+    #[test]
+    fn test_null_result_is_kept() {
+        // A null result is a valid success and must not read back as "no result"
+        // null 结果是有效的成功响应，反序列化后不能变成"没有结果"
+        let response = Response::success(Value::Null, RequestId::Number(1));
+        let json = serde_json::to_string(&response).unwrap();
+        assert_eq!(json, r#"{"jsonrpc":"2.0","id":1,"result":null}"#);
+        assert_eq!(
+            serde_json::from_str::<Message>(&json).unwrap(),
+            Message::Response(response)
+        );
+
+        let parsed: Response = serde_json::from_str(r#"{"jsonrpc":"2.0","id":1}"#).unwrap();
+        assert!(parsed.result.is_none());
+    }
+
+    #[test]
+    fn test_error_response_with_null_id() {
+        let response = Response::error(
+            ResponseError {
+                code: error_codes::PARSE_ERROR,
+                message: "Parse error".to_string(),
+                data: None,
+            },
+            RequestId::Null,
+        );
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(
+            json,
+            json!({
+                "jsonrpc": "2.0",
+                "id": null,
+                "error": { "code": -32700, "message": "Parse error" }
+            })
+        );
+
+        match serde_json::from_value(json).unwrap() {
+            Message::Response(parsed) => assert!(parsed.id.is_null()),
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_requests_reject_null_id() {
+        let json = r#"{"jsonrpc":"2.0","method":"ping","id":null}"#;
+        assert!(serde_json::from_str::<Request>(json).is_err());
+        assert!(std::panic::catch_unwind(|| Request::ping(RequestId::Null)).is_err());
+    }
+
+    #[test]
+    fn test_ping_pong_constructors() {
+        let id = RequestId::Number(7);
+
+        let ping = serde_json::to_value(Request::ping(id.clone())).unwrap();
+        assert_eq!(ping, json!({"jsonrpc": "2.0", "method": "ping", "id": 7}));
+        assert!(ping.get("params").is_none());
+
+        let pong = serde_json::to_value(Response::pong(id)).unwrap();
+        assert_eq!(pong, json!({"jsonrpc": "2.0", "id": 7, "result": {}}));
+    }
+
+    #[test]
+    fn test_list_resource_templates_method() {
+        let method = Method::ListResourceTemplates;
+        assert_eq!(method.to_string(), "resources/templates/list");
+
+        // 测试序列化与 Display 一致并可往返
+        // Test serialization matches Display and round-trips
+        let json = serde_json::to_string(&method).unwrap();
+        assert_eq!(json, r#""resources/templates/list""#);
+        let parsed: Method = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.to_string(), method.to_string());
+    }
+
+    #[test]
+    fn test_session_id_tracker_is_bounded() {
+        let mut tracker = SessionIdTracker::new(3);
+        for n in 1..=3 {
+            assert!(tracker.insert(&RequestId::Number(n)));
+        }
+
+        // A recent duplicate is rejected
+        // 最近的重复 ID 被拒绝
+        assert!(!tracker.insert(&RequestId::Number(3)));
+
+        // Inserting a fourth ID evicts the oldest one
+        // 插入第四个 ID 会淘汰最旧的 ID
+        assert!(tracker.insert(&RequestId::String("four".to_string())));
+        assert_eq!(tracker.len(), 3);
+        assert!(!tracker.contains(&RequestId::Number(1)));
+        assert!(tracker.insert(&RequestId::Number(1)));
+        assert!(!tracker.contains(&RequestId::Number(2)));
+    }
+
+    #[test]
+    fn test_request_notification_conversion() {
+        let request = Request::new(
+            Method::ExecuteTool,
+            Some(json!({ "id": "echo" })),
+            RequestId::Number(7),
+        );
+
+        // Dropping the ID keeps method and params
+        // 丢弃 ID 时保留方法和参数
+        let notification = request.clone().into_notification();
+        assert_eq!(notification.method, request.method);
+        assert_eq!(notification.params, request.params);
+        let json = serde_json::to_value(Message::Notification(notification.clone())).unwrap();
+        assert!(json.get("id").is_none());
+
+        // Adding an ID back produces an equivalent request
+        // 重新添加 ID 会生成等价的请求
+        let promoted = notification.into_request(RequestId::String("again".to_string()));
+        assert_eq!(promoted.method, request.method);
+        assert_eq!(promoted.params, request.params);
+        assert_eq!(promoted.id, RequestId::String("again".to_string()));
+    }
+
+    #[test]
+    fn test_pretty_message() {
+        let message = Message::Request(Request::new(
+            Method::Ping,
+            None,
+            RequestId::String("ping-1".to_string()),
+        ));
+
+        // 测试美化输出是带缩进的 JSON
+        // Test pretty output is indented JSON
+        let pretty = PrettyMessage(&message).to_string();
+        assert!(pretty.contains("\n  \"method\": \"ping\""));
+        assert_eq!(pretty, message.pretty_json().unwrap());
+
+        // 测试美化输出可以解析回相同的消息
+        // Test pretty output parses back to the same message
+        let parsed: Message = serde_json::from_str(&pretty).unwrap();
+        assert_eq!(
+            serde_json::to_string(&parsed).unwrap(),
+            serde_json::to_string(&message).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_message_conversions() {
+        let message = Message::Request(Request::new(Method::Ping, None, RequestId::Number(7)));
+
+        // 测试与 Value 相互转换
+        // Test conversion to and from Value
+        let value = Value::try_from(message.clone()).unwrap();
+        assert_eq!(
+            value,
+            json!({ "jsonrpc": "2.0", "method": "ping", "id": 7 })
+        );
+        assert_eq!(Message::try_from(value).unwrap(), message);
+
+        // 测试从字符串和字节解析
+        // Test parsing from strings and bytes
+        let json = serde_json::to_string(&message).unwrap();
+        assert_eq!(Message::try_from(json.clone()).unwrap(), message);
+        assert_eq!(Message::from_bytes(json.as_bytes()).unwrap(), message);
+
+        // 测试无效输入报告为序列化错误
+        // Test invalid input is reported as a serialization error
+        assert!(matches!(
+            Message::try_from(json!({ "jsonrpc": "2.0" })),
+            Err(Error::Serialization(_))
+        ));
+        assert!(matches!(
+            Message::try_from("not json".to_string()),
+            Err(Error::Serialization(_))
+        ));
+        assert!(matches!(
+            Message::from_bytes(b"\xff\xfe"),
+            Err(Error::Serialization(_))
+        ));
+    }
 }
@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::RequestId;
+
+/// Params key carrying the request metadata
+pub const META_KEY: &str = "_meta";
+
+/// Token correlating `$/progress` notifications with the request they report on
+pub type ProgressToken = RequestId;
+
+/// Cross-cutting metadata sent in a request's `params._meta`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestMeta {
+    /// Token the receiver should use when reporting progress
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub progress_token: Option<ProgressToken>,
+    /// Time after which the sender no longer needs the result, sent as milliseconds
+    /// since the Unix epoch
+    #[serde(
+        default,
+        with = "deadline_millis",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub deadline: Option<SystemTime>,
+}
+
+impl RequestMeta {
+    /// Time left until the deadline, zero once it has passed
+    pub fn remaining(&self) -> Option<Duration> {
+        self.deadline.map(|deadline| {
+            deadline
+                .duration_since(SystemTime::now())
+                .unwrap_or_default()
+        })
+    }
+}
+
+mod deadline_millis {
+    use super::*;
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        deadline: &Option<SystemTime>,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        let millis = deadline.map(|deadline| {
+            deadline
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64
+        });
+        millis.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Option<SystemTime>, D::Error> {
+        Ok(Option::<u64>::deserialize(deserializer)?
+            .map(|millis| UNIX_EPOCH + Duration::from_millis(millis)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_meta_wire_format() {
+        let meta = RequestMeta {
+            progress_token: Some(RequestId::String("op-1".to_string())),
+            deadline: Some(UNIX_EPOCH + Duration::from_millis(1_700_000_000_123)),
+        };
+        let value = serde_json::to_value(&meta).unwrap();
+        assert_eq!(
+            value,
+            json!({ "progressToken": "op-1", "deadline": 1_700_000_000_123u64 })
+        );
+        assert_eq!(serde_json::from_value::<RequestMeta>(value).unwrap(), meta);
+
+        assert_eq!(
+            serde_json::to_value(RequestMeta::default()).unwrap(),
+            json!({})
+        );
+        assert_eq!(meta.remaining(), Some(Duration::ZERO));
+    }
+}
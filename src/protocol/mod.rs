@@ -1,20 +1,77 @@
+pub mod capability_diff;
+pub mod logging;
 pub mod message;
+pub mod meta;
+pub mod operation;
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::{Error, Result};
+
+pub use capability_diff::{CapabilitiesChangedParams, Capability, CapabilityDiff};
+pub use logging::{LogLevel, LoggingMessageParams, SetLevelParams};
 pub use message::*;
+pub use meta::{ProgressToken, RequestMeta, META_KEY};
+pub use operation::{
+    OperationId, OperationState, OperationTracker, DEFAULT_FINISHED_OPERATIONS, OPERATION_ID_KEY,
+};
 
 /// Current protocol version
+///
+/// Must be a `YYYY-MM-DD` date; `build.rs` warns otherwise and exposes the value
+/// as the `MCP_PROTOCOL_VERSION` compile-time environment variable.
 pub const PROTOCOL_VERSION: &str = "2024-11-05";
 pub const JSONRPC_VERSION: &str = "2.0";
 
 /// Represents a unique identifier for JSON-RPC requests
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, Hash)]
 #[serde(untagged)]
 pub enum RequestId {
     String(String),
     Number(i64),
+    /// `null`, only valid in error responses to messages whose ID could not be
+    /// determined; deserializing a `RequestId` rejects it
+    Null,
+}
+
+/// Non-null request ID as it appears on the wire
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum WireRequestId {
+    String(String),
+    Number(i64),
+}
+
+impl From<WireRequestId> for RequestId {
+    fn from(id: WireRequestId) -> Self {
+        match id {
+            WireRequestId::String(s) => RequestId::String(s),
+            WireRequestId::Number(n) => RequestId::Number(n),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for RequestId {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        WireRequestId::deserialize(deserializer).map(Into::into)
+    }
+}
+
+impl RequestId {
+    /// Whether this is the `null` ID of an error response
+    pub fn is_null(&self) -> bool {
+        matches!(self, RequestId::Null)
+    }
+
+    /// Deserializes a response ID, which may be `null`
+    pub(crate) fn deserialize_nullable<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        Ok(Option::<WireRequestId>::deserialize(deserializer)?.map_or(RequestId::Null, Into::into))
+    }
 }
 
 /// Client capabilities
@@ -29,9 +86,25 @@ pub struct ClientCapabilities {
     /// Experimental features
     #[serde(skip_serializing_if = "Option::is_none")]
     pub experimental: Option<Value>,
+    /// Experimental support for receiving streamed tool results
+    #[serde(rename = "toolStreaming", skip_serializing_if = "Option::is_none")]
+    pub tool_streaming: Option<ToolStreamingCapability>,
+}
+
+/// Experimental tool result streaming capability
+///
+/// Streaming is used only when both client and server advertise it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ToolStreamingCapability {
+    /// Chunk formats understood, e.g. `text` or `json_lines`
+    #[serde(default)]
+    pub formats: Vec<String>,
 }
 
-/// Server capabilities
+/// Server capabilities, as sent in the `initialize` result
+///
+/// `server_features::ServerFeatureFlags` is a flag-only view of this type; the
+/// default advertises no features.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ServerCapabilities {
     /// Prompt capabilities
@@ -49,6 +122,71 @@ pub struct ServerCapabilities {
     /// Experimental features
     #[serde(skip_serializing_if = "Option::is_none")]
     pub experimental: Option<Value>,
+    /// Experimental support for streaming tool results
+    #[serde(rename = "toolStreaming", skip_serializing_if = "Option::is_none")]
+    pub tool_streaming: Option<ToolStreamingCapability>,
+}
+
+impl ServerCapabilities {
+    /// Whether prompts are supported
+    pub fn supports_prompts(&self) -> bool {
+        self.prompts.is_some()
+    }
+
+    /// Whether the server notifies when the prompt list changes
+    pub fn supports_prompts_list_changed(&self) -> bool {
+        self.prompts.as_ref().is_some_and(|p| p.list_changed)
+    }
+
+    /// Whether resources are supported
+    pub fn supports_resources(&self) -> bool {
+        self.resources.is_some()
+    }
+
+    /// Whether resources can be subscribed to
+    pub fn supports_resource_subscribe(&self) -> bool {
+        self.resources.as_ref().is_some_and(|r| r.subscribe)
+    }
+
+    /// Whether the server notifies when the resource list changes
+    pub fn supports_resources_list_changed(&self) -> bool {
+        self.resources.as_ref().is_some_and(|r| r.list_changed)
+    }
+
+    /// Whether resource templates can be listed
+    pub fn supports_resource_templates(&self) -> bool {
+        self.resources.as_ref().is_some_and(|r| r.templates)
+    }
+
+    /// Whether several resources can be fetched with `resources/bulk_get`
+    pub fn supports_resource_bulk_get(&self) -> bool {
+        self.resources.as_ref().is_some_and(|r| r.bulk_get)
+    }
+
+    /// Whether several changes can be applied at once with `resources/transaction`
+    pub fn supports_resource_transactions(&self) -> bool {
+        self.resources.as_ref().is_some_and(|r| r.transactions)
+    }
+
+    /// Whether tools are supported
+    pub fn supports_tools(&self) -> bool {
+        self.tools.is_some()
+    }
+
+    /// Whether the server notifies when the tool list changes
+    pub fn supports_tools_list_changed(&self) -> bool {
+        self.tools.as_ref().is_some_and(|t| t.list_changed)
+    }
+
+    /// Whether the server accepts logging requests
+    pub fn supports_logging(&self) -> bool {
+        self.logging.is_some()
+    }
+
+    /// Whether tool results can be streamed
+    pub fn supports_tool_streaming(&self) -> bool {
+        self.tool_streaming.is_some()
+    }
 }
 
 /// Root directory capability
@@ -68,6 +206,15 @@ pub struct ResourceCapability {
     /// Support for list change notifications
     #[serde(default)]
     pub list_changed: bool,
+    /// Support for listing resource templates
+    #[serde(default)]
+    pub templates: bool,
+    /// Support for fetching several resources with `resources/bulk_get`
+    #[serde(default)]
+    pub bulk_get: bool,
+    /// Support for applying several changes at once with `resources/transaction`
+    #[serde(default)]
+    pub transactions: bool,
 }
 
 /// Feature capability with list change support
@@ -87,6 +234,47 @@ pub struct ImplementationInfo {
     pub version: String,
 }
 
+/// Parameters of the `initialize` request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InitializeParams {
+    /// Protocol version requested by the client
+    pub protocol_version: String,
+    /// Client capabilities
+    #[serde(default)]
+    pub capabilities: ClientCapabilities,
+    /// Client implementation information
+    pub client_info: ImplementationInfo,
+}
+
+impl InitializeParams {
+    /// Parses `initialize` request params, failing with `INVALID_PARAMS` if they are
+    /// missing or malformed (e.g. without `clientInfo`)
+    pub fn from_params(params: Option<&Value>) -> Result<Self> {
+        let params = params.ok_or_else(|| Error::JsonRpc {
+            code: error_codes::INVALID_PARAMS,
+            message: "Missing initialize params".to_string(),
+        })?;
+        serde_json::from_value(params.clone()).map_err(|e| Error::JsonRpc {
+            code: error_codes::INVALID_PARAMS,
+            message: format!("Invalid initialize params: {}", e),
+        })
+    }
+}
+
+/// Result of the `initialize` request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InitializeResult {
+    /// Protocol version selected by the server
+    pub protocol_version: String,
+    /// Server capabilities
+    #[serde(default)]
+    pub capabilities: ServerCapabilities,
+    /// Server implementation information
+    pub server_info: ImplementationInfo,
+}
+
 /// Represents the role of an MCP participant
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Role {
@@ -97,3 +285,95 @@ pub enum Role {
     /// A service that provides context and capabilities
     Server,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_build_script_exposes_protocol_version() {
+        assert_eq!(env!("MCP_PROTOCOL_VERSION"), PROTOCOL_VERSION);
+    }
+
+    #[test]
+    fn test_initialize_params_require_client_info() {
+        let params = json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": {}
+        });
+
+        let err = InitializeParams::from_params(Some(&params)).unwrap_err();
+        assert!(matches!(err, Error::JsonRpc { code, .. } if code == error_codes::INVALID_PARAMS));
+        assert!(InitializeParams::from_params(None).is_err());
+    }
+
+    #[test]
+    fn test_initialize_params_parse() {
+        let params = json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "clientInfo": { "name": "TestClient", "version": "1.0.0" }
+        });
+
+        let parsed = InitializeParams::from_params(Some(&params)).unwrap();
+        assert_eq!(parsed.protocol_version, PROTOCOL_VERSION);
+        assert_eq!(parsed.client_info.name, "TestClient");
+    }
+
+    #[test]
+    fn test_initialize_result_requires_server_info() {
+        let result = json!({ "protocolVersion": PROTOCOL_VERSION, "capabilities": {} });
+        assert!(serde_json::from_value::<InitializeResult>(result).is_err());
+    }
+
+    #[test]
+    fn test_server_capability_checks_with_partial_features() {
+        let capabilities: ServerCapabilities = serde_json::from_value(json!({
+            "tools": { "list_changed": true },
+            "resources": { "subscribe": true }
+        }))
+        .unwrap();
+
+        assert!(capabilities.supports_tools());
+        assert!(capabilities.supports_tools_list_changed());
+        assert!(capabilities.supports_resources());
+        assert!(capabilities.supports_resource_subscribe());
+        assert!(!capabilities.supports_resources_list_changed());
+        assert!(!capabilities.supports_resource_templates());
+        assert!(!capabilities.supports_resource_bulk_get());
+        assert!(!capabilities.supports_prompts());
+        assert!(!capabilities.supports_prompts_list_changed());
+        assert!(!capabilities.supports_logging());
+        assert!(!capabilities.supports_tool_streaming());
+
+        let none = ServerCapabilities::default();
+        assert!(!none.supports_tools() && !none.supports_tools_list_changed());
+        assert!(!none.supports_resource_subscribe());
+    }
+
+    #[test]
+    fn test_resource_capability_templates_flag() {
+        let capability = ResourceCapability {
+            subscribe: false,
+            list_changed: false,
+            templates: true,
+            bulk_get: false,
+            transactions: false,
+        };
+        assert_eq!(
+            serde_json::to_value(&capability).unwrap(),
+            json!({
+                "subscribe": false,
+                "list_changed": false,
+                "templates": true,
+                "bulk_get": false,
+                "transactions": false
+            })
+        );
+
+        let parsed: ResourceCapability = serde_json::from_value(json!({})).unwrap();
+        assert!(!parsed.templates);
+        assert!(!parsed.bulk_get);
+        assert!(!parsed.transactions);
+    }
+}
@@ -0,0 +1,229 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+/// Params key carrying the operation ID
+pub const OPERATION_ID_KEY: &str = "_operationId";
+
+/// Finished operations an `OperationTracker` remembers unless configured otherwise
+pub const DEFAULT_FINISHED_OPERATIONS: usize = 1024;
+
+/// Correlates a request with its response and any related notifications
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct OperationId(pub Uuid);
+
+impl OperationId {
+    /// Generates a random operation ID
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+
+    /// Reads the operation ID from message params, if present and valid
+    pub fn from_params(params: Option<&Value>) -> Option<Self> {
+        params?
+            .get(OPERATION_ID_KEY)
+            .and_then(|id| serde_json::from_value(id.clone()).ok())
+    }
+
+    /// Stores the operation ID in message params
+    ///
+    /// Missing params become an object; non-object params are left untouched and
+    /// `false` is returned.
+    pub fn attach(&self, params: &mut Option<Value>) -> bool {
+        match params.get_or_insert_with(|| Value::Object(Map::new())) {
+            Value::Object(map) => {
+                map.insert(
+                    OPERATION_ID_KEY.to_string(),
+                    Value::String(self.to_string()),
+                );
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Default for OperationId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for OperationId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Phase of a tracked operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationState {
+    /// Sent or registered, not started yet
+    Pending,
+    /// Being processed
+    InProgress,
+    /// Finished successfully
+    Complete,
+    /// Finished with an error
+    Failed,
+    /// Cancelled before completion
+    Cancelled,
+}
+
+impl OperationState {
+    /// Whether the operation has reached a final state
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            OperationState::Complete | OperationState::Failed | OperationState::Cancelled
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+struct Operations {
+    states: HashMap<OperationId, OperationState>,
+    /// Finished operations, oldest first
+    finished: VecDeque<OperationId>,
+}
+
+/// Thread-safe map of operation states
+///
+/// Running operations are kept until they finish; of the finished ones, only the
+/// most recent are remembered, so the map cannot grow without bound.
+#[derive(Debug)]
+pub struct OperationTracker {
+    operations: Mutex<Operations>,
+    max_finished: usize,
+}
+
+impl OperationTracker {
+    /// Creates an empty tracker remembering `DEFAULT_FINISHED_OPERATIONS` finished
+    /// operations
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_FINISHED_OPERATIONS)
+    }
+
+    /// Creates an empty tracker remembering at most `max_finished` finished operations
+    pub fn with_capacity(max_finished: usize) -> Self {
+        Self {
+            operations: Mutex::default(),
+            max_finished,
+        }
+    }
+
+    /// Current state of an operation
+    pub fn status(&self, id: &OperationId) -> Option<OperationState> {
+        self.operations.lock().unwrap().states.get(id).copied()
+    }
+
+    /// Moves an operation to a new state
+    ///
+    /// Finished operations keep their final state, so a late progress update or
+    /// completion cannot override a cancellation. Once more than the configured
+    /// number of operations have finished, the oldest finished one is forgotten.
+    pub fn transition(&self, id: OperationId, state: OperationState) {
+        let mut operations = self.operations.lock().unwrap();
+        if operations.states.get(&id).is_some_and(|s| s.is_finished()) {
+            return;
+        }
+        operations.states.insert(id, state);
+        if state.is_finished() {
+            operations.finished.push_back(id);
+            while operations.finished.len() > self.max_finished {
+                if let Some(oldest) = operations.finished.pop_front() {
+                    operations.states.remove(&oldest);
+                }
+            }
+        }
+    }
+
+    /// Forgets all finished operations
+    pub fn clear_finished(&self) {
+        let mut operations = self.operations.lock().unwrap();
+        let Operations { states, finished } = &mut *operations;
+        for id in finished.drain(..) {
+            states.remove(&id);
+        }
+    }
+
+    /// Number of tracked operations
+    pub fn len(&self) -> usize {
+        self.operations.lock().unwrap().states.len()
+    }
+
+    /// Whether no operations are tracked
+    pub fn is_empty(&self) -> bool {
+        self.operations.lock().unwrap().states.is_empty()
+    }
+}
+
+impl Default for OperationTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_attach_and_read_back() {
+        let id = OperationId::new();
+
+        let mut params = None;
+        assert!(id.attach(&mut params));
+        assert_eq!(OperationId::from_params(params.as_ref()), Some(id));
+
+        let mut params = Some(json!({ "id": "echo" }));
+        assert!(id.attach(&mut params));
+        assert_eq!(params.as_ref().unwrap()["id"], "echo");
+        assert_eq!(OperationId::from_params(params.as_ref()), Some(id));
+
+        let mut params = Some(json!([1, 2]));
+        assert!(!id.attach(&mut params));
+    }
+
+    #[test]
+    fn test_finished_state_is_final() {
+        let tracker = OperationTracker::new();
+        let id = OperationId::new();
+
+        tracker.transition(id, OperationState::Pending);
+        tracker.transition(id, OperationState::InProgress);
+        tracker.transition(id, OperationState::Cancelled);
+        tracker.transition(id, OperationState::Complete);
+        assert_eq!(tracker.status(&id), Some(OperationState::Cancelled));
+
+        tracker.clear_finished();
+        assert!(tracker.is_empty());
+    }
+
+    #[test]
+    fn test_oldest_finished_operations_are_forgotten() {
+        let tracker = OperationTracker::with_capacity(2);
+        let running = OperationId::new();
+        tracker.transition(running, OperationState::InProgress);
+
+        let finished: Vec<_> = (0..3).map(|_| OperationId::new()).collect();
+        tracker.transition(finished[0], OperationState::Complete);
+        tracker.transition(finished[1], OperationState::Failed);
+        tracker.transition(finished[2], OperationState::Cancelled);
+
+        assert_eq!(tracker.status(&finished[0]), None);
+        assert_eq!(tracker.status(&finished[1]), Some(OperationState::Failed));
+        assert_eq!(
+            tracker.status(&finished[2]),
+            Some(OperationState::Cancelled)
+        );
+        assert_eq!(tracker.status(&running), Some(OperationState::InProgress));
+        assert_eq!(tracker.len(), 3);
+    }
+}
@@ -0,0 +1,175 @@
+use lru::LruCache;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+
+use crate::protocol::{RequestId, Response};
+
+/// Identifies the session a request arrived on
+///
+/// `McpServer` numbers its transports in the order they were added, so the same
+/// request ID sent on two transports is never treated as a duplicate. Transports
+/// serving several clients also tell which client sent a request, so clients of the
+/// same transport are kept apart too.
+pub type SessionId = u64;
+
+/// Session, client within it and request ID a stored response answers
+type Key = (SessionId, Option<u64>, RequestId);
+
+/// Default maximum number of responses kept by a `DeduplicationCache`
+pub const DEFAULT_DEDUPLICATION_CAPACITY: usize = 10_000;
+
+/// Counters of a `DeduplicationCache`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DeduplicationStats {
+    /// Requests answered with a stored response
+    pub hits: u64,
+    /// Requests that had to be executed
+    pub misses: u64,
+    /// Responses dropped to stay within capacity
+    pub evictions: u64,
+}
+
+/// Sliding window of recent responses, used to answer redelivered requests
+///
+/// Responses are keyed by the session, the client within it, if the transport
+/// tells, and the request ID, and replayed for requests seen again within `window`. At most `capacity` responses are kept; the least
+/// recently used one is evicted first.
+pub struct DeduplicationCache {
+    window: Duration,
+    entries: Mutex<LruCache<Key, (Instant, Response)>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+}
+
+impl DeduplicationCache {
+    /// Creates a cache keeping up to `DEFAULT_DEDUPLICATION_CAPACITY` responses
+    pub fn new(window: Duration) -> Self {
+        Self::with_capacity(window, DEFAULT_DEDUPLICATION_CAPACITY)
+    }
+
+    /// Creates a cache keeping up to `capacity` responses for `window` each
+    pub fn with_capacity(window: Duration, capacity: usize) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        Self {
+            window,
+            entries: Mutex::new(LruCache::new(capacity)),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+        }
+    }
+
+    /// Hits, misses and evictions so far
+    pub fn stats(&self) -> DeduplicationStats {
+        DeduplicationStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Stored response for a request seen within the window
+    ///
+    /// Counts a hit when a response is found and a miss otherwise.
+    pub fn get(&self, session: SessionId, client: Option<u64>, id: &RequestId) -> Option<Response> {
+        let mut entries = self.entries.lock().unwrap();
+        let key = (session, client, id.clone());
+        let found = match entries.get(&key) {
+            Some((seen_at, response)) if seen_at.elapsed() < self.window => Some(response.clone()),
+            Some(_) => {
+                entries.pop(&key);
+                None
+            }
+            None => None,
+        };
+
+        let counter = if found.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        found
+    }
+
+    /// Stores the response computed for a request
+    pub fn insert(
+        &self,
+        session: SessionId,
+        client: Option<u64>,
+        id: RequestId,
+        response: Response,
+    ) {
+        let key = (session, client, id);
+        let mut entries = self.entries.lock().unwrap();
+        if let Some((evicted, _)) = entries.push(key.clone(), (Instant::now(), response)) {
+            if evicted != key {
+                self.evictions.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Number of stored responses, including expired ones not yet dropped
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    /// Whether no responses are stored
+    pub fn is_empty(&self) -> bool {
+        self.entries.lock().unwrap().is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn response(id: i64) -> Response {
+        Response::success(json!(id), RequestId::Number(id))
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_responses_are_replayed_within_window() {
+        let cache = DeduplicationCache::new(Duration::from_secs(10));
+        let id = RequestId::Number(1);
+
+        assert!(cache.get(1, None, &id).is_none());
+        cache.insert(1, None, id.clone(), response(1));
+        assert_eq!(cache.get(1, None, &id), Some(response(1)));
+        assert!(cache.get(2, None, &id).is_none());
+        assert!(cache.get(1, Some(7), &id).is_none());
+
+        tokio::time::advance(Duration::from_secs(11)).await;
+        assert!(cache.get(1, None, &id).is_none());
+        assert!(cache.is_empty());
+
+        assert_eq!(
+            cache.stats(),
+            DeduplicationStats {
+                hits: 1,
+                misses: 4,
+                evictions: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_least_recently_used_response_is_evicted() {
+        let cache = DeduplicationCache::with_capacity(Duration::from_secs(60), 2);
+        cache.insert(1, None, RequestId::Number(1), response(1));
+        cache.insert(1, None, RequestId::Number(2), response(2));
+        cache.get(1, None, &RequestId::Number(1));
+        cache.insert(1, None, RequestId::Number(3), response(3));
+        cache.insert(1, None, RequestId::Number(3), response(3));
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(1, None, &RequestId::Number(2)).is_none());
+        assert!(cache.get(1, None, &RequestId::Number(1)).is_some());
+        assert_eq!(cache.stats().evictions, 1);
+    }
+}
@@ -0,0 +1,867 @@
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+use std::future::Future;
+use std::sync::Arc;
+
+use crate::{
+    protocol::{
+        error_codes, ImplementationInfo, InitializeParams, InitializeResult, Message, Method,
+        Notification, OperationId, OperationState, OperationTracker, Request, RequestId,
+        RequestMeta, Response, ResponseError, ServerCapabilities, PROTOCOL_VERSION,
+    },
+    server_features::{
+        AuthContext, BulkGetRequest, CompleteParams, CompleteResult, CompletionProvider,
+        GetResourceResult, ListResourceTemplatesResult, ListToolsFilter, NoopProgressSink, Page,
+        Prompt, PromptExecutionContext, PromptManager, ReadResourcesRequest, Resource,
+        ResourceAuditEntry, ResourceManager, ResourceTransactionRequest, ToolExecutionContext,
+        ToolManager, TransportProgressSink, ValidateToolResult,
+    },
+    transport::Transport,
+    Error, Result,
+};
+
+use super::{EventBus, ServerEvent, ServerPlugin};
+
+/// Routes requests to the registered feature handlers
+///
+/// A dispatcher is shared by every transport of an `McpServer`, so all clients see
+/// the same tools, resources, prompts and completions.
+pub struct Dispatcher {
+    pub(super) server_info: ImplementationInfo,
+    pub(super) tools: Option<Arc<dyn ToolManager>>,
+    pub(super) resources: Option<Arc<dyn ResourceManager>>,
+    pub(super) prompts: Option<Arc<dyn PromptManager>>,
+    pub(super) completion: Option<Arc<dyn CompletionProvider>>,
+    pub(super) operations: Arc<OperationTracker>,
+    pub(super) plugins: Vec<Arc<dyn ServerPlugin>>,
+    pub(super) events: EventBus,
+}
+
+impl Dispatcher {
+    /// Capabilities advertised for the registered handlers, as adjusted by plugins
+    pub fn capabilities(&self) -> ServerCapabilities {
+        let mut capabilities = ServerCapabilities {
+            prompts: self.prompts.as_ref().map(|p| p.capabilities()),
+            resources: self.resources.as_ref().map(|r| r.capabilities()),
+            tools: self.tools.as_ref().map(|t| t.capabilities()),
+            // Sessions answer `logging/setLevel` themselves
+            logging: Some(json!({})),
+            ..Default::default()
+        };
+        for plugin in &self.plugins {
+            plugin.on_initialize(&mut capabilities);
+        }
+        capabilities
+    }
+
+    /// Plugins consulted before the built-in handlers, in registration order
+    pub fn plugins(&self) -> &[Arc<dyn ServerPlugin>] {
+        &self.plugins
+    }
+
+    /// States of requests that carried an `_operationId`
+    pub fn operations(&self) -> &Arc<OperationTracker> {
+        &self.operations
+    }
+
+    /// Compute the response to a request
+    ///
+    /// Requests carrying an `_operationId` are tracked as in progress while they are
+    /// handled and complete afterwards. The `_meta` of the params is removed and
+    /// handed to tool and prompt executions through their context.
+    pub async fn handle_request(&self, request: &Request) -> Response {
+        match self.try_handle_request(request, None, None).await {
+            Ok(result) => Response::success(result, request.id.clone()),
+            Err(e) => Response::error(ResponseError::from(e), request.id.clone()),
+        }
+    }
+
+    /// Compute the result of a request, leaving errors to the caller
+    ///
+    /// Tracks operations like `handle_request`. `auth` is the identity the transport
+    /// established for the caller and is handed to tool and prompt executions and to
+    /// resource audits. Tools report progress as `$/progress` notifications over
+    /// `transport` when the request carries a `_meta.progressToken`.
+    pub(super) async fn try_handle_request(
+        &self,
+        request: &Request,
+        auth: Option<&AuthContext>,
+        transport: Option<&Arc<dyn Transport>>,
+    ) -> Result<Value> {
+        let operation = OperationId::from_params(request.params.as_ref());
+        if let Some(id) = operation {
+            self.operations.transition(id, OperationState::InProgress);
+        }
+
+        let mut stripped = request.clone();
+        let meta = stripped.extract_meta().unwrap_or_default();
+        let result = self
+            .dispatch(&stripped, meta, auth, transport, operation)
+            .await;
+
+        if let Some(id) = operation {
+            let state = match result {
+                Ok(_) => OperationState::Complete,
+                Err(_) => OperationState::Failed,
+            };
+            self.operations.transition(id, state);
+        }
+        result
+    }
+
+    /// Answer a JSON-RPC batch
+    ///
+    /// The requests of the batch are handled concurrently and their responses are
+    /// returned as a batch in the same order; notifications get no response. Returns
+    /// `None` when the batch contains only notifications, and a single error
+    /// response when it is empty.
+    pub async fn handle_batch(&self, batch: &[Message]) -> Option<Message> {
+        answer_batch(
+            batch,
+            |request| async move { Some(self.handle_request(request).await) },
+            |notification| self.handle_notification(notification),
+        )
+        .await
+    }
+
+    /// Handle a notification from a client
+    ///
+    /// Cancellation and progress notifications carrying an `_operationId` update the
+    /// state of that operation.
+    pub async fn handle_notification(&self, notification: &Notification) {
+        tracing::trace!(method = %notification.method, "received notification");
+
+        if let Some(id) = OperationId::from_params(notification.params.as_ref()) {
+            if notification.method == Method::Cancel.to_string() {
+                self.operations.transition(id, OperationState::Cancelled);
+            } else if notification.method == Method::Progress.to_string() {
+                self.operations.transition(id, OperationState::InProgress);
+            }
+        }
+    }
+
+    async fn dispatch(
+        &self,
+        request: &Request,
+        meta: RequestMeta,
+        auth: Option<&AuthContext>,
+        transport: Option<&Arc<dyn Transport>>,
+        operation: Option<OperationId>,
+    ) -> Result<Value> {
+        let params = request.params.as_ref();
+        let method = serde_json::from_value::<Method>(Value::String(request.method.clone()))
+            .map_err(|_| method_not_found(&request.method))?;
+
+        match method {
+            Method::Initialize => {
+                InitializeParams::from_params(params)?;
+                Ok(serde_json::to_value(InitializeResult {
+                    protocol_version: PROTOCOL_VERSION.to_string(),
+                    capabilities: self.capabilities(),
+                    server_info: self.server_info.clone(),
+                })?)
+            }
+            Method::Ping => Ok(json!({})),
+            Method::Shutdown => Ok(Value::Null),
+
+            Method::ListTools => {
+                let filter: ListToolsFilter = optional_param(params, "filter")?.unwrap_or_default();
+                let cursor: Option<String> = optional_param(params, "cursor")?;
+                let page = self
+                    .tools(&request.method)?
+                    .list_tools_page(&filter, cursor.as_deref())
+                    .await?;
+                page_result("tools", page)
+            }
+            Method::GetTool => {
+                let id: String = param(params, "id")?;
+                let tool = self.tools(&request.method)?.get_tool(&id).await?;
+                Ok(json!({ "tool": tool }))
+            }
+            Method::ExecuteTool => {
+                let id: String = param(params, "id")?;
+                let arguments = optional_param(params, "params")?.unwrap_or_else(|| json!({}));
+                let context = match (transport, &meta.progress_token) {
+                    (Some(transport), Some(token)) => ToolExecutionContext::new(
+                        TransportProgressSink::new(Arc::clone(transport), json!(token))
+                            .with_operation_id(operation),
+                    ),
+                    _ => ToolExecutionContext::new(NoopProgressSink),
+                };
+                let context = context
+                    .with_meta(meta)
+                    .with_auth(auth.cloned())
+                    .with_operation_id(operation);
+                let result = self
+                    .tools(&request.method)?
+                    .execute_tool_with_context(&id, arguments, &context)
+                    .await?;
+                Ok(serde_json::to_value(result)?)
+            }
+            Method::ValidateTool => {
+                let id: String = param(params, "id")?;
+                let arguments = optional_param(params, "params")?.unwrap_or_else(|| json!({}));
+                let violations = self
+                    .tools(&request.method)?
+                    .validate_tool_params(&id, &arguments)
+                    .await?;
+                Ok(serde_json::to_value(ValidateToolResult::from(violations))?)
+            }
+            Method::CancelTool => {
+                let id: String = param(params, "id")?;
+                self.tools(&request.method)?.cancel_tool(&id).await?;
+                Ok(json!({}))
+            }
+
+            Method::ListResources => {
+                let cursor: Option<String> = optional_param(params, "cursor")?;
+                let page = self
+                    .resources(&request.method)?
+                    .list_resources_page(cursor.as_deref())
+                    .await?;
+                page_result("resources", page)
+            }
+            Method::GetResource => {
+                let id: String = param(params, "id")?;
+                let resources = self.resources(&request.method)?;
+                let resource = resources.get_resource(&id).await?;
+                let contents = vec![resources.resource_contents(&resource).await?];
+                Ok(serde_json::to_value(GetResourceResult {
+                    resource,
+                    contents,
+                })?)
+            }
+            Method::CreateResource => {
+                let resource: Resource = param(params, "resource")?;
+                let id = resource.id.clone();
+                let resources = self.resources(&request.method)?;
+                resources.create_resource(resource).await?;
+                audit(resources, request, vec![id], auth).await;
+                Ok(json!({}))
+            }
+            Method::UpdateResource => {
+                let id: String = param(params, "id")?;
+                let resource: Resource = param(params, "resource")?;
+                let resources = self.resources(&request.method)?;
+                resources.update_resource(&id, resource).await?;
+                audit(resources, request, vec![id], auth).await;
+                Ok(json!({}))
+            }
+            Method::DeleteResource => {
+                let id: String = param(params, "id")?;
+                let resources = self.resources(&request.method)?;
+                resources.delete_resource(&id).await?;
+                audit(resources, request, vec![id], auth).await;
+                Ok(json!({}))
+            }
+            Method::BulkGetResources => {
+                let bulk: BulkGetRequest = decode(params.cloned().unwrap_or(Value::Null))?;
+                let ids: Vec<&str> = bulk.ids.iter().map(String::as_str).collect();
+                Ok(serde_json::to_value(
+                    self.resources(&request.method)?
+                        .bulk_get_resources(&ids, bulk.if_none_match.as_ref())
+                        .await?,
+                )?)
+            }
+            Method::ReadResources => {
+                let read: ReadResourcesRequest = decode(params.cloned().unwrap_or(Value::Null))?;
+                Ok(serde_json::to_value(
+                    self.resources(&request.method)?
+                        .read_resources(&read.ids)
+                        .await?,
+                )?)
+            }
+            Method::ResourceTransaction => {
+                let transaction: ResourceTransactionRequest =
+                    decode(params.cloned().unwrap_or(Value::Null))?;
+                let resources = self.resources(&request.method)?;
+                if !resources.capabilities().transactions {
+                    return Err(method_not_found(&request.method));
+                }
+                let ids = transaction
+                    .operations
+                    .iter()
+                    .map(|operation| operation.resource_id().to_string())
+                    .collect();
+                resources.transaction(transaction.operations).await?;
+                audit(resources, request, ids, auth).await;
+                Ok(json!({}))
+            }
+            Method::ListResourceTemplates => {
+                let resources = self.resources(&request.method)?;
+                if !resources.capabilities().templates {
+                    return Err(method_not_found(&request.method));
+                }
+                let templates = resources.list_resource_templates().await?;
+                Ok(serde_json::to_value(ListResourceTemplatesResult {
+                    templates,
+                })?)
+            }
+
+            Method::ListPrompts => {
+                let cursor: Option<String> = optional_param(params, "cursor")?;
+                let page = self
+                    .prompts(&request.method)?
+                    .list_prompts_page(cursor.as_deref())
+                    .await?;
+                page_result("prompts", page)
+            }
+            Method::GetPrompt => {
+                let id: String = param(params, "id")?;
+                let arguments: Option<Value> = optional_param(params, "arguments")?;
+                let result = self
+                    .prompts(&request.method)?
+                    .get_prompt_messages(&id, arguments)
+                    .await?;
+                Ok(serde_json::to_value(result)?)
+            }
+            Method::ExecutePrompt => {
+                let id: String = param(params, "id")?;
+                let arguments: Option<Value> = optional_param(params, "params")?;
+                let prompts = self.prompts(&request.method)?;
+                prompts
+                    .get_prompt(&id)
+                    .await?
+                    .check_params(arguments.as_ref())?;
+                prompts
+                    .execute_prompt_with_context(
+                        &id,
+                        arguments,
+                        &PromptExecutionContext {
+                            meta,
+                            auth: auth.cloned(),
+                            operation_id: operation,
+                        },
+                    )
+                    .await
+            }
+            Method::CreatePrompt => {
+                let prompt: Prompt = param(params, "prompt")?;
+                self.prompts(&request.method)?.create_prompt(prompt).await?;
+                self.events.publish(ServerEvent::PromptChanged);
+                Ok(json!({}))
+            }
+            Method::DeletePrompt => {
+                let id: String = param(params, "id")?;
+                self.prompts(&request.method)?.delete_prompt(&id).await?;
+                self.events.publish(ServerEvent::PromptChanged);
+                Ok(json!({}))
+            }
+
+            Method::Complete => {
+                let provider = self
+                    .completion
+                    .as_ref()
+                    .ok_or_else(|| method_not_found(&request.method))?;
+                let params: CompleteParams = decode(params.cloned().unwrap_or(Value::Null))?;
+                let result: CompleteResult = provider.complete(params).await?;
+                Ok(serde_json::to_value(result)?)
+            }
+
+            _ => Err(method_not_found(&request.method)),
+        }
+    }
+
+    fn tools(&self, method: &str) -> Result<&Arc<dyn ToolManager>> {
+        self.tools.as_ref().ok_or_else(|| method_not_found(method))
+    }
+
+    fn resources(&self, method: &str) -> Result<&Arc<dyn ResourceManager>> {
+        self.resources
+            .as_ref()
+            .ok_or_else(|| method_not_found(method))
+    }
+
+    fn prompts(&self, method: &str) -> Result<&Arc<dyn PromptManager>> {
+        self.prompts
+            .as_ref()
+            .ok_or_else(|| method_not_found(method))
+    }
+}
+
+/// Answer a batch with the given request and notification handlers
+///
+/// Shared by `Dispatcher::handle_batch` and `McpServer`, which answers requests
+/// through its deduplication cache.
+pub(super) async fn answer_batch<'a, R, RF, N, NF>(
+    batch: &'a [Message],
+    respond: R,
+    notify: N,
+) -> Option<Message>
+where
+    R: Fn(&'a Request) -> RF,
+    RF: Future<Output = Option<Response>>,
+    N: Fn(&'a Notification) -> NF,
+    NF: Future<Output = ()>,
+{
+    if batch.is_empty() {
+        let error = ResponseError::from(invalid_request("Empty batch"));
+        return Some(Message::Response(Response::error(error, RequestId::Null)));
+    }
+
+    let (respond, notify) = (&respond, &notify);
+    let answers = futures::future::join_all(batch.iter().map(|message| async move {
+        match message {
+            Message::Request(request) => respond(request).await,
+            Message::Notification(notification) => {
+                notify(notification).await;
+                None
+            }
+            Message::Response(response) => {
+                tracing::trace!(id = ?response.id, "ignoring unsolicited response in batch");
+                None
+            }
+            Message::Batch(_) => {
+                let error = ResponseError::from(invalid_request("Nested batch"));
+                Some(Response::error(error, RequestId::Null))
+            }
+        }
+    }))
+    .await;
+
+    let responses: Vec<Message> = answers
+        .into_iter()
+        .flatten()
+        .map(Message::Response)
+        .collect();
+    (!responses.is_empty()).then_some(Message::Batch(responses))
+}
+
+fn invalid_request(message: &str) -> Error {
+    Error::JsonRpc {
+        code: error_codes::INVALID_REQUEST,
+        message: message.to_string(),
+    }
+}
+
+fn method_not_found(method: &str) -> Error {
+    Error::JsonRpc {
+        code: error_codes::METHOD_NOT_FOUND,
+        message: format!("Method not found: {}", method),
+    }
+}
+
+fn invalid_params(message: String) -> Error {
+    Error::JsonRpc {
+        code: error_codes::INVALID_PARAMS,
+        message,
+    }
+}
+
+/// Result of a `*/list` request: the page's items under `key`, plus `nextCursor`
+/// unless this is the last page
+fn page_result<T: Serialize>(key: &str, page: Page<T>) -> Result<Value> {
+    let mut result = serde_json::Map::new();
+    result.insert(key.to_string(), serde_json::to_value(page.items)?);
+    if let Some(cursor) = page.next_cursor {
+        result.insert("nextCursor".to_string(), Value::String(cursor));
+    }
+    Ok(Value::Object(result))
+}
+
+/// Tell `resources` that `request` changed the resources with the given IDs
+async fn audit(
+    resources: &Arc<dyn ResourceManager>,
+    request: &Request,
+    resource_ids: Vec<String>,
+    auth: Option<&AuthContext>,
+) {
+    resources
+        .audit(ResourceAuditEntry {
+            method: request.method.clone(),
+            resource_ids,
+            auth: auth.cloned(),
+        })
+        .await;
+}
+
+fn decode<T: DeserializeOwned>(value: Value) -> Result<T> {
+    serde_json::from_value(value).map_err(|e| invalid_params(format!("Invalid params: {}", e)))
+}
+
+/// Extract a required field from the request params
+fn param<T: DeserializeOwned>(params: Option<&Value>, key: &str) -> Result<T> {
+    optional_param(params, key)?
+        .ok_or_else(|| invalid_params(format!("Missing parameter: {}", key)))
+}
+
+/// Extract an optional field from the request params
+fn optional_param<T: DeserializeOwned>(params: Option<&Value>, key: &str) -> Result<Option<T>> {
+    match params.and_then(|params| params.get(key)) {
+        Some(value) => decode(value.clone()).map(Some),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::RequestId;
+    use crate::server::McpServer;
+    use crate::server_features::{
+        CompletionReference, Prompt, StaticCompletionProvider, ToolResult,
+    };
+    use crate::testing::MockResourceManager;
+    use async_trait::async_trait;
+
+    struct GreetingPrompts;
+
+    #[async_trait]
+    impl PromptManager for GreetingPrompts {
+        async fn list_prompts(&self) -> Result<Vec<Prompt>> {
+            Ok(vec![self.get_prompt("greet").await?])
+        }
+
+        async fn get_prompt(&self, id: &str) -> Result<Prompt> {
+            Ok(Prompt {
+                id: id.to_string(),
+                name: "Greet".to_string(),
+                description: "Greets someone".to_string(),
+                template: "Hello, {name}!".to_string(),
+                parameters: Some(json!({
+                    "type": "object",
+                    "properties": { "name": { "type": "string" } },
+                    "required": ["name"]
+                })),
+                arguments: Vec::new(),
+            })
+        }
+
+        async fn execute_prompt(&self, _id: &str, params: Option<Value>) -> Result<Value> {
+            let name = params.unwrap()["name"].as_str().unwrap().to_string();
+            Ok(json!(format!("Hello, {}!", name)))
+        }
+    }
+
+    /// Answers with the progress token and operation it was called with
+    struct TokenEcho;
+
+    #[async_trait]
+    impl ToolManager for TokenEcho {
+        async fn list_tools(&self) -> Result<Vec<crate::server_features::Tool>> {
+            Ok(vec![])
+        }
+
+        async fn get_tool(&self, id: &str) -> Result<crate::server_features::Tool> {
+            Err(method_not_found(id))
+        }
+
+        async fn execute_tool(&self, _id: &str, _params: Value) -> Result<ToolResult> {
+            unreachable!("the dispatcher passes a context")
+        }
+
+        async fn execute_tool_with_context(
+            &self,
+            _id: &str,
+            params: Value,
+            context: &ToolExecutionContext,
+        ) -> Result<ToolResult> {
+            Ok(json!({
+                "token": context.meta.progress_token,
+                "operation": context.operation_id,
+                "params": params
+            })
+            .into())
+        }
+
+        async fn cancel_tool(&self, _id: &str) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    fn request(method: Method, params: Value) -> Request {
+        Request::new(method, Some(params), RequestId::Number(1))
+    }
+
+    #[tokio::test]
+    async fn test_unregistered_feature_is_method_not_found() {
+        let dispatcher = McpServer::builder().build().dispatcher;
+        let response = dispatcher
+            .handle_request(&request(Method::ListTools, json!({})))
+            .await;
+        assert_eq!(response.error.unwrap().code, error_codes::METHOD_NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_batch_edge_cases() {
+        let dispatcher = McpServer::builder().build().dispatcher;
+
+        let answer = dispatcher.handle_batch(&[]).await;
+        assert!(matches!(
+            answer,
+            Some(Message::Response(Response { error: Some(ref e), .. }))
+                if e.code == error_codes::INVALID_REQUEST
+        ));
+
+        let notification = Message::Notification(Notification::new(Method::Initialized, None));
+        assert!(dispatcher
+            .handle_batch(&[notification.clone(), notification])
+            .await
+            .is_none());
+
+        let nested = Message::Batch(vec![Message::Request(Request::ping(RequestId::Number(1)))]);
+        let Some(Message::Batch(responses)) = dispatcher.handle_batch(&[nested]).await else {
+            panic!("expected a batch response");
+        };
+        assert!(matches!(
+            &responses[..],
+            [Message::Response(Response { error: Some(e), .. })]
+                if e.code == error_codes::INVALID_REQUEST
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_initialize_advertises_registered_features() {
+        let dispatcher = McpServer::builder()
+            .with_prompts(Arc::new(GreetingPrompts))
+            .build()
+            .dispatcher;
+        let response = dispatcher
+            .handle_request(&request(
+                Method::Initialize,
+                json!({
+                    "protocolVersion": PROTOCOL_VERSION,
+                    "clientInfo": { "name": "test", "version": "1.0.0" }
+                }),
+            ))
+            .await;
+
+        let result: InitializeResult = serde_json::from_value(response.result.unwrap()).unwrap();
+        // Read-only prompts never change, so there is nothing to announce
+        assert!(!result.capabilities.prompts.unwrap().list_changed);
+        assert!(result.capabilities.tools.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_request_meta_reaches_tool_context() {
+        let dispatcher = McpServer::builder()
+            .with_tools(Arc::new(TokenEcho))
+            .build()
+            .dispatcher;
+        let meta = RequestMeta {
+            progress_token: Some(RequestId::String("op-1".to_string())),
+            deadline: None,
+        };
+        let response = dispatcher
+            .handle_request(
+                &request(
+                    Method::ExecuteTool,
+                    json!({ "id": "echo", "params": { "a": 1 } }),
+                )
+                .with_meta(meta),
+            )
+            .await;
+        assert_eq!(
+            response.result.unwrap()["structuredContent"],
+            json!({ "token": "op-1", "operation": null, "params": { "a": 1 } })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_prompts_are_read_only_by_default() {
+        let events = EventBus::default();
+        let mut changes = events.subscribe();
+        let dispatcher = McpServer::builder()
+            .with_prompts(Arc::new(GreetingPrompts))
+            .with_event_bus(events)
+            .build()
+            .dispatcher;
+
+        let response = dispatcher
+            .handle_request(&request(Method::DeletePrompt, json!({ "id": "greet" })))
+            .await;
+        assert_eq!(response.error.unwrap().code, error_codes::METHOD_NOT_FOUND);
+        assert!(changes.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_execute_prompt_checks_params() {
+        let dispatcher = McpServer::builder()
+            .with_prompts(Arc::new(GreetingPrompts))
+            .build()
+            .dispatcher;
+
+        let invalid = dispatcher
+            .handle_request(&request(Method::ExecutePrompt, json!({ "id": "greet" })))
+            .await;
+        assert_eq!(invalid.error.unwrap().code, error_codes::INVALID_PARAMS);
+
+        let valid = dispatcher
+            .handle_request(&request(
+                Method::ExecutePrompt,
+                json!({ "id": "greet", "params": { "name": "Ada" } }),
+            ))
+            .await;
+        assert_eq!(valid.result.unwrap(), json!("Hello, Ada!"));
+    }
+
+    #[tokio::test]
+    async fn test_get_prompt_returns_rendered_messages() {
+        let dispatcher = McpServer::builder()
+            .with_prompts(Arc::new(GreetingPrompts))
+            .build()
+            .dispatcher;
+
+        let response = dispatcher
+            .handle_request(&request(
+                Method::GetPrompt,
+                json!({ "id": "greet", "arguments": { "name": "Ada" } }),
+            ))
+            .await;
+        assert_eq!(
+            response.result.unwrap(),
+            json!({
+                "description": "Greets someone",
+                "messages": [{
+                    "role": "user",
+                    "content": { "type": "text", "text": "Hello, Ada!" }
+                }]
+            })
+        );
+
+        let missing = dispatcher
+            .handle_request(&request(Method::GetPrompt, json!({ "id": "greet" })))
+            .await;
+        assert_eq!(missing.error.unwrap().code, error_codes::INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn test_operations_are_tracked() {
+        let dispatcher = McpServer::builder()
+            .with_tools(Arc::new(TokenEcho))
+            .build()
+            .dispatcher;
+        let id = OperationId::new();
+        let mut params = Some(json!({ "id": "echo" }));
+        id.attach(&mut params);
+
+        let request = Request::new(Method::ExecuteTool, params.clone(), RequestId::Number(1));
+        let response = dispatcher.handle_request(&request).await;
+        assert_eq!(
+            response.result.unwrap()["structuredContent"]["operation"],
+            json!(id)
+        );
+        assert_eq!(
+            dispatcher.operations().status(&id),
+            Some(OperationState::Complete)
+        );
+
+        let failed = OperationId::new();
+        let mut params = None;
+        failed.attach(&mut params);
+        let request = Request::new(Method::ListPrompts, params, RequestId::Number(2));
+        assert!(dispatcher.handle_request(&request).await.error.is_some());
+        assert_eq!(
+            dispatcher.operations().status(&failed),
+            Some(OperationState::Failed)
+        );
+
+        let other = OperationId::new();
+        let mut params = None;
+        other.attach(&mut params);
+        dispatcher
+            .handle_notification(&Notification::new(Method::Progress, params.clone()))
+            .await;
+        assert_eq!(
+            dispatcher.operations().status(&other),
+            Some(OperationState::InProgress)
+        );
+        dispatcher
+            .handle_notification(&Notification::new(Method::Cancel, params))
+            .await;
+        assert_eq!(
+            dispatcher.operations().status(&other),
+            Some(OperationState::Cancelled)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resource_changes_are_audited() {
+        let resources = Arc::new(MockResourceManager::new().with_transactions());
+        let dispatcher = McpServer::builder()
+            .with_resources(resources.clone())
+            .build()
+            .dispatcher;
+        let resource =
+            |id: &str| json!({ "id": id, "type_": "file", "metadata": {}, "content": null });
+        let auth = AuthContext::new("alice");
+
+        dispatcher
+            .try_handle_request(
+                &request(Method::CreateResource, json!({ "resource": resource("a") })),
+                Some(&auth),
+                None,
+            )
+            .await
+            .unwrap();
+        dispatcher
+            .try_handle_request(
+                &request(
+                    Method::ResourceTransaction,
+                    json!({ "operations": [
+                        { "create": resource("b") },
+                        { "delete": "a" }
+                    ] }),
+                ),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        // Failed changes are not audited
+        dispatcher
+            .try_handle_request(
+                &request(Method::DeleteResource, json!({ "id": "a" })),
+                Some(&auth),
+                None,
+            )
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            resources.audits(),
+            vec![
+                ResourceAuditEntry {
+                    method: "resources/create".to_string(),
+                    resource_ids: vec!["a".to_string()],
+                    auth: Some(auth),
+                },
+                ResourceAuditEntry {
+                    method: "resources/transaction".to_string(),
+                    resource_ids: vec!["b".to_string(), "a".to_string()],
+                    auth: None,
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_complete() {
+        let reference = CompletionReference::Prompt {
+            name: "greet".to_string(),
+        };
+        let provider = StaticCompletionProvider::new().with_values(
+            reference,
+            "name",
+            vec!["Ada".to_string(), "Alan".to_string(), "Grace".to_string()],
+        );
+        let dispatcher = McpServer::builder()
+            .with_completion(Arc::new(provider))
+            .build()
+            .dispatcher;
+
+        let response = dispatcher
+            .handle_request(&request(
+                Method::Complete,
+                json!({
+                    "ref": { "type": "ref/prompt", "name": "greet" },
+                    "argument": { "name": "name", "value": "A" }
+                }),
+            ))
+            .await;
+        assert_eq!(
+            response.result.unwrap()["completion"]["values"],
+            json!(["Ada", "Alan"])
+        );
+    }
+}
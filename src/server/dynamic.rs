@@ -0,0 +1,217 @@
+use async_trait::async_trait;
+use serde_json::Value;
+use std::sync::{Arc, RwLock};
+
+use super::{Broadcaster, McpServer, ServerBuilder, ServerPlugin, Session};
+use crate::{
+    protocol::{
+        error_codes, CapabilitiesChangedParams, Capability, CapabilityDiff, Method, Notification,
+        Request, ServerCapabilities,
+    },
+    Error, Result,
+};
+
+/// `McpServer` whose capabilities can change after clients have initialized
+///
+/// The advertised capabilities start out as those of the wrapped server. Adding or
+/// removing one updates them and then broadcasts
+/// `notifications/capabilities/changed` with the diff and the full new set;
+/// clients should treat that set as replacing the one from `initialize`.
+///
+/// Changes race with requests already in flight:
+/// - a request for a removed capability that the server has already started
+///   handling completes normally;
+/// - a request for a removed capability that arrives after the change fails with
+///   `METHOD_NOT_FOUND`, even if the client sent it before the notification
+///   reached it;
+/// - a client may not use an added capability until it has seen the notification,
+///   and the feature still needs a handler to do anything useful.
+pub struct DynamicCapabilityServer {
+    server: McpServer,
+    controller: CapabilityController,
+}
+
+impl DynamicCapabilityServer {
+    /// Build the server configured by `builder`
+    pub fn new(builder: ServerBuilder) -> Self {
+        let mut server = builder.build();
+        let current = Arc::new(RwLock::new(server.dispatcher.capabilities()));
+        // Nothing else holds the dispatcher until `run`
+        Arc::get_mut(&mut server.dispatcher)
+            .expect("dispatcher is not shared before the server runs")
+            .plugins
+            .push(Arc::new(CapabilityGate {
+                current: Arc::clone(&current),
+            }));
+
+        let controller = CapabilityController {
+            current,
+            broadcaster: server.broadcaster(),
+        };
+        Self { server, controller }
+    }
+
+    /// The wrapped server
+    pub fn server(&self) -> &McpServer {
+        &self.server
+    }
+
+    /// Handle for changing capabilities that stays usable after `run` consumes
+    /// the server
+    pub fn controller(&self) -> CapabilityController {
+        self.controller.clone()
+    }
+
+    /// Capabilities currently advertised
+    pub fn capabilities(&self) -> ServerCapabilities {
+        self.controller.capabilities()
+    }
+
+    /// See `CapabilityController::add_capability`
+    pub fn add_capability(&self, capability: Capability) -> Result<bool> {
+        self.controller.add_capability(capability)
+    }
+
+    /// See `CapabilityController::remove_capability`
+    pub fn remove_capability(&self, capability: Capability) -> Result<bool> {
+        self.controller.remove_capability(capability)
+    }
+
+    /// Serve requests; see `McpServer::run`
+    pub async fn run(self) -> Result<()> {
+        self.server.run().await
+    }
+}
+
+/// Handle for changing the capabilities of a `DynamicCapabilityServer`
+#[derive(Debug, Clone)]
+pub struct CapabilityController {
+    current: Arc<RwLock<ServerCapabilities>>,
+    broadcaster: Broadcaster,
+}
+
+impl CapabilityController {
+    /// Capabilities currently advertised
+    pub fn capabilities(&self) -> ServerCapabilities {
+        self.current.read().unwrap().clone()
+    }
+
+    /// Start advertising `capability`, notifying clients
+    ///
+    /// Returns whether anything changed; no notification is sent otherwise.
+    pub fn add_capability(&self, capability: Capability) -> Result<bool> {
+        self.update(|capabilities| capability.enable(capabilities))
+    }
+
+    /// Stop advertising `capability`, notifying clients
+    ///
+    /// Returns whether anything changed; no notification is sent otherwise.
+    pub fn remove_capability(&self, capability: Capability) -> Result<bool> {
+        self.update(|capabilities| capability.disable(capabilities))
+    }
+
+    fn update(&self, change: impl FnOnce(&mut ServerCapabilities)) -> Result<bool> {
+        let params = {
+            let mut current = self.current.write().unwrap();
+            let mut updated = current.clone();
+            change(&mut updated);
+            let diff = CapabilityDiff::compute(&current, &updated);
+            if diff.is_empty() {
+                return Ok(false);
+            }
+            *current = updated.clone();
+            CapabilitiesChangedParams {
+                diff,
+                capabilities: updated,
+            }
+        };
+
+        let notification = Notification::new(
+            Method::CapabilitiesChanged,
+            Some(serde_json::to_value(params)?),
+        );
+        self.broadcaster.broadcast(notification)?;
+        Ok(true)
+    }
+}
+
+/// Advertises the current capabilities and rejects requests for removed ones
+struct CapabilityGate {
+    current: Arc<RwLock<ServerCapabilities>>,
+}
+
+#[async_trait]
+impl ServerPlugin for CapabilityGate {
+    fn on_initialize(&self, capabilities: &mut ServerCapabilities) {
+        let current = self.current.read().unwrap();
+        for capability in Capability::ALL {
+            if capability.is_enabled(&current) {
+                if !capability.is_enabled(capabilities) {
+                    capability.enable(capabilities);
+                }
+            } else {
+                capability.disable(capabilities);
+            }
+        }
+    }
+
+    async fn on_request(&self, request: &Request, _session: &Session) -> Option<Result<Value>> {
+        let current = self.current.read().unwrap();
+        Capability::ALL
+            .into_iter()
+            .find(|capability| capability.covers(&request.method))
+            .filter(|capability| !capability.is_enabled(&current))
+            .map(|_| {
+                Err(Error::JsonRpc {
+                    code: error_codes::METHOD_NOT_FOUND,
+                    message: format!("Method not found: {}", request.method),
+                })
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::McpClient;
+    use crate::testing::MockToolManager;
+    use crate::transport::MemoryTransport;
+
+    #[tokio::test]
+    async fn test_capability_changes_are_announced() {
+        let (client, transport) = MemoryTransport::pair();
+        let server = DynamicCapabilityServer::new(
+            McpServer::builder()
+                .with_tools(Arc::new(MockToolManager::new()))
+                .with_transport(Box::new(transport)),
+        );
+        let controller = server.controller();
+        tokio::spawn(server.run());
+
+        let client = McpClient::connect(Box::new(client)).await.unwrap();
+        let mut changes = client
+            .notifications()
+            .subscribe("notifications/capabilities/changed");
+        client.request(Method::ListTools, None).await.unwrap();
+
+        assert!(!controller.add_capability(Capability::Tools).unwrap());
+        assert!(controller.remove_capability(Capability::Tools).unwrap());
+        let notification = changes.recv().await.unwrap();
+        let params: CapabilitiesChangedParams =
+            serde_json::from_value(notification.notification.params.unwrap()).unwrap();
+        assert_eq!(params.diff.removed, [Capability::Tools]);
+        assert!(params.capabilities.tools.is_none());
+
+        let err = client.request(Method::ListTools, None).await.unwrap_err();
+        assert!(
+            matches!(err, Error::JsonRpc { code, .. } if code == error_codes::METHOD_NOT_FOUND)
+        );
+        assert!(client
+            .server_capabilities()
+            .is_some_and(|capabilities| !capabilities.supports_tools()));
+
+        assert!(controller.add_capability(Capability::Tools).unwrap());
+        changes.recv().await.unwrap();
+        client.request(Method::ListTools, None).await.unwrap();
+    }
+}
@@ -0,0 +1,101 @@
+use async_trait::async_trait;
+
+use crate::{
+    protocol::{RequestId, ResponseError},
+    Error,
+};
+
+/// What an `McpServer` does about an error
+#[derive(Debug, Clone, PartialEq)]
+pub enum ErrorAction {
+    /// Keep serving the transport; a failed request is left unanswered
+    Continue,
+    /// Stop serving the transport, without answering a failed request
+    Shutdown,
+    /// Answer with this error and keep serving; receive errors are answered with
+    /// a `null` ID
+    SendErrorResponse(ResponseError),
+}
+
+/// Decides how an `McpServer` recovers from errors while serving a transport
+///
+/// Registered with `ServerBuilder::with_error_handler`; `DefaultErrorHandler` is
+/// used otherwise.
+#[async_trait]
+pub trait ErrorHandler: Send + Sync {
+    /// Called when receiving from the transport fails
+    async fn on_receive_error(&self, err: &Error) -> ErrorAction;
+
+    /// Called when a request to `method` with ID `id` fails
+    async fn on_handler_error(&self, method: &str, err: &Error, id: RequestId) -> ErrorAction;
+}
+
+/// Error handling used unless another handler is registered
+///
+/// Malformed messages are answered with a parse error and other unreadable ones
+/// skipped. The transport stops being served on `Error::Io`, `Error::Transport` and
+/// `Error::NotInitialized`, which is how transports report that the peer is gone.
+/// Failed requests are answered with their error, as an `INTERNAL_ERROR` unless it
+/// carries a JSON-RPC code.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultErrorHandler;
+
+#[async_trait]
+impl ErrorHandler for DefaultErrorHandler {
+    async fn on_receive_error(&self, err: &Error) -> ErrorAction {
+        match err {
+            Error::Parse(_) => ErrorAction::SendErrorResponse(ResponseError::from(err)),
+            Error::Io(_) | Error::Transport(_) | Error::NotInitialized => ErrorAction::Shutdown,
+            _ => ErrorAction::Continue,
+        }
+    }
+
+    async fn on_handler_error(&self, _method: &str, err: &Error, _id: RequestId) -> ErrorAction {
+        ErrorAction::SendErrorResponse(ResponseError::from(err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::error_codes;
+
+    #[tokio::test]
+    async fn test_default_receive_actions() {
+        let handler = DefaultErrorHandler;
+        match handler.on_receive_error(&Error::Parse("bad".into())).await {
+            ErrorAction::SendErrorResponse(error) => {
+                assert_eq!(error.code, error_codes::PARSE_ERROR)
+            }
+            other => panic!("unexpected action: {:?}", other),
+        }
+        assert_eq!(
+            handler
+                .on_receive_error(&Error::Protocol("unreadable".into()))
+                .await,
+            ErrorAction::Continue
+        );
+        let io = std::io::Error::new(std::io::ErrorKind::BrokenPipe, "closed");
+        assert_eq!(
+            handler.on_receive_error(&Error::Io(io)).await,
+            ErrorAction::Shutdown
+        );
+    }
+
+    #[tokio::test]
+    async fn test_default_handler_errors_are_answered() {
+        let action = DefaultErrorHandler
+            .on_handler_error(
+                "tools/execute",
+                &Error::Configuration("missing key".into()),
+                RequestId::Number(1),
+            )
+            .await;
+        match action {
+            ErrorAction::SendErrorResponse(error) => {
+                assert_eq!(error.code, error_codes::INTERNAL_ERROR)
+            }
+            other => panic!("unexpected action: {:?}", other),
+        }
+    }
+}
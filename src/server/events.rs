@@ -0,0 +1,78 @@
+use tokio::sync::broadcast;
+
+use crate::protocol::Method;
+
+/// Change to the server's feature set that connected clients should hear about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServerEvent {
+    /// The set of available prompts changed
+    PromptChanged,
+    /// The set of available tools changed
+    ToolChanged,
+    /// The set of available resources changed
+    ResourceChanged,
+}
+
+impl ServerEvent {
+    /// Notification method announcing this change to clients
+    pub fn list_changed_method(&self) -> Method {
+        match self {
+            ServerEvent::PromptChanged => Method::ListPromptsChanged,
+            ServerEvent::ToolChanged => Method::ListToolsChanged,
+            ServerEvent::ResourceChanged => Method::ListResourcesChanged,
+        }
+    }
+}
+
+/// Channel that feature managers publish `ServerEvent`s on
+///
+/// Cloning is cheap and every clone publishes to the same subscribers. A running
+/// `McpServer` subscribes to its bus and broadcasts the matching `list_changed`
+/// notification to all connected clients.
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<ServerEvent>,
+}
+
+impl EventBus {
+    /// Create a bus that buffers up to `capacity` events per slow subscriber
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    /// Publish an event, returning the number of subscribers it reached
+    pub fn publish(&self, event: ServerEvent) -> usize {
+        self.sender.send(event).unwrap_or(0)
+    }
+
+    /// Receive every event published from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<ServerEvent> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_publish_reaches_subscribers() {
+        let bus = EventBus::default();
+        assert_eq!(bus.publish(ServerEvent::ToolChanged), 0);
+
+        let mut events = bus.subscribe();
+        assert_eq!(bus.clone().publish(ServerEvent::PromptChanged), 1);
+        assert_eq!(events.try_recv().unwrap(), ServerEvent::PromptChanged);
+        assert_eq!(
+            ServerEvent::PromptChanged.list_changed_method().to_string(),
+            "notifications/prompts/list_changed"
+        );
+    }
+}
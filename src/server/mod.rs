@@ -0,0 +1,1826 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+mod dedup;
+mod dispatcher;
+mod dynamic;
+mod error_handler;
+mod events;
+mod plugin;
+
+pub use dedup::{
+    DeduplicationCache, DeduplicationStats, SessionId, DEFAULT_DEDUPLICATION_CAPACITY,
+};
+pub use dispatcher::Dispatcher;
+pub use dynamic::{CapabilityController, DynamicCapabilityServer};
+pub use error_handler::{DefaultErrorHandler, ErrorAction, ErrorHandler};
+pub use events::{EventBus, ServerEvent};
+pub use plugin::ServerPlugin;
+
+use crate::{
+    protocol::{
+        error_codes, ImplementationInfo, LogLevel, LoggingMessageParams, Message, Method,
+        Notification, OperationId, OperationState, OperationTracker, Request, RequestId, Response,
+        SetLevelParams,
+    },
+    server_features::{CompletionProvider, PromptManager, ResourceManager, ToolManager},
+    transport::{ClientFilter, MessageOrigin, Transport},
+    Error, Result,
+};
+
+/// High-level MCP server
+///
+/// Serves one set of feature handlers over any number of transports. Each transport
+/// is driven concurrently and responses are sent back on the transport the request
+/// arrived on.
+pub struct McpServer {
+    dispatcher: Arc<Dispatcher>,
+    transports: Vec<Box<dyn Transport>>,
+    broadcaster: Broadcaster,
+    events: EventBus,
+    deduplication: Option<Arc<DeduplicationCache>>,
+    error_handler: Arc<dyn ErrorHandler>,
+}
+
+/// Handle for sending notifications to every transport a running server serves
+///
+/// Obtained from `McpServer::broadcaster`; it stays usable after `run` consumes the
+/// server.
+#[derive(Debug, Clone)]
+pub struct Broadcaster {
+    sender: broadcast::Sender<Notification>,
+}
+
+impl Broadcaster {
+    fn new() -> Self {
+        let (sender, _) = broadcast::channel(64);
+        Self { sender }
+    }
+
+    /// Queue a notification on every transport currently being served
+    ///
+    /// Returns the number of transports it was queued on, which is zero while the
+    /// server is not running. Each transport delivers it to all of its clients.
+    pub fn broadcast(&self, notification: Notification) -> Result<usize> {
+        Ok(self.sender.send(notification).unwrap_or(0))
+    }
+
+    /// Broadcast a `notifications/message` log message
+    ///
+    /// Transports whose client asked for a higher level with `logging/setLevel` drop it.
+    pub fn log(&self, level: LogLevel, logger: Option<&str>, data: Value) -> Result<usize> {
+        let params = LoggingMessageParams {
+            level,
+            logger: logger.map(str::to_string),
+            data,
+        };
+        self.broadcast(params.to_notification()?)
+    }
+}
+
+impl McpServer {
+    /// Start configuring a server
+    pub fn builder() -> ServerBuilder {
+        ServerBuilder::default()
+    }
+
+    /// Register an additional transport to serve on
+    pub fn add_transport(&mut self, transport: Box<dyn Transport>) {
+        self.transports.push(transport);
+    }
+
+    /// Dispatcher shared by all transports
+    pub fn dispatcher(&self) -> &Dispatcher {
+        &self.dispatcher
+    }
+
+    /// Current state of an operation started by a request carrying `_operationId`
+    pub fn operation_status(&self, id: &OperationId) -> Option<OperationState> {
+        self.dispatcher.operations().status(id)
+    }
+
+    /// Handle to the operation tracker that stays usable after `run` consumes the server
+    pub fn operations(&self) -> Arc<OperationTracker> {
+        Arc::clone(self.dispatcher.operations())
+    }
+
+    /// Send a notification to every connected client
+    ///
+    /// See `Broadcaster::broadcast`; use `broadcaster` to keep broadcasting once
+    /// `run` has consumed the server.
+    pub fn broadcast(&self, notification: Notification) -> Result<usize> {
+        self.broadcaster.broadcast(notification)
+    }
+
+    /// Handle for broadcasting notifications while the server runs
+    pub fn broadcaster(&self) -> Broadcaster {
+        self.broadcaster.clone()
+    }
+
+    /// Cache replaying responses to redelivered requests, if enabled
+    pub fn deduplication(&self) -> Option<Arc<DeduplicationCache>> {
+        self.deduplication.clone()
+    }
+
+    /// Bus whose events are announced to clients as `list_changed` notifications
+    pub fn events(&self) -> EventBus {
+        self.events.clone()
+    }
+
+    /// Initialize every transport and serve requests until all of them are closed
+    ///
+    /// A transport stops being served when receiving from it fails (typically because
+    /// the peer disconnected) or when its client sends `exit`. Transports are closed
+    /// once all of them have stopped.
+    ///
+    /// If a transport fails to initialize, the ones initialized before it are closed
+    /// and the error is returned without serving any of them.
+    pub async fn run(self) -> Result<()> {
+        let mut transports: Vec<Box<dyn Transport>> = Vec::with_capacity(self.transports.len());
+        for mut transport in self.transports {
+            if let Err(e) = transport.initialize().await {
+                for mut initialized in transports {
+                    if let Err(e) = initialized.close().await {
+                        tracing::warn!("failed to close transport: {}", e);
+                    }
+                }
+                return Err(e);
+            }
+            transports.push(transport);
+        }
+
+        let announcer = tokio::spawn(Self::announce_events(
+            self.events.subscribe(),
+            self.broadcaster.clone(),
+        ));
+        let mut tasks = Vec::with_capacity(transports.len());
+        for (session, transport) in (0..).zip(transports) {
+            let transport = Arc::new(transport);
+            let session = Session {
+                id: session,
+                transport: Arc::clone(&transport) as Arc<dyn Transport>,
+                dispatcher: Arc::clone(&self.dispatcher),
+                deduplication: self.deduplication.clone(),
+                error_handler: Arc::clone(&self.error_handler),
+                log_levels: Arc::default(),
+                stopping: AtomicBool::new(false),
+            };
+            tasks.push(tokio::spawn(Self::serve(
+                transport,
+                session,
+                self.broadcaster.sender.subscribe(),
+            )));
+        }
+
+        let mut result = Ok(());
+        for task in tasks {
+            let closed = match task.await.map(Arc::try_unwrap) {
+                Ok(Ok(mut transport)) => transport.close().await,
+                Ok(Err(_)) => Err(Error::Transport("Transport is still in use".into())),
+                Err(e) => Err(Error::Transport(format!("Transport task failed: {}", e))),
+            };
+            if result.is_ok() {
+                result = closed;
+            }
+        }
+        announcer.abort();
+        result
+    }
+
+    async fn announce_events(
+        mut events: broadcast::Receiver<ServerEvent>,
+        broadcaster: Broadcaster,
+    ) {
+        loop {
+            match events.recv().await {
+                Ok(event) => {
+                    let notification = Notification::new(event.list_changed_method(), None);
+                    let _ = broadcaster.broadcast(notification);
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "dropped server events");
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    }
+
+    /// Serve one transport, handing it back once its session has ended
+    async fn serve(
+        transport: Arc<Box<dyn Transport>>,
+        session: Session,
+        mut broadcasts: broadcast::Receiver<Notification>,
+    ) -> Arc<Box<dyn Transport>> {
+        loop {
+            // Both receives are cancel-safe, so losing the race drops no message.
+            // `run` holds a sender until every transport stops, so the broadcast
+            // channel only closes once serving is over anyway.
+            let received = tokio::select! {
+                broadcast = broadcasts.recv() => {
+                    match broadcast {
+                        Ok(notification) => {
+                            let accept = session.filter(&notification);
+                            if let Err(e) = transport.send_filtered(notification, accept).await {
+                                tracing::warn!("failed to send broadcast: {}", e);
+                                break;
+                            }
+                        }
+                        Err(RecvError::Lagged(skipped)) => {
+                            tracing::warn!(skipped, "dropped broadcast notifications");
+                        }
+                        Err(RecvError::Closed) => break,
+                    }
+                    continue;
+                }
+                received = transport.receive_with_origin() => received,
+            };
+            match received {
+                Ok((Message::Request(request), origin)) => {
+                    if let Some(response) = session.respond(&request, &origin).await {
+                        if let Err(e) = transport.send(Message::Response(response)).await {
+                            tracing::warn!("failed to send response: {}", e);
+                            break;
+                        }
+                    }
+                    if session.is_stopping() {
+                        tracing::debug!("error handler stopped serving transport");
+                        break;
+                    }
+                }
+                Ok((Message::Notification(notification), _)) => {
+                    session.notify(&notification).await;
+                    if notification.method == Method::Exit.to_string() {
+                        break;
+                    }
+                }
+                Ok((Message::Response(response), _)) => {
+                    tracing::trace!(id = ?response.id, "ignoring unsolicited response");
+                }
+                Ok((Message::Batch(batch), origin)) => {
+                    if let Some(answer) = session.respond_batch(&batch, &origin).await {
+                        if let Err(e) = transport.send(answer).await {
+                            tracing::warn!("failed to send batch response: {}", e);
+                            break;
+                        }
+                    }
+                    let exit = Method::Exit.to_string();
+                    if session.is_stopping()
+                        || batch
+                            .iter()
+                            .any(|m| matches!(m, Message::Notification(n) if n.method == exit))
+                    {
+                        break;
+                    }
+                }
+                Err(e) => match session.error_handler.on_receive_error(&e).await {
+                    ErrorAction::Continue => {
+                        tracing::warn!("skipping unreadable message: {}", e);
+                    }
+                    ErrorAction::SendErrorResponse(error) => {
+                        tracing::debug!("answering unreadable message: {}", e);
+                        let response = Response::error(error, RequestId::Null);
+                        if let Err(e) = transport.send(Message::Response(response)).await {
+                            tracing::warn!("failed to send error response: {}", e);
+                            break;
+                        }
+                    }
+                    ErrorAction::Shutdown => {
+                        tracing::debug!("stopped serving transport: {}", e);
+                        break;
+                    }
+                },
+            }
+        }
+        transport
+    }
+}
+
+/// State of one served transport
+pub struct Session {
+    id: SessionId,
+    /// Transport being served, over which tools report progress
+    transport: Arc<dyn Transport>,
+    dispatcher: Arc<Dispatcher>,
+    deduplication: Option<Arc<DeduplicationCache>>,
+    error_handler: Arc<dyn ErrorHandler>,
+    /// Levels set with `logging/setLevel`, by the client that set them
+    log_levels: Arc<std::sync::Mutex<HashMap<Option<u64>, LogLevel>>>,
+    /// Set once the error handler asked to stop serving the transport
+    stopping: AtomicBool,
+}
+
+impl Session {
+    /// Identifies the transport this session serves
+    pub fn id(&self) -> SessionId {
+        self.id
+    }
+
+    /// Least severe log level a client asked for with `logging/setLevel`
+    ///
+    /// `client` is the `MessageOrigin::client_id` of the client, `None` on
+    /// transports serving a single client.
+    pub fn log_level(&self, client: Option<u64>) -> Option<LogLevel> {
+        self.log_levels.lock().unwrap().get(&client).copied()
+    }
+
+    /// Clients a broadcast notification should reach
+    ///
+    /// Each client is spared log messages below the level it set with
+    /// `logging/setLevel`.
+    fn filter(&self, notification: &Notification) -> ClientFilter {
+        let Some(level) = LoggingMessageParams::level_of(notification) else {
+            return Arc::new(|_| true);
+        };
+        let levels = Arc::clone(&self.log_levels);
+        Arc::new(move |client| {
+            levels
+                .lock()
+                .unwrap()
+                .get(&client)
+                .is_none_or(|minimum| level >= *minimum)
+        })
+    }
+
+    /// Store the level `client` requested with `logging/setLevel`
+    fn set_log_level(&self, request: &Request, client: Option<u64>) -> Result<Value> {
+        let params: SetLevelParams =
+            serde_json::from_value(request.params.clone().unwrap_or(Value::Null)).map_err(|e| {
+                Error::JsonRpc {
+                    code: error_codes::INVALID_PARAMS,
+                    message: format!("Invalid logging/setLevel params: {}", e),
+                }
+            })?;
+        self.log_levels.lock().unwrap().insert(client, params.level);
+        Ok(json!({}))
+    }
+
+    /// Whether the error handler asked to stop serving the transport
+    fn is_stopping(&self) -> bool {
+        self.stopping.load(Ordering::SeqCst)
+    }
+
+    /// Answer a request, replaying the stored response if it was seen recently
+    ///
+    /// Returns `None` when the error handler chose not to answer.
+    async fn respond(&self, request: &Request, origin: &MessageOrigin) -> Option<Response> {
+        let cache = match &self.deduplication {
+            Some(cache) if request.id != RequestId::Null => cache,
+            _ => return self.handle_request(request, origin).await,
+        };
+        if let Some(response) = cache.get(self.id, origin.client_id, &request.id) {
+            tracing::debug!(id = ?request.id, "replaying response to duplicate request");
+            return Some(response);
+        }
+
+        let response = self.handle_request(request, origin).await?;
+        cache.insert(
+            self.id,
+            origin.client_id,
+            request.id.clone(),
+            response.clone(),
+        );
+        Some(response)
+    }
+
+    /// Answer a request, letting the error handler decide what to do if it fails
+    async fn handle_request(&self, request: &Request, origin: &MessageOrigin) -> Option<Response> {
+        let err = match self.result(request, origin).await {
+            Ok(result) => return Some(Response::success(result, request.id.clone())),
+            Err(err) => err,
+        };
+        let action = self
+            .error_handler
+            .on_handler_error(&request.method, &err, request.id.clone())
+            .await;
+        match action {
+            ErrorAction::Continue => None,
+            ErrorAction::Shutdown => {
+                self.stopping.store(true, Ordering::SeqCst);
+                None
+            }
+            ErrorAction::SendErrorResponse(error) => {
+                Some(Response::error(error, request.id.clone()))
+            }
+        }
+    }
+
+    /// Result of the first plugin that takes the request, or of the dispatcher
+    async fn result(&self, request: &Request, origin: &MessageOrigin) -> Result<Value> {
+        for plugin in self.dispatcher.plugins() {
+            if let Some(result) = plugin.on_request(request, self).await {
+                return result;
+            }
+        }
+        // The level belongs to the client, not the shared dispatcher
+        if request.method == Method::SetLogLevel.to_string() {
+            return self.set_log_level(request, origin.client_id);
+        }
+        self.dispatcher
+            .try_handle_request(request, origin.auth.as_ref(), Some(&self.transport))
+            .await
+    }
+
+    /// Let every plugin observe a notification, then handle it
+    async fn notify(&self, notification: &Notification) {
+        for plugin in self.dispatcher.plugins() {
+            plugin.on_notification(notification, self).await;
+        }
+        self.dispatcher.handle_notification(notification).await;
+    }
+
+    /// Answer a batch, handling its requests concurrently
+    async fn respond_batch(&self, batch: &[Message], origin: &MessageOrigin) -> Option<Message> {
+        dispatcher::answer_batch(
+            batch,
+            |request| self.respond(request, origin),
+            |notification| self.notify(notification),
+        )
+        .await
+    }
+}
+
+/// Builder for `McpServer`
+pub struct ServerBuilder {
+    server_info: ImplementationInfo,
+    tools: Option<Arc<dyn ToolManager>>,
+    resources: Option<Arc<dyn ResourceManager>>,
+    prompts: Option<Arc<dyn PromptManager>>,
+    completion: Option<Arc<dyn CompletionProvider>>,
+    transports: Vec<Box<dyn Transport>>,
+    events: EventBus,
+    deduplication: Option<Arc<DeduplicationCache>>,
+    plugins: Vec<Arc<dyn ServerPlugin>>,
+    error_handler: Arc<dyn ErrorHandler>,
+}
+
+impl Default for ServerBuilder {
+    fn default() -> Self {
+        Self {
+            server_info: ImplementationInfo {
+                name: env!("CARGO_PKG_NAME").to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+            },
+            tools: None,
+            resources: None,
+            prompts: None,
+            completion: None,
+            transports: Vec::new(),
+            events: EventBus::default(),
+            deduplication: None,
+            plugins: Vec::new(),
+            error_handler: Arc::new(DefaultErrorHandler),
+        }
+    }
+}
+
+impl ServerBuilder {
+    /// Set the implementation info reported in the `initialize` result
+    pub fn with_server_info(mut self, server_info: ImplementationInfo) -> Self {
+        self.server_info = server_info;
+        self
+    }
+
+    /// Serve tools from the given manager
+    pub fn with_tools(mut self, tools: Arc<dyn ToolManager>) -> Self {
+        self.tools = Some(tools);
+        self
+    }
+
+    /// Serve resources from the given manager
+    pub fn with_resources(mut self, resources: Arc<dyn ResourceManager>) -> Self {
+        self.resources = Some(resources);
+        self
+    }
+
+    /// Serve prompts from the given manager
+    pub fn with_prompts(mut self, prompts: Arc<dyn PromptManager>) -> Self {
+        self.prompts = Some(prompts);
+        self
+    }
+
+    /// Answer `completion/complete` with the given provider
+    pub fn with_completion(mut self, completion: Arc<dyn CompletionProvider>) -> Self {
+        self.completion = Some(completion);
+        self
+    }
+
+    /// Announce events published on the given bus to connected clients
+    ///
+    /// Share the bus with feature managers so they can publish `ServerEvent`s.
+    pub fn with_event_bus(mut self, events: EventBus) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Replay responses to requests redelivered within `window` instead of
+    /// executing them again
+    ///
+    /// Duplicates are detected per transport by request ID; see
+    /// `DeduplicationCache`.
+    pub fn with_deduplication(mut self, window: Duration) -> Self {
+        self.deduplication = Some(Arc::new(DeduplicationCache::new(window)));
+        self
+    }
+
+    /// Register a plugin
+    ///
+    /// Plugins see requests and notifications before the built-in handlers, in the
+    /// order they were registered; the first plugin answering a request wins.
+    pub fn with_plugin(mut self, plugin: impl ServerPlugin + 'static) -> Self {
+        self.plugins.push(Arc::new(plugin));
+        self
+    }
+
+    /// Decide how to recover from errors with `handler` instead of `DefaultErrorHandler`
+    pub fn with_error_handler(mut self, handler: impl ErrorHandler + 'static) -> Self {
+        self.error_handler = Arc::new(handler);
+        self
+    }
+
+    /// Serve on the given transport
+    pub fn with_transport(mut self, transport: Box<dyn Transport>) -> Self {
+        self.transports.push(transport);
+        self
+    }
+
+    /// Build the server
+    pub fn build(self) -> McpServer {
+        McpServer {
+            dispatcher: Arc::new(Dispatcher {
+                server_info: self.server_info,
+                tools: self.tools,
+                resources: self.resources,
+                prompts: self.prompts,
+                completion: self.completion,
+                operations: Arc::default(),
+                plugins: self.plugins,
+                events: self.events.clone(),
+            }),
+            transports: self.transports,
+            broadcaster: Broadcaster::new(),
+            events: self.events,
+            deduplication: self.deduplication,
+            error_handler: self.error_handler,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::McpClient;
+    use crate::client_features::sampling::SamplingMessage;
+    use crate::protocol::{error_codes, Request, RequestId};
+    use crate::protocol::{FeatureCapability, ResourceCapability};
+    use crate::server_features::{
+        ListToolsFilter, Prompt, Resource, ResourceContents, ResourceManager, ResourceOperation,
+        ResourceTemplate, Tool, ToolExecutionContext, ToolResult,
+    };
+    use crate::testing::{MockResourceManager, MockToolManager};
+    use crate::transport::{
+        stdio::server::{StdioServer, StdioServerConfig},
+        MemoryTransport,
+    };
+    use serde_json::{json, Value};
+    use tokio::io::{duplex, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+
+    fn echo_tool() -> Tool {
+        Tool {
+            id: "echo".to_string(),
+            name: "Echo".to_string(),
+            description: "Echoes its input".to_string(),
+            parameters: json!({ "type": "object" }),
+            requires_approval: false,
+            tags: Vec::new(),
+            estimated_duration: None,
+            side_effects: Vec::new(),
+        }
+    }
+
+    fn execute(id: i64) -> Request {
+        Request::new(
+            Method::ExecuteTool,
+            Some(json!({ "id": "echo", "params": { "from": id } })),
+            RequestId::Number(id),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_validate_tool_does_not_execute() {
+        let mut tool = echo_tool();
+        tool.parameters = json!({
+            "type": "object",
+            "properties": { "text": { "type": "string" } },
+            "required": ["text"]
+        });
+        let tools = Arc::new(MockToolManager::new().with_tool(tool, json!("ok")));
+        let (client, transport) = MemoryTransport::pair();
+        let server = McpServer::builder()
+            .with_tools(tools.clone())
+            .with_transport(Box::new(transport))
+            .build();
+        tokio::spawn(server.run());
+        let client = McpClient::connect(Box::new(client)).await.unwrap();
+
+        let violations = client
+            .validate_tool("echo", json!({ "text": "hi" }))
+            .await
+            .unwrap();
+        assert!(violations.is_empty());
+
+        let violations = client.validate_tool("echo", json!({})).await.unwrap();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].path, "$.text");
+
+        let raw = client
+            .request(
+                Method::ValidateTool,
+                Some(json!({ "id": "echo", "params": { "text": 1 } })),
+            )
+            .await
+            .unwrap();
+        assert_eq!(raw["valid"], json!(false));
+
+        assert!(client.validate_tool("missing", json!({})).await.is_err());
+        assert!(tools.executions().is_empty());
+    }
+
+    /// Prompts kept in memory, editable at runtime
+    #[derive(Default)]
+    struct EditablePrompts(std::sync::Mutex<Vec<Prompt>>);
+
+    #[async_trait::async_trait]
+    impl PromptManager for EditablePrompts {
+        async fn list_prompts(&self) -> Result<Vec<Prompt>> {
+            Ok(self.0.lock().unwrap().clone())
+        }
+
+        async fn get_prompt(&self, id: &str) -> Result<Prompt> {
+            self.list_prompts()
+                .await?
+                .into_iter()
+                .find(|prompt| prompt.id == id)
+                .ok_or_else(|| Error::Protocol(format!("Prompt not found: {}", id)))
+        }
+
+        async fn execute_prompt(&self, id: &str, params: Option<Value>) -> Result<Value> {
+            Ok(json!(self.get_prompt(id).await?.render(params.as_ref())))
+        }
+
+        async fn create_prompt(&self, prompt: Prompt) -> Result<()> {
+            self.0.lock().unwrap().push(prompt);
+            Ok(())
+        }
+
+        async fn delete_prompt(&self, id: &str) -> Result<()> {
+            self.get_prompt(id).await?;
+            self.0.lock().unwrap().retain(|prompt| prompt.id != id);
+            Ok(())
+        }
+
+        fn capabilities(&self) -> FeatureCapability {
+            FeatureCapability { list_changed: true }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prompts_are_managed_at_runtime() {
+        let (client, transport) = MemoryTransport::pair();
+        let server = McpServer::builder()
+            .with_prompts(Arc::new(EditablePrompts::default()))
+            .with_transport(Box::new(transport))
+            .build();
+        tokio::spawn(server.run());
+        let client = McpClient::connect(Box::new(client)).await.unwrap();
+        let info = ImplementationInfo {
+            name: "test".to_string(),
+            version: "1.0.0".to_string(),
+        };
+        let initialized = client
+            .initialize(info, crate::protocol::ClientCapabilities::default())
+            .await
+            .unwrap();
+        assert!(initialized.capabilities.supports_prompts_list_changed());
+        let mut changes = client
+            .notifications()
+            .subscribe("notifications/prompts/list_changed");
+
+        let prompt = Prompt {
+            id: "greet".to_string(),
+            name: "Greet".to_string(),
+            description: String::new(),
+            template: "Hello, {name}!".to_string(),
+            parameters: None,
+            arguments: Vec::new(),
+        };
+        client.create_prompt(prompt).await.unwrap();
+        changes.recv().await.unwrap();
+        let prompts = client.list_prompts(None).await.unwrap().items;
+        assert_eq!(prompts.len(), 1);
+        let rendered = client
+            .get_prompt("greet", Some(json!({ "name": "Ada" })))
+            .await
+            .unwrap();
+        assert_eq!(rendered.description, None);
+        assert_eq!(rendered.messages, [SamplingMessage::user("Hello, Ada!")]);
+
+        client.delete_prompt("greet").await.unwrap();
+        changes.recv().await.unwrap();
+        assert!(client.list_prompts(None).await.unwrap().items.is_empty());
+
+        // A failed change announces nothing
+        assert!(client.delete_prompt("greet").await.is_err());
+        assert!(changes.try_recv().is_err());
+    }
+
+    /// Stops serving on the first failed request
+    struct StopOnFailure;
+
+    #[async_trait::async_trait]
+    impl ErrorHandler for StopOnFailure {
+        async fn on_receive_error(&self, err: &Error) -> ErrorAction {
+            DefaultErrorHandler.on_receive_error(err).await
+        }
+
+        async fn on_handler_error(
+            &self,
+            _method: &str,
+            _err: &Error,
+            _id: RequestId,
+        ) -> ErrorAction {
+            ErrorAction::Shutdown
+        }
+    }
+
+    #[tokio::test]
+    async fn test_error_handler_can_stop_serving() {
+        let (client, transport) = MemoryTransport::pair();
+        let server = McpServer::builder()
+            .with_tools(Arc::new(MockToolManager::new()))
+            .with_error_handler(StopOnFailure)
+            .with_transport(Box::new(transport))
+            .build();
+        let running = tokio::spawn(server.run());
+
+        let ping = Request::new(Method::Ping, None, RequestId::Number(1));
+        client.send(Message::Request(ping)).await.unwrap();
+        assert!(matches!(
+            client.receive().await.unwrap(),
+            Message::Response(Response { error: None, .. })
+        ));
+
+        let missing = Request::new(
+            Method::ExecuteTool,
+            Some(json!({ "id": "missing" })),
+            RequestId::Number(2),
+        );
+        client.send(Message::Request(missing)).await.unwrap();
+        tokio::time::timeout(Duration::from_secs(5), running)
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert!(client.receive().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_log_messages_below_the_set_level_are_filtered() {
+        let (client, transport) = MemoryTransport::pair();
+        let server = McpServer::builder()
+            .with_transport(Box::new(transport))
+            .build();
+        let broadcaster = server.broadcaster();
+        tokio::spawn(server.run());
+
+        let client = McpClient::connect(Box::new(client)).await.unwrap();
+        let mut messages = client.notifications().subscribe("notifications/message");
+        client
+            .request(Method::SetLogLevel, Some(json!({ "level": "warning" })))
+            .await
+            .unwrap();
+
+        broadcaster
+            .log(LogLevel::Debug, None, json!("cache miss"))
+            .unwrap();
+        broadcaster
+            .log(LogLevel::Error, Some("db"), json!("connection lost"))
+            .unwrap();
+        let received = messages.recv().await.unwrap();
+        let params: LoggingMessageParams =
+            serde_json::from_value(received.notification.params.unwrap()).unwrap();
+        assert_eq!(params.level, LogLevel::Error);
+        assert_eq!(params.data, json!("connection lost"));
+        // A round trip proves nothing else was queued before it
+        client.request(Method::Ping, None).await.unwrap();
+        assert!(messages.try_recv().is_err());
+
+        let err = client
+            .request(Method::SetLogLevel, Some(json!({ "level": "loud" })))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::JsonRpc { code, .. } if code == error_codes::INVALID_PARAMS));
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_reaches_every_transport() {
+        let (first, first_transport) = MemoryTransport::pair();
+        let (second, second_transport) = MemoryTransport::pair();
+        let events = EventBus::default();
+        let server = McpServer::builder()
+            .with_event_bus(events.clone())
+            .with_transport(Box::new(first_transport))
+            .with_transport(Box::new(second_transport))
+            .build();
+        assert_eq!(
+            server
+                .broadcast(Notification::new(Method::Ping, None))
+                .unwrap(),
+            0
+        );
+        let broadcaster = server.broadcaster();
+        tokio::spawn(server.run());
+
+        let mut clients = Vec::new();
+        for transport in [first, second] {
+            let client = McpClient::connect(Box::new(transport)).await.unwrap();
+            // A round trip proves the transport is being served
+            client.request(Method::Ping, None).await.unwrap();
+            clients.push(client);
+        }
+
+        let mut tools = clients
+            .iter()
+            .map(|c| {
+                c.notifications()
+                    .subscribe("notifications/tools/list_changed")
+            })
+            .collect::<Vec<_>>();
+        let notification = Notification::new(Method::ListToolsChanged, None);
+        assert_eq!(broadcaster.broadcast(notification).unwrap(), 2);
+        for rx in &mut tools {
+            rx.recv().await.unwrap();
+        }
+
+        let mut prompts = clients[0]
+            .notifications()
+            .subscribe("notifications/prompts/list_changed");
+        assert_eq!(events.publish(ServerEvent::PromptChanged), 1);
+        let received = prompts.recv().await.unwrap();
+        assert_eq!(
+            received.notification.method,
+            "notifications/prompts/list_changed"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_requests_are_replayed() {
+        let tools = Arc::new(MockToolManager::new().with_tool(echo_tool(), json!("ok")));
+        let (client, transport) = MemoryTransport::pair();
+        let server = McpServer::builder()
+            .with_tools(tools.clone())
+            .with_deduplication(Duration::from_secs(60))
+            .with_transport(Box::new(transport))
+            .build();
+        let cache = server.deduplication().unwrap();
+        tokio::spawn(server.run());
+
+        let mut responses = Vec::new();
+        for request in [execute(1), execute(1), execute(2)] {
+            client.send(Message::Request(request)).await.unwrap();
+            match client.receive().await.unwrap() {
+                Message::Response(response) => responses.push(response),
+                other => panic!("unexpected message: {:?}", other),
+            }
+        }
+
+        assert_eq!(responses[0], responses[1]);
+        assert_eq!(responses[2].id, RequestId::Number(2));
+        assert_eq!(tools.executions().len(), 2);
+        assert_eq!(
+            cache.stats(),
+            DeduplicationStats {
+                hits: 1,
+                misses: 2,
+                evictions: 0
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_batch_is_answered_without_notification_responses() {
+        let tools = Arc::new(MockToolManager::new().with_tool(echo_tool(), json!("ok")));
+        let (client, transport) = MemoryTransport::pair();
+        let server = McpServer::builder()
+            .with_tools(tools.clone())
+            .with_transport(Box::new(transport))
+            .build();
+        tokio::spawn(server.run());
+
+        let batch = Message::Batch(vec![
+            Message::Request(execute(1)),
+            Message::Notification(Notification::new(Method::Initialized, None)),
+            Message::Request(execute(2)),
+        ]);
+        client.send(batch).await.unwrap();
+
+        let Message::Batch(responses) = client.receive().await.unwrap() else {
+            panic!("expected a batch response");
+        };
+        let ids: Vec<RequestId> = responses
+            .iter()
+            .map(|message| match message {
+                Message::Response(response) => response.id.clone(),
+                other => panic!("unexpected message: {:?}", other),
+            })
+            .collect();
+        assert_eq!(ids, vec![RequestId::Number(1), RequestId::Number(2)]);
+        assert_eq!(tools.executions().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_list_tools_filter() {
+        let tool = |id: &str, tags: &[&str], requires_approval: bool| Tool {
+            id: id.to_string(),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            requires_approval,
+            ..echo_tool()
+        };
+        let tools = MockToolManager::new()
+            .with_tool(tool("fs.read", &["fs"], false), json!("ok"))
+            .with_tool(tool("fs.write", &["fs", "danger"], true), json!("ok"))
+            .with_tool(tool("net.fetch", &["net"], false), json!("ok"));
+        let (client, transport) = MemoryTransport::pair();
+        let server = McpServer::builder()
+            .with_tools(Arc::new(tools))
+            .with_transport(Box::new(transport))
+            .build();
+        tokio::spawn(server.run());
+        let client = McpClient::connect(Box::new(client)).await.unwrap();
+
+        let ids = |tools: Vec<Tool>| tools.into_iter().map(|t| t.id).collect::<Vec<_>>();
+        let all = client.list_tools(ListToolsFilter::default()).await.unwrap();
+        assert_eq!(ids(all), ["fs.read", "fs.write", "net.fetch"]);
+
+        let filter = ListToolsFilter {
+            name_prefix: Some("fs.".to_string()),
+            tags: Some(vec!["fs".to_string()]),
+            requires_approval: Some(false),
+        };
+        assert_eq!(ids(client.list_tools(filter).await.unwrap()), ["fs.read"]);
+
+        let filter = ListToolsFilter {
+            tags: Some(vec!["fs".to_string(), "danger".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(ids(client.list_tools(filter).await.unwrap()), ["fs.write"]);
+
+        let unfiltered = client.request(Method::ListTools, None).await.unwrap();
+        assert_eq!(unfiltered["tools"].as_array().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_resources_are_streamed_page_by_page() {
+        use futures::StreamExt;
+
+        let resources = (0..15).fold(
+            MockResourceManager::new().with_page_size(5),
+            |manager, i| {
+                manager.with_resource(Resource {
+                    id: format!("r{}", i),
+                    type_: "text".to_string(),
+                    metadata: json!({}),
+                    content: Some(json!(i)),
+                })
+            },
+        );
+        let resources = Arc::new(resources);
+        let (client, transport) = MemoryTransport::pair();
+        let server = McpServer::builder()
+            .with_resources(resources.clone())
+            .with_transport(Box::new(transport))
+            .build();
+        tokio::spawn(server.run());
+        let client = McpClient::connect(Box::new(client)).await.unwrap();
+
+        let first = client.list_resources(None).await.unwrap();
+        assert_eq!(first.items.len(), 5);
+        assert_eq!(first.next_cursor.as_deref(), Some("5"));
+        assert_eq!(resources.page_requests(), 1);
+
+        // Nothing is fetched ahead of demand
+        let stream = client.list_all_resources_stream();
+        futures::pin_mut!(stream);
+        for _ in 0..5 {
+            stream.next().await.unwrap().unwrap();
+        }
+        assert_eq!(resources.page_requests(), 2);
+
+        let rest: Vec<Resource> = stream.map(|r| r.unwrap()).collect().await;
+        assert_eq!(rest.len(), 10);
+        assert_eq!(rest.last().unwrap().id, "r14");
+        assert_eq!(resources.page_requests(), 4);
+
+        let bad_cursor = client.list_resources(Some("x".to_string())).await;
+        assert!(
+            matches!(bad_cursor, Err(Error::JsonRpc { code, .. }) if code == error_codes::INVALID_PARAMS)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resource_transaction_requires_capability() {
+        let resource = |id: &str| Resource {
+            id: id.to_string(),
+            type_: "text".to_string(),
+            metadata: json!({}),
+            content: None,
+        };
+        let operations = || {
+            vec![
+                ResourceOperation::Create(resource("b")),
+                ResourceOperation::Delete("a".to_string()),
+            ]
+        };
+
+        for transactions in [false, true] {
+            let mut resources = MockResourceManager::new().with_resource(resource("a"));
+            if transactions {
+                resources = resources.with_transactions();
+            }
+            let resources = Arc::new(resources);
+            let (client, transport) = MemoryTransport::pair();
+            let server = McpServer::builder()
+                .with_resources(resources.clone())
+                .with_transport(Box::new(transport))
+                .build();
+            tokio::spawn(server.run());
+            let client = McpClient::connect(Box::new(client)).await.unwrap();
+
+            let result = client.resource_transaction(operations()).await;
+            if transactions {
+                result.unwrap();
+                let listed = resources.list_resources().await.unwrap();
+                assert_eq!(listed.len(), 1);
+                assert_eq!(listed[0].id, "b");
+            } else {
+                assert!(
+                    matches!(result, Err(Error::JsonRpc { code, .. }) if code == error_codes::METHOD_NOT_FOUND)
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_tool() {
+        let tools = Arc::new(MockToolManager::new().with_tool(echo_tool(), json!("ok")));
+        let (client, transport) = MemoryTransport::pair();
+        let server = McpServer::builder()
+            .with_tools(tools)
+            .with_transport(Box::new(transport))
+            .build();
+        tokio::spawn(server.run());
+        let client = McpClient::connect(Box::new(client)).await.unwrap();
+
+        let tool = client.get_tool("echo").await.unwrap();
+        assert_eq!(tool.name, "Echo");
+
+        match client.get_tool("missing").await {
+            Err(Error::JsonRpc { code, .. }) => assert_eq!(code, error_codes::METHOD_NOT_FOUND),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    struct TemplateResources;
+
+    #[async_trait::async_trait]
+    impl ResourceManager for TemplateResources {
+        async fn list_resources(&self) -> Result<Vec<Resource>> {
+            Ok(vec![])
+        }
+
+        async fn get_resource(&self, id: &str) -> Result<Resource> {
+            Err(Error::Protocol(format!("Unknown resource: {}", id)))
+        }
+
+        async fn create_resource(&self, _resource: Resource) -> Result<()> {
+            Ok(())
+        }
+
+        async fn update_resource(&self, _id: &str, _resource: Resource) -> Result<()> {
+            Ok(())
+        }
+
+        async fn delete_resource(&self, _id: &str) -> Result<()> {
+            Ok(())
+        }
+
+        async fn list_resource_templates(&self) -> Result<Vec<ResourceTemplate>> {
+            Ok(vec![ResourceTemplate {
+                uri_template: "file:///{path}".to_string(),
+                name: "Files".to_string(),
+                description: None,
+                mime_type: None,
+            }])
+        }
+
+        fn capabilities(&self) -> ResourceCapability {
+            ResourceCapability {
+                subscribe: false,
+                list_changed: false,
+                templates: true,
+                bulk_get: false,
+                transactions: false,
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_resource_templates() {
+        let (client, transport) = MemoryTransport::pair();
+        let server = McpServer::builder()
+            .with_resources(Arc::new(TemplateResources))
+            .with_transport(Box::new(transport))
+            .build();
+        tokio::spawn(server.run());
+        let client = McpClient::connect(Box::new(client)).await.unwrap();
+
+        let templates = client.list_resource_templates().await.unwrap();
+        assert_eq!(templates.len(), 1);
+        assert_eq!(templates[0].uri_template, "file:///{path}");
+    }
+
+    #[tokio::test]
+    async fn test_bulk_get_reports_per_resource_errors() {
+        let (client, transport) = MemoryTransport::pair();
+        let server = McpServer::builder()
+            .with_resources(Arc::new(TemplateResources))
+            .with_transport(Box::new(transport))
+            .build();
+        tokio::spawn(server.run());
+        let client = McpClient::connect(Box::new(client)).await.unwrap();
+
+        let result = client.bulk_get_resources(&["a", "b"], None).await.unwrap();
+        assert!(result.resources.is_empty());
+        assert_eq!(result.errors.len(), 2);
+        assert_eq!(result.errors["a"].code, error_codes::INTERNAL_ERROR);
+        assert!(result.errors["b"].message.contains("Unknown resource: b"));
+    }
+
+    /// Serves every `.png` resource as the same PNG bytes
+    struct ImageResources;
+
+    const PNG: &[u8] = &[0x89, b'P', b'N', b'G', 0x00, 0xff];
+
+    #[async_trait::async_trait]
+    impl ResourceManager for ImageResources {
+        async fn list_resources(&self) -> Result<Vec<Resource>> {
+            Ok(vec![])
+        }
+
+        async fn get_resource(&self, id: &str) -> Result<Resource> {
+            if !id.ends_with(".png") {
+                return Err(Error::Protocol(format!("Unknown resource: {}", id)));
+            }
+            Ok(Resource {
+                id: id.to_string(),
+                type_: "file".to_string(),
+                metadata: json!({}),
+                content: None,
+            })
+        }
+
+        async fn create_resource(&self, _resource: Resource) -> Result<()> {
+            Ok(())
+        }
+
+        async fn update_resource(&self, _id: &str, _resource: Resource) -> Result<()> {
+            Ok(())
+        }
+
+        async fn delete_resource(&self, _id: &str) -> Result<()> {
+            Ok(())
+        }
+
+        async fn resource_contents(&self, resource: &Resource) -> Result<ResourceContents> {
+            Ok(ResourceContents::from_bytes(&resource.id, "image/png", PNG))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_resource_returns_binary_contents() {
+        let (client, transport) = MemoryTransport::pair();
+        let server = McpServer::builder()
+            .with_resources(Arc::new(ImageResources))
+            .with_transport(Box::new(transport))
+            .build();
+        tokio::spawn(server.run());
+        let client = McpClient::connect(Box::new(client)).await.unwrap();
+
+        let result = client.get_resource("logo.png").await.unwrap();
+        assert_eq!(result.resource.id, "logo.png");
+        assert_eq!(result.contents[0].mime_type(), Some("image/png"));
+        assert_eq!(result.contents[0].to_bytes().unwrap(), PNG);
+
+        let mut bytes = Vec::new();
+        client
+            .stream_resource("logo.png")
+            .await
+            .unwrap()
+            .read_to_end(&mut bytes)
+            .await
+            .unwrap();
+        assert_eq!(bytes, PNG);
+    }
+
+    #[tokio::test]
+    async fn test_read_resources_reports_per_resource_errors() {
+        let (client, transport) = MemoryTransport::pair();
+        let server = McpServer::builder()
+            .with_resources(Arc::new(ImageResources))
+            .with_transport(Box::new(transport))
+            .build();
+        tokio::spawn(server.run());
+        let client = McpClient::connect(Box::new(client)).await.unwrap();
+
+        let ids = ["a.png", "missing", "b.png"].map(String::from);
+        let result = client.read_resources(&ids).await.unwrap();
+        let uris: Vec<&str> = result.contents.iter().map(|c| c.uri()).collect();
+        assert_eq!(uris, ["a.png", "b.png"]);
+        assert_eq!(result.contents[1].to_bytes().unwrap(), PNG);
+        assert_eq!(result.errors.len(), 1);
+        assert!(result.errors["missing"]
+            .message
+            .contains("Unknown resource"));
+    }
+
+    #[tokio::test]
+    async fn test_list_resource_templates_requires_resources() {
+        let (client, transport) = MemoryTransport::pair();
+        let server = McpServer::builder()
+            .with_transport(Box::new(transport))
+            .build();
+        tokio::spawn(server.run());
+        let client = McpClient::connect(Box::new(client)).await.unwrap();
+
+        match client.list_resource_templates().await {
+            Err(Error::JsonRpc { code, .. }) => assert_eq!(code, error_codes::METHOD_NOT_FOUND),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_resource_templates_requires_the_templates_capability() {
+        let (client, transport) = MemoryTransport::pair();
+        let server = McpServer::builder()
+            .with_resources(Arc::new(MockResourceManager::new()))
+            .with_transport(Box::new(transport))
+            .build();
+        tokio::spawn(server.run());
+        let client = McpClient::connect(Box::new(client)).await.unwrap();
+
+        match client.list_resource_templates().await {
+            Err(Error::JsonRpc { code, .. }) => assert_eq!(code, error_codes::METHOD_NOT_FOUND),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_malformed_line_is_answered_and_skipped() {
+        let tools = Arc::new(MockToolManager::new().with_tool(echo_tool(), json!("ok")));
+        let (mut stdin, server_stdin) = duplex(4096);
+        let (server_stdout, stdout) = duplex(4096);
+        let stdio = StdioServer::with_io(StdioServerConfig::default(), server_stdin, server_stdout);
+        let server = McpServer::builder()
+            .with_tools(tools)
+            .with_transport(stdio.into_transport())
+            .build();
+        tokio::spawn(server.run());
+
+        let line = serde_json::to_string(&Message::Request(execute(1))).unwrap();
+        stdin.write_all(b"{not json}\n").await.unwrap();
+        stdin.write_all(line.as_bytes()).await.unwrap();
+        stdin.write_all(b"\n").await.unwrap();
+
+        let mut stdout = BufReader::new(stdout);
+        let mut reply = String::new();
+        stdout.read_line(&mut reply).await.unwrap();
+        let reply: serde_json::Value = serde_json::from_str(&reply).unwrap();
+        assert_eq!(reply["id"], serde_json::Value::Null);
+        assert_eq!(reply["error"]["code"], error_codes::PARSE_ERROR);
+
+        let mut reply = String::new();
+        stdout.read_line(&mut reply).await.unwrap();
+        match serde_json::from_str(&reply).unwrap() {
+            Message::Response(response) => {
+                assert_eq!(response.id, RequestId::Number(1));
+                let result: ToolResult = serde_json::from_value(response.result.unwrap()).unwrap();
+                assert_eq!(result, ToolResult::text("ok"));
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_serves_stdio_and_memory_transports_together() {
+        let tools = Arc::new(MockToolManager::new().with_tool(echo_tool(), json!("ok")));
+
+        let (stdin, server_stdin) = duplex(4096);
+        let (server_stdout, stdout) = duplex(4096);
+        let stdio = StdioServer::with_io(StdioServerConfig::default(), server_stdin, server_stdout);
+        let (memory_client, memory_server) = MemoryTransport::pair();
+
+        let server = McpServer::builder()
+            .with_tools(tools.clone())
+            .with_transport(stdio.into_transport())
+            .with_transport(Box::new(memory_server))
+            .build();
+        let running = tokio::spawn(server.run());
+
+        // Request over stdio
+        let mut stdin = stdin;
+        let line = serde_json::to_string(&Message::Request(execute(1))).unwrap();
+        stdin.write_all(line.as_bytes()).await.unwrap();
+        stdin.write_all(b"\n").await.unwrap();
+        let mut stdout = BufReader::new(stdout);
+        let mut reply = String::new();
+        stdout.read_line(&mut reply).await.unwrap();
+        match serde_json::from_str(&reply).unwrap() {
+            Message::Response(response) => {
+                assert_eq!(response.id, RequestId::Number(1));
+                let result: ToolResult = serde_json::from_value(response.result.unwrap()).unwrap();
+                assert_eq!(result, ToolResult::text("ok"));
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+
+        // Request over the in-memory transport
+        memory_client
+            .send(Message::Request(execute(2)))
+            .await
+            .unwrap();
+        match memory_client.receive().await.unwrap() {
+            Message::Response(response) => assert_eq!(response.id, RequestId::Number(2)),
+            other => panic!("unexpected message: {:?}", other),
+        }
+
+        let executions = tools.executions();
+        assert_eq!(executions.len(), 2);
+        assert_eq!(executions[0].1, json!({ "from": 1 }));
+        assert_eq!(executions[1].1, json!({ "from": 2 }));
+
+        // The server stops once every peer has gone away
+        drop(stdin);
+        let mut memory_client = memory_client;
+        memory_client.close().await.unwrap();
+        running.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_serves_http_transport() {
+        use crate::transport::http::{
+            client::{HttpClient, HttpClientConfig},
+            server::{AxumHttpServer, HttpServerConfig},
+        };
+
+        let addr = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let tools = Arc::new(MockToolManager::new().with_tool(echo_tool(), json!("ok")));
+        let http = AxumHttpServer::new(HttpServerConfig {
+            addr,
+            ..Default::default()
+        });
+        let server = McpServer::builder()
+            .with_tools(tools.clone())
+            .with_transport(http.into_transport())
+            .build();
+        let broadcaster = server.broadcaster();
+        let running = tokio::spawn(server.run());
+        while tokio::net::TcpStream::connect(addr).await.is_err() {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        let client = HttpClient::new(HttpClientConfig {
+            base_url: format!("http://{}", addr),
+            ..Default::default()
+        })
+        .unwrap();
+        let client = McpClient::connect(client.into_transport()).await.unwrap();
+        let mut changes = client
+            .notifications()
+            .subscribe("notifications/tools/list_changed");
+
+        let result = client
+            .request(
+                Method::ExecuteTool,
+                Some(json!({ "id": "echo", "params": { "from": 1 } })),
+            )
+            .await
+            .unwrap();
+        let result: ToolResult = serde_json::from_value(result).unwrap();
+        assert_eq!(result, ToolResult::text("ok"));
+        assert_eq!(tools.executions().len(), 1);
+
+        broadcaster
+            .broadcast(Notification::tools_list_changed())
+            .unwrap();
+        changes.recv().await.unwrap();
+        assert!(!running.is_finished());
+        running.abort();
+    }
+
+    #[tokio::test]
+    async fn test_http_clients_may_use_the_same_request_ids() {
+        use crate::transport::http::{
+            client::{HttpClient, HttpClientConfig},
+            server::{AxumHttpServer, HttpServerConfig},
+        };
+
+        let addr = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let tools = Arc::new(MockToolManager::new().with_tool(echo_tool(), json!("ok")));
+        let http = AxumHttpServer::new(HttpServerConfig {
+            addr,
+            ..Default::default()
+        });
+        let server = McpServer::builder()
+            .with_tools(tools)
+            .with_transport(http.into_transport())
+            .build();
+        let running = tokio::spawn(server.run());
+        while tokio::net::TcpStream::connect(addr).await.is_err() {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        let connect = || async {
+            let client = HttpClient::new(HttpClientConfig {
+                base_url: format!("http://{}", addr),
+                ..Default::default()
+            })
+            .unwrap();
+            McpClient::connect(client.into_transport()).await.unwrap()
+        };
+        let (first, second) = tokio::join!(connect(), connect());
+
+        // Both clients number their first request 1
+        let (executed, pinged) = tokio::join!(
+            first.request(Method::ExecuteTool, Some(json!({ "id": "echo" }))),
+            second.request(Method::Ping, None)
+        );
+        let executed: ToolResult = serde_json::from_value(executed.unwrap()).unwrap();
+        assert_eq!(executed, ToolResult::text("ok"));
+        assert_eq!(pinged.unwrap(), json!({}));
+        running.abort();
+    }
+
+    #[tokio::test]
+    async fn test_deduplication_keeps_http_clients_apart() {
+        use crate::transport::http::{
+            client::{HttpClient, HttpClientConfig},
+            server::{AxumHttpServer, HttpServerConfig},
+        };
+
+        let addr = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let prompts = EditablePrompts::default();
+        prompts.0.lock().unwrap().push(Prompt {
+            id: "greet".to_string(),
+            name: "Greet".to_string(),
+            description: String::new(),
+            template: "Hello, {name}!".to_string(),
+            parameters: None,
+            arguments: Vec::new(),
+        });
+        let http = AxumHttpServer::new(HttpServerConfig {
+            addr,
+            ..Default::default()
+        });
+        let server = McpServer::builder()
+            .with_prompts(Arc::new(prompts))
+            .with_deduplication(Duration::from_secs(60))
+            .with_transport(http.into_transport())
+            .build();
+        let cache = server.deduplication().unwrap();
+        let running = tokio::spawn(server.run());
+        while tokio::net::TcpStream::connect(addr).await.is_err() {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        let connect = || async {
+            let client = HttpClient::new(HttpClientConfig {
+                base_url: format!("http://{}", addr),
+                ..Default::default()
+            })
+            .unwrap();
+            McpClient::connect(client.into_transport()).await.unwrap()
+        };
+        let greet = |name: &str| Some(json!({ "id": "greet", "params": { "name": name } }));
+
+        // Both requests have ID 1, but come from different clients
+        let ada = connect().await;
+        let ada = ada.request(Method::ExecutePrompt, greet("Ada")).await;
+        let bob = connect().await;
+        let bob = bob.request(Method::ExecutePrompt, greet("Bob")).await;
+        assert_eq!(ada.unwrap(), json!("Hello, Ada!"));
+        assert_eq!(bob.unwrap(), json!("Hello, Bob!"));
+        assert_eq!(cache.stats().hits, 0);
+        running.abort();
+    }
+
+    #[tokio::test]
+    async fn test_log_levels_are_set_per_http_client() {
+        use crate::transport::http::{
+            client::{HttpClient, HttpClientConfig},
+            server::{AxumHttpServer, HttpServerConfig},
+        };
+
+        let addr = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let http = AxumHttpServer::new(HttpServerConfig {
+            addr,
+            ..Default::default()
+        });
+        let server = McpServer::builder()
+            .with_transport(http.into_transport())
+            .build();
+        let broadcaster = server.broadcaster();
+        let running = tokio::spawn(server.run());
+        while tokio::net::TcpStream::connect(addr).await.is_err() {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        let connect = || async {
+            let client = HttpClient::new(HttpClientConfig {
+                base_url: format!("http://{}", addr),
+                ..Default::default()
+            })
+            .unwrap();
+            McpClient::connect(client.into_transport()).await.unwrap()
+        };
+        let quiet = connect().await;
+        let verbose = connect().await;
+        let mut quiet_messages = quiet.notifications().subscribe("notifications/message");
+        let mut verbose_messages = verbose.notifications().subscribe("notifications/message");
+        quiet
+            .request(Method::SetLogLevel, Some(json!({ "level": "error" })))
+            .await
+            .unwrap();
+
+        broadcaster
+            .log(LogLevel::Info, None, json!("cache miss"))
+            .unwrap();
+        broadcaster
+            .log(LogLevel::Error, None, json!("connection lost"))
+            .unwrap();
+        let level = |received: crate::client::SequencedNotification| {
+            let params: LoggingMessageParams =
+                serde_json::from_value(received.notification.params.unwrap()).unwrap();
+            params.level
+        };
+        assert_eq!(
+            level(verbose_messages.recv().await.unwrap()),
+            LogLevel::Info
+        );
+        assert_eq!(
+            level(verbose_messages.recv().await.unwrap()),
+            LogLevel::Error
+        );
+        assert_eq!(level(quiet_messages.recv().await.unwrap()), LogLevel::Error);
+        assert!(quiet_messages.try_recv().is_err());
+        running.abort();
+    }
+
+    /// Transport recording whether it was closed, optionally failing to initialize
+    struct Probe {
+        fail: bool,
+        closed: Arc<AtomicBool>,
+    }
+
+    #[async_trait::async_trait]
+    impl Transport for Probe {
+        async fn initialize(&mut self) -> Result<()> {
+            match self.fail {
+                true => Err(Error::Transport("cannot bind".into())),
+                false => Ok(()),
+            }
+        }
+
+        async fn send(&self, _message: Message) -> Result<()> {
+            Ok(())
+        }
+
+        async fn receive(&self) -> Result<Message> {
+            std::future::pending().await
+        }
+
+        async fn close(&mut self) -> Result<()> {
+            self.closed.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_initialize_closes_initialized_transports() {
+        let closed = Arc::new(AtomicBool::new(false));
+        let server = McpServer::builder()
+            .with_transport(Box::new(Probe {
+                fail: false,
+                closed: closed.clone(),
+            }))
+            .with_transport(Box::new(Probe {
+                fail: true,
+                closed: Arc::default(),
+            }))
+            .build();
+
+        let result = tokio::time::timeout(Duration::from_secs(1), server.run()).await;
+        assert!(matches!(result, Ok(Err(Error::Transport(_)))));
+        assert!(closed.load(Ordering::SeqCst));
+    }
+
+    /// Answers every execution with the subject of the caller
+    struct WhoAmI;
+
+    #[async_trait::async_trait]
+    impl ToolManager for WhoAmI {
+        async fn list_tools(&self) -> Result<Vec<Tool>> {
+            Ok(vec![])
+        }
+
+        async fn get_tool(&self, id: &str) -> Result<Tool> {
+            Err(Error::Protocol(format!("Tool not found: {}", id)))
+        }
+
+        async fn execute_tool(&self, id: &str, params: Value) -> Result<ToolResult> {
+            self.execute_tool_with_context(id, params, &ToolExecutionContext::default())
+                .await
+        }
+
+        async fn execute_tool_with_context(
+            &self,
+            _id: &str,
+            _params: Value,
+            context: &ToolExecutionContext,
+        ) -> Result<ToolResult> {
+            let subject = context.auth.as_ref().map_or("anonymous", |a| &a.subject);
+            Ok(ToolResult::text(subject))
+        }
+
+        async fn cancel_tool(&self, _id: &str) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tools_see_the_authenticated_caller() {
+        use crate::transport::http::{
+            auth::AuthScheme,
+            client::{HttpClient, HttpClientConfig},
+            server::{AxumHttpServer, HttpServerConfig},
+        };
+
+        let addr = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let scheme = AuthScheme::Basic {
+            user: "alice".to_string(),
+            pass: "secret".to_string(),
+        };
+        let http = AxumHttpServer::new(HttpServerConfig {
+            addr,
+            auth_scheme: scheme.clone(),
+            ..Default::default()
+        });
+        let server = McpServer::builder()
+            .with_tools(Arc::new(WhoAmI))
+            .with_transport(http.into_transport())
+            .build();
+        let running = tokio::spawn(server.run());
+        while tokio::net::TcpStream::connect(addr).await.is_err() {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        let client = HttpClient::new(HttpClientConfig {
+            base_url: format!("http://{}", addr),
+            auth_scheme: scheme,
+            ..Default::default()
+        })
+        .unwrap();
+        let client = McpClient::connect(client.into_transport()).await.unwrap();
+        let result = client
+            .request(Method::ExecuteTool, Some(json!({ "id": "whoami" })))
+            .await
+            .unwrap();
+        let result: ToolResult = serde_json::from_value(result).unwrap();
+        assert_eq!(result, ToolResult::text("alice"));
+        running.abort();
+    }
+
+    #[tokio::test]
+    async fn test_unauthenticated_transports_have_no_caller() {
+        let (server_side, client_side) = MemoryTransport::pair();
+        let server = McpServer::builder()
+            .with_tools(Arc::new(WhoAmI))
+            .with_transport(Box::new(server_side))
+            .build();
+        let running = tokio::spawn(server.run());
+
+        let client = McpClient::connect(Box::new(client_side)).await.unwrap();
+        let result = client
+            .request(Method::ExecuteTool, Some(json!({ "id": "whoami" })))
+            .await
+            .unwrap();
+        let result: ToolResult = serde_json::from_value(result).unwrap();
+        assert_eq!(result, ToolResult::text("anonymous"));
+        running.abort();
+    }
+
+    /// Reports one progress update per step before answering
+    struct Counting;
+
+    #[async_trait::async_trait]
+    impl ToolManager for Counting {
+        async fn list_tools(&self) -> Result<Vec<Tool>> {
+            Ok(vec![])
+        }
+
+        async fn get_tool(&self, id: &str) -> Result<Tool> {
+            Err(Error::Protocol(format!("Tool not found: {}", id)))
+        }
+
+        async fn execute_tool(&self, id: &str, params: Value) -> Result<ToolResult> {
+            self.execute_tool_with_context(id, params, &ToolExecutionContext::default())
+                .await
+        }
+
+        async fn execute_tool_with_context(
+            &self,
+            _id: &str,
+            params: Value,
+            context: &ToolExecutionContext,
+        ) -> Result<ToolResult> {
+            let steps = params["steps"].as_u64().unwrap_or(0);
+            for step in 1..=steps {
+                context.progress.report(step, Some(steps), None).await?;
+            }
+            Ok(ToolResult::text("counted"))
+        }
+
+        async fn cancel_tool(&self, _id: &str) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tool_progress_reaches_the_client() {
+        let (client, transport) = MemoryTransport::pair();
+        let server = McpServer::builder()
+            .with_tools(Arc::new(Counting))
+            .with_transport(Box::new(transport))
+            .build();
+        let broadcaster = server.broadcaster();
+        let running = tokio::spawn(server.run());
+
+        let client = McpClient::connect(Box::new(client)).await.unwrap();
+        let mut routed = client.notifications().subscribe("$/progress");
+        let reports = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorder = Arc::clone(&reports);
+        let result = client
+            .execute_tool_with_progress("count", Some(json!({ "steps": 2 })), move |progress| {
+                recorder.lock().unwrap().push(progress);
+            })
+            .await
+            .unwrap();
+        let result: ToolResult = serde_json::from_value(result).unwrap();
+        assert_eq!(result, ToolResult::text("counted"));
+
+        let token = reports.lock().unwrap()[0].progress_token.clone();
+        let values: Vec<u64> = reports.lock().unwrap().iter().map(|p| p.progress).collect();
+        assert_eq!(values, vec![1, 2]);
+
+        // Progress for the same token after the result must not reach the callback
+        broadcaster
+            .broadcast(Notification::new(
+                Method::Progress,
+                Some(json!({ "progressToken": token, "progress": 3 })),
+            ))
+            .unwrap();
+        for _ in 0..3 {
+            routed.recv().await.unwrap();
+        }
+        assert_eq!(reports.lock().unwrap().len(), 2);
+        running.abort();
+    }
+}
@@ -0,0 +1,123 @@
+use async_trait::async_trait;
+use serde_json::Value;
+
+use super::Session;
+use crate::{
+    protocol::{Notification, Request, ServerCapabilities},
+    Result,
+};
+
+/// Extension adding features to an `McpServer` without changing its core
+///
+/// Plugins are registered with `ServerBuilder::with_plugin` and consulted in
+/// registration order. Every method has a default that does nothing, so a plugin
+/// only implements the hooks it needs.
+#[async_trait]
+pub trait ServerPlugin: Send + Sync {
+    /// Adjusts the capabilities advertised in the `initialize` result
+    fn on_initialize(&self, _capabilities: &mut ServerCapabilities) {}
+
+    /// Answers a request, or returns `None` to leave it to later plugins and the
+    /// built-in handlers
+    ///
+    /// An `Err` is sent to the client as an error response.
+    async fn on_request(&self, _request: &Request, _session: &Session) -> Option<Result<Value>> {
+        None
+    }
+
+    /// Observes a notification before the built-in handlers see it
+    async fn on_notification(&self, _notification: &Notification, _session: &Session) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{InitializeResult, Message, Method, RequestId, PROTOCOL_VERSION};
+    use crate::server::McpServer;
+    use crate::transport::{MemoryTransport, Transport};
+    use crate::Error;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    /// Counts notifications and answers `metrics/get`
+    #[derive(Default)]
+    struct MetricsPlugin {
+        notifications: AtomicU64,
+    }
+
+    #[async_trait]
+    impl ServerPlugin for MetricsPlugin {
+        fn on_initialize(&self, capabilities: &mut ServerCapabilities) {
+            capabilities.experimental = Some(json!({ "metrics": {} }));
+        }
+
+        async fn on_request(&self, request: &Request, session: &Session) -> Option<Result<Value>> {
+            (request.method == "metrics/get").then(|| {
+                Ok(json!({
+                    "session": session.id(),
+                    "notifications": self.notifications.load(Ordering::SeqCst),
+                }))
+            })
+        }
+
+        async fn on_notification(&self, _notification: &Notification, _session: &Session) {
+            self.notifications.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Also claims `metrics/get`, but is registered after `MetricsPlugin`
+    struct Shadow;
+
+    #[async_trait]
+    impl ServerPlugin for Shadow {
+        async fn on_request(&self, request: &Request, _session: &Session) -> Option<Result<Value>> {
+            (request.method == "metrics/get").then(|| Err(Error::Protocol("shadowed".into())))
+        }
+    }
+
+    async fn call(client: &MemoryTransport, method: &str, params: Option<Value>, id: i64) -> Value {
+        let request = Request {
+            jsonrpc: "2.0".to_string(),
+            method: method.to_string(),
+            params,
+            id: RequestId::Number(id),
+        };
+        client.send(Message::Request(request)).await.unwrap();
+        match client.receive().await.unwrap() {
+            Message::Response(response) => response.result.expect("successful response"),
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_plugins_extend_the_server() {
+        let (client, transport) = MemoryTransport::pair();
+        let server = McpServer::builder()
+            .with_plugin(MetricsPlugin::default())
+            .with_plugin(Shadow)
+            .with_transport(Box::new(transport))
+            .build();
+        tokio::spawn(server.run());
+
+        let params = json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "clientInfo": { "name": "test", "version": "1.0.0" }
+        });
+        let result: InitializeResult =
+            serde_json::from_value(call(&client, "initialize", Some(params), 1).await).unwrap();
+        assert_eq!(
+            result.capabilities.experimental,
+            Some(json!({ "metrics": {} }))
+        );
+
+        let initialized = Notification::new(Method::Initialized, None);
+        client
+            .send(Message::Notification(initialized))
+            .await
+            .unwrap();
+
+        let metrics = call(&client, "metrics/get", None, 2).await;
+        assert_eq!(metrics, json!({ "session": 0, "notifications": 1 }));
+        assert_eq!(call(&client, "ping", None, 3).await, json!({}));
+    }
+}
@@ -0,0 +1,107 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Identity of the authenticated caller of a request
+///
+/// Set by transports that authenticate their clients, such as the HTTP server, and
+/// handed to feature handlers through their execution contexts.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AuthContext {
+    /// Who is calling, e.g. the `sub` claim of a JWT or a Basic auth user name
+    pub subject: String,
+    /// Every claim the credentials carried, or `null` if they carried none
+    #[serde(default)]
+    pub claims: Value,
+    /// Scopes granted to the caller
+    #[serde(default)]
+    pub scopes: Vec<String>,
+}
+
+impl AuthContext {
+    /// Caller known only by name, without claims or scopes
+    pub fn new(subject: impl Into<String>) -> Self {
+        Self {
+            subject: subject.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Identity carried in the payload of a JWT
+    ///
+    /// The signature is not verified, so only use this for tokens that were
+    /// authenticated some other way. Scopes are read from a space-separated `scope`
+    /// claim or a `scp` array. Returns `None` if `token` is not a JWT.
+    pub fn from_jwt(token: &str) -> Option<Self> {
+        let mut parts = token.split('.');
+        let (Some(_), Some(payload), Some(_), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return None;
+        };
+        let claims: Value = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload).ok()?).ok()?;
+        if !claims.is_object() {
+            return None;
+        }
+
+        let scopes = match (claims.get("scope"), claims.get("scp")) {
+            (Some(Value::String(scope)), _) => {
+                scope.split_whitespace().map(str::to_string).collect()
+            }
+            (_, Some(Value::Array(scopes))) => scopes
+                .iter()
+                .filter_map(|scope| scope.as_str().map(str::to_string))
+                .collect(),
+            _ => Vec::new(),
+        };
+        Some(Self {
+            subject: claims["sub"].as_str().unwrap_or_default().to_string(),
+            claims,
+            scopes,
+        })
+    }
+
+    /// Whether the caller was granted `scope`
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|granted| granted == scope)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn jwt(claims: Value) -> String {
+        let encode = |value: Value| URL_SAFE_NO_PAD.encode(value.to_string());
+        format!(
+            "{}.{}.signature",
+            encode(json!({ "alg": "HS256", "typ": "JWT" })),
+            encode(claims)
+        )
+    }
+
+    #[test]
+    fn test_identity_from_jwt() {
+        let auth = AuthContext::from_jwt(&jwt(json!({
+            "sub": "alice",
+            "scope": "tools:execute prompts:read",
+            "tenant": "acme"
+        })))
+        .unwrap();
+        assert_eq!(auth.subject, "alice");
+        assert_eq!(auth.claims["tenant"], "acme");
+        assert!(auth.has_scope("tools:execute"));
+        assert!(!auth.has_scope("tools"));
+
+        let auth = AuthContext::from_jwt(&jwt(json!({ "sub": "bob", "scp": ["admin"] }))).unwrap();
+        assert_eq!(auth.scopes, ["admin"]);
+    }
+
+    #[test]
+    fn test_opaque_tokens_are_not_jwts() {
+        assert!(AuthContext::from_jwt("secret").is_none());
+        assert!(AuthContext::from_jwt("a.b.c").is_none());
+        assert!(AuthContext::from_jwt(&format!("{}.x", jwt(json!({})))).is_none());
+    }
+}
@@ -0,0 +1,272 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+use super::tools::{ToolExecutionContext, ToolManager};
+use crate::{Error, Result};
+
+/// One step of a `ToolChain`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolChainStep {
+    /// ID of the tool to execute
+    pub tool_id: String,
+    /// Maps each parameter of this step to the key of the previous output it is read from
+    ///
+    /// An empty mapping passes the whole previous output as the `input` parameter.
+    #[serde(default)]
+    pub param_mapping: HashMap<String, String>,
+}
+
+/// Sequence of tools where each output feeds the next tool's parameters
+///
+/// Chains are plain data and can be stored as JSON.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ToolChain {
+    /// Steps, executed in order
+    pub steps: Vec<ToolChainStep>,
+}
+
+impl ToolChain {
+    /// Creates an empty chain
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a step
+    pub fn then(mut self, tool_id: &str, param_mapping: HashMap<String, String>) -> Self {
+        self.steps.push(ToolChainStep {
+            tool_id: tool_id.to_string(),
+            param_mapping,
+        });
+        self
+    }
+
+    /// Runs the steps in order and returns the output of the last one
+    ///
+    /// `initial_params` act as the output of a virtual step zero, so the first step's
+    /// mapping reads from them. An empty chain returns `initial_params` unchanged.
+    /// A step whose result is flagged as an error stops the chain. Every step is
+    /// executed with `ctx`, so they share its progress sink, metadata and caller.
+    pub async fn execute(
+        &self,
+        tools: &dyn ToolManager,
+        initial_params: Value,
+        ctx: ToolExecutionContext,
+    ) -> Result<Value> {
+        let mut output = initial_params;
+        for (index, step) in self.steps.iter().enumerate() {
+            let params = step
+                .map_params(output)
+                .map_err(|e| step_error(index, step, e))?;
+            let result = tools
+                .execute_tool_with_context(&step.tool_id, params, &ctx)
+                .await
+                .map_err(|e| step_error(index, step, e))?;
+            if result.is_error {
+                let message = match result.into_value() {
+                    Value::String(text) => text,
+                    value => value.to_string(),
+                };
+                return Err(step_error(index, step, Error::Protocol(message)));
+            }
+            output = result.into_value();
+        }
+        Ok(output)
+    }
+}
+
+impl ToolChainStep {
+    fn map_params(&self, previous: Value) -> Result<Value> {
+        if self.param_mapping.is_empty() {
+            let mut params = Map::new();
+            params.insert("input".to_string(), previous);
+            return Ok(Value::Object(params));
+        }
+
+        let mut params = Map::new();
+        for (param, key) in &self.param_mapping {
+            let value = previous
+                .get(key)
+                .cloned()
+                .ok_or_else(|| Error::Protocol(format!("Previous output has no key '{}'", key)))?;
+            params.insert(param.clone(), value);
+        }
+        Ok(Value::Object(params))
+    }
+}
+
+/// Adds the failing step to an error, keeping JSON-RPC error codes intact
+fn step_error(index: usize, step: &ToolChainStep, error: Error) -> Error {
+    let context = format!("Tool chain step {} ({}) failed", index, step.tool_id);
+    match error {
+        Error::JsonRpc { code, message } => Error::JsonRpc {
+            code,
+            message: format!("{}: {}", context, message),
+        },
+        other => Error::Protocol(format!("{}: {}", context, other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server_features::{AuthContext, Tool, ToolResult};
+    use crate::testing::MockToolManager;
+    use serde_json::json;
+
+    fn tool(id: &str) -> Tool {
+        Tool {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            parameters: json!({ "type": "object" }),
+            requires_approval: false,
+            tags: Vec::new(),
+            estimated_duration: None,
+            side_effects: Vec::new(),
+        }
+    }
+
+    fn tools() -> MockToolManager {
+        MockToolManager::new()
+            .with_tool(tool("fetch"), json!({ "body": "hello", "status": 200 }))
+            .with_tool(tool("summarize"), json!({ "summary": "hi" }))
+    }
+
+    #[tokio::test]
+    async fn test_outputs_are_mapped_into_next_step() {
+        let tools = tools();
+        let chain = ToolChain::new()
+            .then("fetch", HashMap::from([("url".into(), "url".into())]))
+            .then("summarize", HashMap::from([("text".into(), "body".into())]));
+
+        let result = chain
+            .execute(
+                &tools,
+                json!({ "url": "https://example.com" }),
+                ToolExecutionContext::default(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result, json!({ "summary": "hi" }));
+        let executions = tools.executions();
+        assert_eq!(executions[0].1, json!({ "url": "https://example.com" }));
+        assert_eq!(executions[1].1, json!({ "text": "hello" }));
+    }
+
+    #[tokio::test]
+    async fn test_empty_mapping_passes_whole_output_as_input() {
+        let tools = tools();
+        let chain = ToolChain::new()
+            .then("fetch", HashMap::new())
+            .then("summarize", HashMap::new());
+
+        chain
+            .execute(&tools, json!("start"), ToolExecutionContext::default())
+            .await
+            .unwrap();
+        let executions = tools.executions();
+        assert_eq!(executions[0].1, json!({ "input": "start" }));
+        assert_eq!(
+            executions[1].1,
+            json!({ "input": { "body": "hello", "status": 200 } })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failure_reports_step() {
+        let chain = ToolChain::new()
+            .then("fetch", HashMap::new())
+            .then("missing", HashMap::new());
+
+        match chain
+            .execute(&tools(), json!({}), ToolExecutionContext::default())
+            .await
+        {
+            Err(Error::JsonRpc { message, .. }) => {
+                assert!(message.starts_with("Tool chain step 1 (missing) failed"))
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_error_result_stops_chain() {
+        let tools = tools().with_tool(tool("broken"), ToolResult::error("upstream timed out"));
+        let chain = ToolChain::new()
+            .then("broken", HashMap::new())
+            .then("fetch", HashMap::new());
+
+        let err = chain
+            .execute(&tools, json!({}), ToolExecutionContext::default())
+            .await
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Tool chain step 0 (broken) failed: Protocol error: upstream timed out"));
+        assert_eq!(tools.executions().len(), 1);
+    }
+
+    /// Appends the caller's subject to its input
+    struct Sign;
+
+    #[async_trait::async_trait]
+    impl ToolManager for Sign {
+        async fn list_tools(&self) -> Result<Vec<Tool>> {
+            Ok(vec![tool("sign")])
+        }
+
+        async fn get_tool(&self, _id: &str) -> Result<Tool> {
+            Ok(tool("sign"))
+        }
+
+        async fn execute_tool(&self, id: &str, params: Value) -> Result<ToolResult> {
+            self.execute_tool_with_context(id, params, &ToolExecutionContext::default())
+                .await
+        }
+
+        async fn execute_tool_with_context(
+            &self,
+            _id: &str,
+            params: Value,
+            ctx: &ToolExecutionContext,
+        ) -> Result<ToolResult> {
+            let subject = ctx.auth.as_ref().map_or("anonymous", |a| &a.subject);
+            let input = params["input"].as_str().unwrap_or_default();
+            Ok(json!(format!("{}{}", input, subject)).into())
+        }
+
+        async fn cancel_tool(&self, _id: &str) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_every_step_gets_the_context() {
+        let chain = ToolChain::new()
+            .then("sign", HashMap::new())
+            .then("sign", HashMap::new());
+        let ctx = ToolExecutionContext::default().with_auth(Some(AuthContext::new("ada")));
+
+        let result = chain.execute(&Sign, json!(">"), ctx).await.unwrap();
+        assert_eq!(result, json!(">adaada"));
+    }
+
+    #[test]
+    fn test_chain_round_trips_through_json() {
+        let json = json!({
+            "steps": [
+                { "tool_id": "fetch" },
+                { "tool_id": "summarize", "param_mapping": { "text": "body" } }
+            ]
+        });
+        let chain: ToolChain = serde_json::from_value(json).unwrap();
+        assert!(chain.steps[0].param_mapping.is_empty());
+        assert_eq!(chain.steps[1].param_mapping["text"], "body");
+
+        let round_trip: ToolChain =
+            serde_json::from_value(serde_json::to_value(&chain).unwrap()).unwrap();
+        assert_eq!(round_trip.steps.len(), 2);
+    }
+}
@@ -0,0 +1,262 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::Result;
+
+/// Maximum number of values returned in a single completion result
+pub const MAX_COMPLETION_VALUES: usize = 100;
+
+/// What is being completed
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum CompletionReference {
+    /// An argument of a prompt
+    #[serde(rename = "ref/prompt")]
+    Prompt {
+        /// Prompt name
+        name: String,
+    },
+    /// A variable of a resource URI template
+    #[serde(rename = "ref/resource")]
+    Resource {
+        /// Resource URI or URI template
+        uri: String,
+    },
+}
+
+/// The argument being completed and its current value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompletionArgument {
+    /// Argument name
+    pub name: String,
+    /// Value typed so far
+    pub value: String,
+}
+
+/// Parameters of a `completion/complete` request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompleteParams {
+    /// Prompt or resource the argument belongs to
+    #[serde(rename = "ref")]
+    pub ref_: CompletionReference,
+    /// Argument to complete
+    pub argument: CompletionArgument,
+}
+
+/// Result of a `completion/complete` request
+///
+/// Serialized in the MCP wire shape `{ "completion": { "values", "total", "hasMore" } }`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "CompleteResultWire", into = "CompleteResultWire")]
+pub struct CompleteResult {
+    /// Suggested values, at most `MAX_COMPLETION_VALUES`
+    pub values: Vec<String>,
+    /// Total number of matches, which may exceed the values returned
+    pub total: Option<usize>,
+    /// Whether more matches exist than were returned
+    pub has_more: Option<bool>,
+}
+
+impl CompleteResult {
+    /// Creates a result from all matches, truncating to `MAX_COMPLETION_VALUES`
+    pub fn new(mut values: Vec<String>) -> Self {
+        let total = values.len();
+        values.truncate(MAX_COMPLETION_VALUES);
+        Self {
+            values,
+            total: Some(total),
+            has_more: Some(total > MAX_COMPLETION_VALUES),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CompleteResultWire {
+    completion: CompletionValues,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CompletionValues {
+    values: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    has_more: Option<bool>,
+}
+
+impl From<CompleteResultWire> for CompleteResult {
+    fn from(wire: CompleteResultWire) -> Self {
+        Self {
+            values: wire.completion.values,
+            total: wire.completion.total,
+            has_more: wire.completion.has_more,
+        }
+    }
+}
+
+impl From<CompleteResult> for CompleteResultWire {
+    fn from(result: CompleteResult) -> Self {
+        Self {
+            completion: CompletionValues {
+                values: result.values,
+                total: result.total,
+                has_more: result.has_more,
+            },
+        }
+    }
+}
+
+/// Completion provider trait
+#[async_trait]
+pub trait CompletionProvider: Send + Sync {
+    /// Suggests values for a prompt or resource argument
+    async fn complete(&self, params: CompleteParams) -> Result<CompleteResult>;
+}
+
+/// Completion provider backed by fixed value lists, filtered by prefix
+#[derive(Debug, Clone, Default)]
+pub struct StaticCompletionProvider {
+    values: HashMap<(CompletionReference, String), Vec<String>>,
+}
+
+impl StaticCompletionProvider {
+    /// Creates an empty provider
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the candidate values for an argument
+    pub fn with_values(
+        mut self,
+        reference: CompletionReference,
+        argument: &str,
+        values: Vec<String>,
+    ) -> Self {
+        self.values
+            .insert((reference, argument.to_string()), values);
+        self
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for StaticCompletionProvider {
+    async fn complete(&self, params: CompleteParams) -> Result<CompleteResult> {
+        let matches = self
+            .values
+            .get(&(params.ref_, params.argument.name))
+            .map(|values| {
+                values
+                    .iter()
+                    .filter(|value| value.starts_with(&params.argument.value))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(CompleteResult::new(matches))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn language_ref() -> CompletionReference {
+        CompletionReference::Prompt {
+            name: "code_review".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_complete_params_wire_shape() {
+        let params = CompleteParams {
+            ref_: language_ref(),
+            argument: CompletionArgument {
+                name: "language".to_string(),
+                value: "py".to_string(),
+            },
+        };
+
+        let json = serde_json::to_value(&params).unwrap();
+        assert_eq!(
+            json,
+            json!({
+                "ref": { "type": "ref/prompt", "name": "code_review" },
+                "argument": { "name": "language", "value": "py" }
+            })
+        );
+        assert_eq!(
+            serde_json::from_value::<CompleteParams>(json).unwrap(),
+            params
+        );
+
+        let resource: CompletionReference =
+            serde_json::from_value(json!({ "type": "ref/resource", "uri": "file:///{path}" }))
+                .unwrap();
+        assert!(
+            matches!(resource, CompletionReference::Resource { uri } if uri == "file:///{path}")
+        );
+    }
+
+    #[test]
+    fn test_complete_result_wire_shape() {
+        let result = CompleteResult::new(vec!["python".to_string(), "pytorch".to_string()]);
+
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(
+            json,
+            json!({
+                "completion": { "values": ["python", "pytorch"], "total": 2, "hasMore": false }
+            })
+        );
+        assert_eq!(
+            serde_json::from_value::<CompleteResult>(json).unwrap(),
+            result
+        );
+    }
+
+    #[test]
+    fn test_complete_result_is_truncated() {
+        let values = (0..150).map(|i| i.to_string()).collect();
+        let result = CompleteResult::new(values);
+
+        assert_eq!(result.values.len(), MAX_COMPLETION_VALUES);
+        assert_eq!(result.total, Some(150));
+        assert_eq!(result.has_more, Some(true));
+    }
+
+    #[tokio::test]
+    async fn test_static_provider_filters_by_prefix() {
+        let provider = StaticCompletionProvider::new().with_values(
+            language_ref(),
+            "language",
+            vec!["python".into(), "pytorch".into(), "rust".into()],
+        );
+
+        let result = provider
+            .complete(CompleteParams {
+                ref_: language_ref(),
+                argument: CompletionArgument {
+                    name: "language".to_string(),
+                    value: "py".to_string(),
+                },
+            })
+            .await
+            .unwrap();
+        assert_eq!(result.values, vec!["python", "pytorch"]);
+
+        let unknown = provider
+            .complete(CompleteParams {
+                ref_: language_ref(),
+                argument: CompletionArgument {
+                    name: "framework".to_string(),
+                    value: String::new(),
+                },
+            })
+            .await
+            .unwrap();
+        assert!(unknown.values.is_empty());
+    }
+}
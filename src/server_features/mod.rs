@@ -1,14 +1,44 @@
+pub mod auth;
+pub mod chain;
+pub mod completion;
+pub mod pagination;
+pub mod progress;
+pub mod prompt_cache;
 pub mod prompts;
+pub mod readonly;
+pub mod resource_cache;
 pub mod resources;
+pub mod schema;
+pub mod tool_transform;
 pub mod tools;
 
+pub use auth::AuthContext;
+pub use chain::{ToolChain, ToolChainStep};
+pub use completion::*;
+pub use pagination::Page;
+pub use progress::{
+    NoopProgressSink, ProgressNotification, ProgressReport, ProgressSink, RecordingProgressSink,
+    TransportProgressSink,
+};
+pub use prompt_cache::PromptCache;
 pub use prompts::*;
+pub use readonly::{ReadonlyResourceManager, READ_ONLY_ERROR_CODE};
+pub use resource_cache::{CacheStats, ResourceContentCache};
 pub use resources::*;
+pub use schema::{SchemaViolation, ViolationKind};
+pub use tool_transform::{ToolResultTransformer, TransformingToolManager, TruncatingTransformer};
 pub use tools::*;
 
-/// Server capability flags
-#[derive(Debug, Clone, Default)]
-pub struct ServerCapabilities {
+use crate::protocol::{FeatureCapability, ResourceCapability, ServerCapabilities};
+
+/// Which server features are enabled, as plain flags
+///
+/// This is a simplified view of `protocol::ServerCapabilities`, the wire type
+/// sent in the `initialize` result. The flags only say whether a feature is
+/// present; sub-capabilities such as `list_changed` are not represented and are
+/// lost when converting from the wire type.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ServerFeatureFlags {
     /// Whether prompts are supported
     pub prompts: bool,
     /// Whether resources are supported
@@ -16,3 +46,71 @@ pub struct ServerCapabilities {
     /// Whether tools are supported
     pub tools: bool,
 }
+
+impl From<&ServerCapabilities> for ServerFeatureFlags {
+    fn from(capabilities: &ServerCapabilities) -> Self {
+        Self {
+            prompts: capabilities.prompts.is_some(),
+            resources: capabilities.resources.is_some(),
+            tools: capabilities.tools.is_some(),
+        }
+    }
+}
+
+impl From<ServerFeatureFlags> for ServerCapabilities {
+    /// Enabled features are advertised without any optional sub-capabilities
+    fn from(flags: ServerFeatureFlags) -> Self {
+        let feature = |enabled: bool| {
+            enabled.then_some(FeatureCapability {
+                list_changed: false,
+            })
+        };
+        Self {
+            prompts: feature(flags.prompts),
+            resources: flags.resources.then_some(ResourceCapability {
+                subscribe: false,
+                list_changed: false,
+                templates: false,
+                bulk_get: false,
+                transactions: false,
+            }),
+            tools: feature(flags.tools),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flags_to_capabilities() {
+        let capabilities = ServerCapabilities::from(ServerFeatureFlags {
+            prompts: true,
+            resources: true,
+            tools: false,
+        });
+        assert!(!capabilities.prompts.unwrap().list_changed);
+        assert!(!capabilities.resources.unwrap().subscribe);
+        assert!(capabilities.tools.is_none());
+        assert!(capabilities.logging.is_none());
+    }
+
+    #[test]
+    fn test_capabilities_to_flags_round_trip() {
+        let flags = ServerFeatureFlags {
+            prompts: false,
+            resources: true,
+            tools: true,
+        };
+        assert_eq!(
+            ServerFeatureFlags::from(&ServerCapabilities::from(flags)),
+            flags
+        );
+        assert_eq!(
+            ServerFeatureFlags::from(&ServerCapabilities::default()),
+            ServerFeatureFlags::default()
+        );
+    }
+}
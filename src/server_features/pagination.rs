@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{protocol::error_codes, Error, Result};
+
+/// One page of a `*/list` result
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Page<T> {
+    /// Items of this page
+    pub items: Vec<T>,
+    /// Cursor to pass to get the next page, `None` on the last page
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Page<T> {
+    /// A single page holding every item
+    pub fn all(items: Vec<T>) -> Self {
+        Self {
+            items,
+            next_cursor: None,
+        }
+    }
+
+    /// The page of `items` starting at `cursor`, holding at most `page_size` items
+    ///
+    /// Cursors are offsets into `items`, so this suits managers whose listing is
+    /// stable between calls. An unreadable cursor fails with `INVALID_PARAMS`.
+    pub fn slice(mut items: Vec<T>, cursor: Option<&str>, page_size: usize) -> Result<Self> {
+        let start = match cursor {
+            Some(cursor) => cursor
+                .parse::<usize>()
+                .ok()
+                .filter(|start| *start <= items.len())
+                .ok_or_else(|| Error::JsonRpc {
+                    code: error_codes::INVALID_PARAMS,
+                    message: format!("Invalid cursor: {}", cursor),
+                })?,
+            None => 0,
+        };
+        let end = start.saturating_add(page_size.max(1)).min(items.len());
+        let next_cursor = (end < items.len()).then(|| end.to_string());
+        items.truncate(end);
+        Ok(Self {
+            items: items.split_off(start),
+            next_cursor,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slice_pages_through_items() {
+        let items: Vec<u32> = (0..7).collect();
+
+        let first = Page::slice(items.clone(), None, 3).unwrap();
+        assert_eq!(first.items, [0, 1, 2]);
+        let second = Page::slice(items.clone(), first.next_cursor.as_deref(), 3).unwrap();
+        assert_eq!(second.items, [3, 4, 5]);
+        let last = Page::slice(items.clone(), second.next_cursor.as_deref(), 3).unwrap();
+        assert_eq!(last, Page::all(vec![6]));
+
+        for cursor in ["x", "8"] {
+            let err = Page::slice(items.clone(), Some(cursor), 3).unwrap_err();
+            assert!(
+                matches!(err, Error::JsonRpc { code, .. } if code == error_codes::INVALID_PARAMS)
+            );
+        }
+    }
+}
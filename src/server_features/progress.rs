@@ -0,0 +1,272 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+
+use crate::{
+    protocol::{Message, Method, Notification, OperationId, RequestId, OPERATION_ID_KEY},
+    transport::Transport,
+    Result,
+};
+
+/// Destination for progress updates reported by a running tool
+///
+/// Tools depend only on this trait, so they can be exercised in tests without a
+/// transport.
+#[async_trait]
+pub trait ProgressSink: Send + Sync {
+    /// Reports that `value` units of work (out of `total`, if known) are done
+    async fn report(&self, value: u64, total: Option<u64>, message: Option<String>) -> Result<()>;
+}
+
+/// Parameters of a `$/progress` notification
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgressNotification {
+    /// Token identifying the operation, taken from the request's `_meta.progressToken`
+    pub progress_token: RequestId,
+    /// Units of work done
+    pub progress: u64,
+    /// Total units of work, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<u64>,
+    /// Optional status message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Operation the progress belongs to, if the request carried an `_operationId`
+    #[serde(
+        rename = "_operationId",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub operation_id: Option<OperationId>,
+}
+
+/// Sends each update as a `$/progress` notification
+pub struct TransportProgressSink {
+    transport: Arc<dyn Transport>,
+    token: Value,
+    operation_id: Option<OperationId>,
+}
+
+impl TransportProgressSink {
+    /// Reports progress for `token` over `transport`
+    pub fn new(transport: Arc<dyn Transport>, token: Value) -> Self {
+        Self {
+            transport,
+            token,
+            operation_id: None,
+        }
+    }
+
+    /// Tags every update with the operation it belongs to
+    pub fn with_operation_id(mut self, operation_id: Option<OperationId>) -> Self {
+        self.operation_id = operation_id;
+        self
+    }
+}
+
+#[async_trait]
+impl ProgressSink for TransportProgressSink {
+    async fn report(&self, value: u64, total: Option<u64>, message: Option<String>) -> Result<()> {
+        let mut params = json!({
+            "progressToken": self.token,
+            "progress": value,
+        });
+        if let Some(total) = total {
+            params["total"] = json!(total);
+        }
+        if let Some(message) = message {
+            params["message"] = json!(message);
+        }
+        if let Some(id) = self.operation_id {
+            params[OPERATION_ID_KEY] = json!(id);
+        }
+
+        self.transport
+            .send(Message::Notification(Notification::new(
+                Method::Progress,
+                Some(params),
+            )))
+            .await
+    }
+}
+
+/// Discards every update
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopProgressSink;
+
+#[async_trait]
+impl ProgressSink for NoopProgressSink {
+    async fn report(
+        &self,
+        _value: u64,
+        _total: Option<u64>,
+        _message: Option<String>,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// A single recorded progress update
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgressReport {
+    /// Units of work done
+    pub value: u64,
+    /// Total units of work, if known
+    pub total: Option<u64>,
+    /// Optional status message
+    pub message: Option<String>,
+}
+
+/// Stores every update for later assertions
+#[derive(Debug, Default)]
+pub struct RecordingProgressSink {
+    reports: Mutex<Vec<ProgressReport>>,
+}
+
+impl RecordingProgressSink {
+    /// Creates an empty recorder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Updates reported so far, in order
+    pub fn reports(&self) -> Vec<ProgressReport> {
+        self.reports.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl ProgressSink for RecordingProgressSink {
+    async fn report(&self, value: u64, total: Option<u64>, message: Option<String>) -> Result<()> {
+        self.reports.lock().unwrap().push(ProgressReport {
+            value,
+            total,
+            message,
+        });
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<S: ProgressSink + ?Sized> ProgressSink for Arc<S> {
+    async fn report(&self, value: u64, total: Option<u64>, message: Option<String>) -> Result<()> {
+        (**self).report(value, total, message).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server_features::{Tool, ToolExecutionContext, ToolManager, ToolResult};
+    use crate::transport::MemoryTransport;
+    use crate::Error;
+
+    struct CountingTool;
+
+    #[async_trait]
+    impl ToolManager for CountingTool {
+        async fn list_tools(&self) -> Result<Vec<Tool>> {
+            Ok(vec![])
+        }
+
+        async fn get_tool(&self, id: &str) -> Result<Tool> {
+            Err(Error::Protocol(format!("Tool not found: {}", id)))
+        }
+
+        async fn execute_tool(&self, id: &str, params: Value) -> Result<ToolResult> {
+            self.execute_tool_with_context(id, params, &ToolExecutionContext::default())
+                .await
+        }
+
+        async fn execute_tool_with_context(
+            &self,
+            _id: &str,
+            params: Value,
+            context: &ToolExecutionContext,
+        ) -> Result<ToolResult> {
+            let steps = params["steps"].as_u64().unwrap_or(0);
+            for step in 1..=steps {
+                context.progress.report(step, Some(steps), None).await?;
+            }
+            Ok(json!({"steps": steps}).into())
+        }
+
+        async fn cancel_tool(&self, _id: &str) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tool_reports_through_context() {
+        let sink = Arc::new(RecordingProgressSink::new());
+        let context = ToolExecutionContext::new(Arc::clone(&sink));
+
+        let result = CountingTool
+            .execute_tool_with_context("count", json!({"steps": 3}), &context)
+            .await
+            .unwrap();
+
+        assert_eq!(result.into_value(), json!({"steps": 3}));
+        let values: Vec<u64> = sink.reports().iter().map(|r| r.value).collect();
+        assert_eq!(values, vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_recording_sink_keeps_reports_in_order() {
+        let sink = RecordingProgressSink::new();
+        sink.report(1, Some(2), None).await.unwrap();
+        sink.report(2, Some(2), Some("done".to_string()))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            sink.reports(),
+            vec![
+                ProgressReport {
+                    value: 1,
+                    total: Some(2),
+                    message: None
+                },
+                ProgressReport {
+                    value: 2,
+                    total: Some(2),
+                    message: Some("done".to_string())
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_transport_sink_sends_progress_notification() {
+        let (local, remote) = MemoryTransport::pair();
+        let sink = TransportProgressSink::new(Arc::new(local), json!("op-1"));
+        sink.report(3, None, Some("working".to_string()))
+            .await
+            .unwrap();
+
+        match remote.receive().await.unwrap() {
+            Message::Notification(notification) => {
+                assert_eq!(notification.method, "$/progress");
+                assert_eq!(
+                    notification.params,
+                    Some(json!({"progressToken": "op-1", "progress": 3, "message": "working"}))
+                );
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+
+        let operation = OperationId::new();
+        let sink = sink.with_operation_id(Some(operation));
+        sink.report(4, None, None).await.unwrap();
+        match remote.receive().await.unwrap() {
+            Message::Notification(notification) => {
+                let progress: ProgressNotification =
+                    serde_json::from_value(notification.params.unwrap()).unwrap();
+                assert_eq!(progress.operation_id, Some(operation));
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+}
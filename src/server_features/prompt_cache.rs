@@ -0,0 +1,319 @@
+use async_trait::async_trait;
+use lru::LruCache;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+use super::pagination::Page;
+use super::prompts::{GetPromptResult, Prompt, PromptExecutionContext, PromptManager};
+use super::resource_cache::CacheStats;
+use crate::protocol::FeatureCapability;
+use crate::server::{EventBus, ServerEvent};
+use crate::Result;
+
+/// Cache key: the prompt ID and a hash of the parameters it was executed with
+type CacheKey = (String, String);
+
+/// Caching wrapper around a `PromptManager`
+///
+/// Successful `execute_prompt` results are kept for `ttl`, keyed on the prompt ID
+/// and the parameters, with at most `max_entries` results cached; the least
+/// recently used entry is evicted first. Failures are not cached.
+pub struct PromptCache<M> {
+    inner: M,
+    ttl: Duration,
+    entries: Mutex<LruCache<CacheKey, (Instant, Value)>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl<M: PromptManager> PromptCache<M> {
+    /// Wraps `inner`, caching up to `max_entries` results for `ttl` each
+    pub fn new(inner: M, max_entries: usize, ttl: Duration) -> Self {
+        let capacity = NonZeroUsize::new(max_entries).unwrap_or(NonZeroUsize::MIN);
+        Self {
+            inner,
+            ttl,
+            entries: Mutex::new(LruCache::new(capacity)),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Cache hits and misses so far
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Drops every cached result of a prompt
+    pub fn invalidate(&self, id: &str) {
+        let mut entries = self.entries.lock().unwrap();
+        let stale: Vec<CacheKey> = entries
+            .iter()
+            .filter(|((prompt_id, _), _)| prompt_id == id)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in stale {
+            entries.pop(&key);
+        }
+    }
+
+    /// Drops every cached result
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    /// The wrapped manager
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    fn key(id: &str, params: Option<&Value>) -> Result<CacheKey> {
+        let params = params.map(canonical).unwrap_or(Value::Null);
+        let hash = hex::encode(Sha256::digest(serde_json::to_vec(&params)?));
+        Ok((id.to_string(), hash))
+    }
+
+    fn cached(&self, key: &CacheKey) -> Option<Value> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(key) {
+            Some((cached_at, result)) if cached_at.elapsed() < self.ttl => Some(result.clone()),
+            Some(_) => {
+                entries.pop(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    async fn cached_or<F>(&self, id: &str, params: Option<&Value>, execute: F) -> Result<Value>
+    where
+        F: std::future::Future<Output = Result<Value>>,
+    {
+        let key = Self::key(id, params)?;
+        if let Some(result) = self.cached(&key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(result);
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let result = execute.await?;
+        self.entries
+            .lock()
+            .unwrap()
+            .put(key, (Instant::now(), result.clone()));
+        Ok(result)
+    }
+}
+
+impl<M: PromptManager + 'static> PromptCache<M> {
+    /// Clears the cache whenever resources or prompts change on `events`
+    ///
+    /// Prompts often embed resource content, so a `ResourceChanged` event can make
+    /// any cached result stale. The returned task ends once the cache is dropped
+    /// or the bus is closed.
+    pub fn clear_on_changes(self: &Arc<Self>, events: &EventBus) -> JoinHandle<()> {
+        let cache = Arc::downgrade(self);
+        let mut events = events.subscribe();
+        tokio::spawn(async move {
+            loop {
+                let event = events.recv().await;
+                let Some(cache) = cache.upgrade() else { break };
+                match event {
+                    Ok(ServerEvent::ResourceChanged | ServerEvent::PromptChanged)
+                    | Err(RecvError::Lagged(_)) => cache.clear(),
+                    Ok(ServerEvent::ToolChanged) => {}
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+}
+
+/// `value` with object keys in sorted order, so equal parameters hash alike
+fn canonical(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut fields: Vec<_> = map.iter().collect();
+            fields.sort_by_key(|(key, _)| *key);
+            Value::Object(
+                fields
+                    .into_iter()
+                    .map(|(key, value)| (key.clone(), canonical(value)))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.iter().map(canonical).collect()),
+        other => other.clone(),
+    }
+}
+
+#[async_trait]
+impl<M: PromptManager> PromptManager for PromptCache<M> {
+    async fn list_prompts(&self) -> Result<Vec<Prompt>> {
+        self.inner.list_prompts().await
+    }
+
+    async fn list_prompts_page(&self, cursor: Option<&str>) -> Result<Page<Prompt>> {
+        self.inner.list_prompts_page(cursor).await
+    }
+
+    async fn get_prompt(&self, id: &str) -> Result<Prompt> {
+        self.inner.get_prompt(id).await
+    }
+
+    async fn create_prompt(&self, prompt: Prompt) -> Result<()> {
+        let id = prompt.id.clone();
+        self.inner.create_prompt(prompt).await?;
+        self.invalidate(&id);
+        Ok(())
+    }
+
+    async fn delete_prompt(&self, id: &str) -> Result<()> {
+        self.inner.delete_prompt(id).await?;
+        self.invalidate(id);
+        Ok(())
+    }
+
+    fn capabilities(&self) -> FeatureCapability {
+        self.inner.capabilities()
+    }
+
+    async fn get_prompt_messages(
+        &self,
+        id: &str,
+        params: Option<Value>,
+    ) -> Result<GetPromptResult> {
+        self.inner.get_prompt_messages(id, params).await
+    }
+
+    async fn execute_prompt(&self, id: &str, params: Option<Value>) -> Result<Value> {
+        self.cached_or(
+            id,
+            params.as_ref(),
+            self.inner.execute_prompt(id, params.clone()),
+        )
+        .await
+    }
+
+    async fn execute_prompt_with_context(
+        &self,
+        id: &str,
+        params: Option<Value>,
+        context: &PromptExecutionContext,
+    ) -> Result<Value> {
+        self.cached_or(
+            id,
+            params.as_ref(),
+            self.inner
+                .execute_prompt_with_context(id, params.clone(), context),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Prompt manager counting executions
+    #[derive(Default)]
+    struct CountingPrompts {
+        executions: AtomicU64,
+    }
+
+    impl CountingPrompts {
+        fn executions(&self) -> u64 {
+            self.executions.load(Ordering::Relaxed)
+        }
+    }
+
+    #[async_trait]
+    impl PromptManager for CountingPrompts {
+        async fn list_prompts(&self) -> Result<Vec<Prompt>> {
+            Ok(Vec::new())
+        }
+
+        async fn get_prompt(&self, id: &str) -> Result<Prompt> {
+            Err(crate::Error::Protocol(format!("Prompt not found: {}", id)))
+        }
+
+        async fn execute_prompt(&self, id: &str, params: Option<Value>) -> Result<Value> {
+            self.executions.fetch_add(1, Ordering::Relaxed);
+            Ok(json!({ "prompt": id, "params": params }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_identical_executions_hit() {
+        let cache = PromptCache::new(CountingPrompts::default(), 8, Duration::from_secs(60));
+
+        let first = cache
+            .execute_prompt("greet", Some(json!({ "name": "Ada", "excited": true })))
+            .await
+            .unwrap();
+        let second = cache
+            .execute_prompt("greet", Some(json!({ "excited": true, "name": "Ada" })))
+            .await
+            .unwrap();
+        assert_eq!(first, second);
+        assert_eq!(cache.inner().executions(), 1);
+
+        cache
+            .execute_prompt("greet", Some(json!({ "name": "Grace" })))
+            .await
+            .unwrap();
+        assert_eq!(cache.inner().executions(), 2);
+        assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 2 });
+        assert!((cache.stats().hit_rate() - 1.0 / 3.0).abs() < f64::EPSILON);
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_and_clear() {
+        let cache = PromptCache::new(CountingPrompts::default(), 8, Duration::from_secs(60));
+        cache.execute_prompt("greet", None).await.unwrap();
+        cache.execute_prompt("summary", None).await.unwrap();
+
+        cache.invalidate("greet");
+        cache.execute_prompt("greet", None).await.unwrap();
+        cache.execute_prompt("summary", None).await.unwrap();
+        assert_eq!(cache.inner().executions(), 3);
+
+        cache.clear();
+        cache.execute_prompt("summary", None).await.unwrap();
+        assert_eq!(cache.inner().executions(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_resource_changes_clear_the_cache() {
+        let cache = Arc::new(PromptCache::new(
+            CountingPrompts::default(),
+            8,
+            Duration::from_secs(60),
+        ));
+        let events = EventBus::default();
+        let watcher = cache.clear_on_changes(&events);
+
+        cache.execute_prompt("greet", None).await.unwrap();
+        events.publish(ServerEvent::ResourceChanged);
+        while !cache.entries.lock().unwrap().is_empty() {
+            tokio::task::yield_now().await;
+        }
+        cache.execute_prompt("greet", None).await.unwrap();
+        assert_eq!(cache.inner().executions(), 2);
+
+        drop(cache);
+        events.publish(ServerEvent::ToolChanged);
+        watcher.await.unwrap();
+    }
+}
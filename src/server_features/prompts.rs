@@ -1,8 +1,15 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{json, Value};
 
-use crate::Result;
+use super::auth::AuthContext;
+use super::pagination::Page;
+use super::schema::{self, SchemaViolation, ViolationKind};
+use crate::{
+    client_features::sampling::SamplingMessage,
+    protocol::{error_codes, FeatureCapability, Method, OperationId, RequestMeta},
+    Error, Result,
+};
 
 /// Represents a prompt template
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +24,136 @@ pub struct Prompt {
     pub template: String,
     /// Optional parameters for the template
     pub parameters: Option<Value>,
+    /// Arguments the prompt accepts, as declared to MCP clients
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub arguments: Vec<PromptArgument>,
+}
+
+/// An argument declared by a prompt
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptArgument {
+    /// Argument name, the key it is passed under
+    pub name: String,
+    /// What the argument is for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Whether the prompt cannot be executed without it
+    #[serde(default)]
+    pub required: bool,
+}
+
+impl Prompt {
+    /// Validates parameters against the prompt's parameter schema and arguments
+    ///
+    /// Returns an empty list when the prompt has neither a schema nor required
+    /// arguments.
+    pub fn validate_params(&self, params: &Value) -> Vec<SchemaViolation> {
+        let mut violations = match &self.parameters {
+            Some(schema) => schema::validate(schema, params),
+            None => Vec::new(),
+        };
+        for argument in self.arguments.iter().filter(|a| a.required) {
+            let path = format!("$.{}", argument.name);
+            if params.get(&argument.name).is_none() && !violations.iter().any(|v| v.path == path) {
+                violations.push(SchemaViolation {
+                    path,
+                    kind: ViolationKind::MissingRequired,
+                    message: format!("missing required argument '{}'", argument.name),
+                });
+            }
+        }
+        violations
+    }
+
+    /// Names of the parameters marked as required in the schema or the arguments
+    pub fn required_params(&self) -> Vec<String> {
+        let mut required = self
+            .parameters
+            .as_ref()
+            .map(schema::required_properties)
+            .unwrap_or_default();
+        for argument in self.arguments.iter().filter(|a| a.required) {
+            if !required.contains(&argument.name) {
+                required.push(argument.name.clone());
+            }
+        }
+        required
+    }
+
+    /// Validates parameters, returning an `INVALID_PARAMS` error if any violations are found
+    ///
+    /// Missing parameters are validated as an empty object.
+    pub fn check_params(&self, params: Option<&Value>) -> Result<()> {
+        let empty = json!({});
+        invalid_params(self.validate_params(params.unwrap_or(&empty)))
+    }
+
+    /// The template with every `{name}` placeholder replaced by the parameter of
+    /// that name
+    ///
+    /// String parameters are inserted as-is and others as JSON; placeholders
+    /// without a matching parameter are left in place.
+    pub fn render(&self, params: Option<&Value>) -> String {
+        let mut rendered = self.template.clone();
+        if let Some(Value::Object(params)) = params {
+            for (name, value) in params {
+                let value = match value {
+                    Value::String(text) => text.clone(),
+                    other => other.to_string(),
+                };
+                rendered = rendered.replace(&format!("{{{}}}", name), &value);
+            }
+        }
+        rendered
+    }
+}
+
+/// A prompt rendered into messages, the result of `prompts/get` in MCP
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GetPromptResult {
+    /// Description of the prompt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Messages to hand to the model, in order
+    pub messages: Vec<SamplingMessage>,
+}
+
+fn invalid_params(violations: Vec<SchemaViolation>) -> Result<()> {
+    if violations.is_empty() {
+        return Ok(());
+    }
+
+    let details: Vec<String> = violations
+        .iter()
+        .map(|v| format!("{}: {}", v.path, v.message))
+        .collect();
+    Err(Error::JsonRpc {
+        code: error_codes::INVALID_PARAMS,
+        message: format!("Invalid prompt parameters: {}", details.join("; ")),
+    })
+}
+
+/// Typed prompt parameters
+///
+/// Usually derived with `#[derive(PromptParams)]` from the `macros` feature, which
+/// also implements `TryFrom<Value>` for the struct.
+pub trait PromptParams: Sized {
+    /// JSON Schema describing the parameters
+    fn parameter_schema() -> Value;
+
+    /// Parameters as the `Value` passed to `PromptManager::execute_prompt`
+    fn to_prompt_params(&self) -> Value;
+
+    /// Validates these parameters against a prompt's parameter schema
+    fn validate(&self, prompt: &Prompt) -> Vec<SchemaViolation> {
+        prompt.validate_params(&self.to_prompt_params())
+    }
+
+    /// Checks a raw value against `parameter_schema`, returning an
+    /// `INVALID_PARAMS` error if any violations are found
+    fn check(params: &Value) -> Result<()> {
+        invalid_params(schema::validate(&Self::parameter_schema(), params))
+    }
 }
 
 /// Prompt manager trait
@@ -25,9 +162,334 @@ pub trait PromptManager: Send + Sync {
     /// Lists available prompts
     async fn list_prompts(&self) -> Result<Vec<Prompt>>;
 
+    /// Lists one page of prompts, starting at `cursor`
+    ///
+    /// The default returns everything from `list_prompts` as a single page.
+    async fn list_prompts_page(&self, _cursor: Option<&str>) -> Result<Page<Prompt>> {
+        Ok(Page::all(self.list_prompts().await?))
+    }
+
     /// Gets a specific prompt by ID
     async fn get_prompt(&self, id: &str) -> Result<Prompt>;
 
     /// Executes a prompt with given parameters
+    ///
+    /// Implementations should reject invalid parameters with `Prompt::check_params`
+    /// before rendering the template.
     async fn execute_prompt(&self, id: &str, params: Option<Value>) -> Result<Value>;
+
+    /// Adds a prompt
+    ///
+    /// The default rejects the request, for managers with a fixed set of prompts.
+    async fn create_prompt(&self, _prompt: Prompt) -> Result<()> {
+        Err(unsupported(Method::CreatePrompt))
+    }
+
+    /// Removes a prompt
+    ///
+    /// The default rejects the request, for managers with a fixed set of prompts.
+    async fn delete_prompt(&self, _id: &str) -> Result<()> {
+        Err(unsupported(Method::DeletePrompt))
+    }
+
+    /// Renders a prompt with the given arguments into messages
+    ///
+    /// The default checks the arguments and renders the template into a single
+    /// user message with `Prompt::render`.
+    async fn get_prompt_messages(
+        &self,
+        id: &str,
+        params: Option<Value>,
+    ) -> Result<GetPromptResult> {
+        let prompt = self.get_prompt(id).await?;
+        prompt.check_params(params.as_ref())?;
+        Ok(GetPromptResult {
+            description: Some(prompt.description.clone()).filter(|d| !d.is_empty()),
+            messages: vec![SamplingMessage::user(prompt.render(params.as_ref()))],
+        })
+    }
+
+    /// Executes a prompt with access to a per-call context
+    ///
+    /// The default ignores the context.
+    async fn execute_prompt_with_context(
+        &self,
+        id: &str,
+        params: Option<Value>,
+        _context: &PromptExecutionContext,
+    ) -> Result<Value> {
+        self.execute_prompt(id, params).await
+    }
+
+    /// Prompt capabilities to advertise for this manager
+    ///
+    /// The server announces every successful `prompts/create` and `prompts/delete`
+    /// with `notifications/prompts/list_changed`, so managers that override
+    /// `create_prompt` and `delete_prompt` should set `list_changed`.
+    fn capabilities(&self) -> FeatureCapability {
+        FeatureCapability {
+            list_changed: false,
+        }
+    }
+}
+
+fn unsupported(method: Method) -> Error {
+    Error::JsonRpc {
+        code: error_codes::METHOD_NOT_FOUND,
+        message: format!("{} is not supported by this server", method),
+    }
+}
+
+/// Per-call context handed to a prompt execution
+#[derive(Debug, Clone, Default)]
+pub struct PromptExecutionContext {
+    /// Metadata the caller sent in `_meta`
+    pub meta: RequestMeta,
+    /// Who is calling, when the transport authenticated the caller
+    pub auth: Option<AuthContext>,
+    /// Operation the call belongs to, when the request carried an `_operationId`
+    pub operation_id: Option<OperationId>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server_features::ViolationKind;
+
+    fn prompt() -> Prompt {
+        Prompt {
+            id: "greet".to_string(),
+            name: "Greet".to_string(),
+            description: "Greets someone".to_string(),
+            template: "Hello, {name}!".to_string(),
+            parameters: Some(json!({
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string" },
+                    "excited": { "type": "boolean" }
+                },
+                "required": ["name"],
+                "additionalProperties": false
+            })),
+            arguments: Vec::new(),
+        }
+    }
+
+    fn summarize() -> Prompt {
+        Prompt {
+            id: "summarize".to_string(),
+            name: "Summarize".to_string(),
+            description: "Summarizes a text".to_string(),
+            template: "Summarize in {style} style: {text}".to_string(),
+            parameters: None,
+            arguments: vec![
+                PromptArgument {
+                    name: "text".to_string(),
+                    description: Some("Text to summarize".to_string()),
+                    required: true,
+                },
+                PromptArgument {
+                    name: "style".to_string(),
+                    description: None,
+                    required: false,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_required_arguments() {
+        let prompt = summarize();
+        assert_eq!(prompt.required_params(), vec!["text".to_string()]);
+        assert!(prompt
+            .check_params(Some(&json!({ "text": "Long story" })))
+            .is_ok());
+
+        let err = prompt
+            .check_params(Some(&json!({ "style": "terse" })))
+            .unwrap_err();
+        assert!(matches!(err, Error::JsonRpc { code, .. } if code == error_codes::INVALID_PARAMS));
+        let violations = prompt.validate_params(&json!({}));
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].path, "$.text");
+
+        let value = serde_json::to_value(&prompt).unwrap();
+        assert_eq!(
+            value["arguments"][1],
+            json!({ "name": "style", "required": false })
+        );
+    }
+
+    #[test]
+    fn test_arguments_and_schema_report_once() {
+        let mut prompt = prompt();
+        prompt.arguments = vec![PromptArgument {
+            name: "name".to_string(),
+            description: None,
+            required: true,
+        }];
+        assert_eq!(prompt.validate_params(&json!({})).len(), 1);
+        assert_eq!(prompt.required_params(), vec!["name".to_string()]);
+    }
+
+    #[test]
+    fn test_valid_params() {
+        assert!(prompt()
+            .validate_params(&json!({ "name": "Ada", "excited": true }))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_missing_required_param() {
+        let violations = prompt().validate_params(&json!({}));
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].kind, ViolationKind::MissingRequired);
+        assert_eq!(violations[0].path, "$.name");
+    }
+
+    #[test]
+    fn test_wrong_param_type() {
+        let violations = prompt().validate_params(&json!({ "name": 42 }));
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].kind, ViolationKind::TypeMismatch);
+    }
+
+    #[test]
+    fn test_extra_param_rejected() {
+        let violations = prompt().validate_params(&json!({ "name": "Ada", "age": 36 }));
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].kind, ViolationKind::UnexpectedProperty);
+        assert_eq!(violations[0].path, "$.age");
+    }
+
+    #[test]
+    fn test_required_params() {
+        assert_eq!(prompt().required_params(), vec!["name".to_string()]);
+
+        let mut untyped = prompt();
+        untyped.parameters = None;
+        assert!(untyped.required_params().is_empty());
+        assert!(untyped
+            .validate_params(&json!({ "anything": 1 }))
+            .is_empty());
+    }
+
+    #[derive(Debug, PartialEq, mcprotocol_rs_macros::PromptParams)]
+    struct GreetParams {
+        #[param(type = "string", required = true)]
+        name: String,
+        #[param(name = "excited", type = "boolean")]
+        loud: Option<bool>,
+    }
+
+    #[test]
+    fn test_derived_params_round_trip() {
+        let params = GreetParams {
+            name: "Ada".to_string(),
+            loud: None,
+        };
+        assert_eq!(params.to_prompt_params(), json!({ "name": "Ada" }));
+        assert!(params.validate(&prompt()).is_empty());
+
+        let parsed = GreetParams::try_from(json!({ "name": "Ada", "excited": true })).unwrap();
+        assert_eq!(parsed.loud, Some(true));
+        assert_eq!(
+            GreetParams::parameter_schema(),
+            json!({
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string" },
+                    "excited": { "type": "boolean" }
+                },
+                "required": ["name"]
+            })
+        );
+    }
+
+    #[test]
+    fn test_derived_params_reject_invalid_values() {
+        for value in [
+            json!({}),
+            json!({ "name": 1 }),
+            json!({ "name": "Ada", "excited": "yes" }),
+        ] {
+            let err = GreetParams::try_from(value).unwrap_err();
+            assert!(
+                matches!(err, Error::JsonRpc { code, .. } if code == error_codes::INVALID_PARAMS)
+            );
+        }
+    }
+
+    struct Prompts;
+
+    #[async_trait]
+    impl PromptManager for Prompts {
+        async fn list_prompts(&self) -> Result<Vec<Prompt>> {
+            Ok(vec![prompt(), summarize()])
+        }
+
+        async fn get_prompt(&self, id: &str) -> Result<Prompt> {
+            self.list_prompts()
+                .await?
+                .into_iter()
+                .find(|prompt| prompt.id == id)
+                .ok_or_else(|| Error::Protocol(format!("Prompt not found: {}", id)))
+        }
+
+        async fn execute_prompt(&self, id: &str, params: Option<Value>) -> Result<Value> {
+            Ok(json!(self.get_prompt(id).await?.render(params.as_ref())))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prompt_messages_wire_shape() {
+        let result = Prompts
+            .get_prompt_messages("greet", Some(json!({ "name": "Ada" })))
+            .await
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(&result).unwrap(),
+            json!({
+                "description": "Greets someone",
+                "messages": [{
+                    "role": "user",
+                    "content": { "type": "text", "text": "Hello, Ada!" }
+                }]
+            })
+        );
+
+        let parsed: GetPromptResult =
+            serde_json::from_value(serde_json::to_value(&result).unwrap()).unwrap();
+        assert_eq!(parsed, result);
+    }
+
+    #[tokio::test]
+    async fn test_prompt_messages_check_arguments() {
+        let result = Prompts
+            .get_prompt_messages(
+                "summarize",
+                Some(json!({ "text": "Long story", "style": 3 })),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            result.messages,
+            [SamplingMessage::user("Summarize in 3 style: Long story")]
+        );
+
+        let err = Prompts
+            .get_prompt_messages("summarize", None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::JsonRpc { code, .. } if code == error_codes::INVALID_PARAMS));
+    }
+
+    #[test]
+    fn test_check_params_returns_invalid_params() {
+        let err = prompt().check_params(None).unwrap_err();
+        assert!(matches!(err, Error::JsonRpc { code, .. } if code == error_codes::INVALID_PARAMS));
+        assert!(prompt()
+            .check_params(Some(&json!({ "name": "Ada" })))
+            .is_ok());
+    }
 }
@@ -0,0 +1,191 @@
+use async_trait::async_trait;
+
+use super::pagination::Page;
+use super::resources::{
+    BulkGetResult, ReadResourcesResult, Resource, ResourceAuditEntry, ResourceContents,
+    ResourceManager, ResourceOperation, ResourceStream, ResourceTemplate,
+};
+use crate::{protocol::ResourceCapability, Error, Result};
+use std::collections::HashMap;
+
+/// Error code returned for rejected mutations
+pub const READ_ONLY_ERROR_CODE: i32 = -32000;
+
+/// Wrapper exposing a `ResourceManager` for reading only
+///
+/// Listing and fetching are delegated to the inner manager; creating, updating,
+/// deleting and transactions fail with `READ_ONLY_ERROR_CODE`. Subscriptions and
+/// transactions are not advertised.
+pub struct ReadonlyResourceManager<M> {
+    inner: M,
+}
+
+impl<M: ResourceManager> ReadonlyResourceManager<M> {
+    /// Wraps `inner`
+    pub fn new(inner: M) -> Self {
+        Self { inner }
+    }
+
+    /// The wrapped manager
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    fn rejected() -> Error {
+        Error::JsonRpc {
+            code: READ_ONLY_ERROR_CODE,
+            message: "Resource is read-only".to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl<M: ResourceManager> ResourceManager for ReadonlyResourceManager<M> {
+    async fn list_resources(&self) -> Result<Vec<Resource>> {
+        self.inner.list_resources().await
+    }
+
+    async fn list_resources_page(&self, cursor: Option<&str>) -> Result<Page<Resource>> {
+        self.inner.list_resources_page(cursor).await
+    }
+
+    async fn get_resource(&self, id: &str) -> Result<Resource> {
+        self.inner.get_resource(id).await
+    }
+
+    async fn create_resource(&self, _resource: Resource) -> Result<()> {
+        Err(Self::rejected())
+    }
+
+    async fn update_resource(&self, _id: &str, _resource: Resource) -> Result<()> {
+        Err(Self::rejected())
+    }
+
+    async fn delete_resource(&self, _id: &str) -> Result<()> {
+        Err(Self::rejected())
+    }
+
+    async fn transaction(&self, _operations: Vec<ResourceOperation>) -> Result<()> {
+        Err(Self::rejected())
+    }
+
+    async fn audit(&self, entry: ResourceAuditEntry) {
+        self.inner.audit(entry).await
+    }
+
+    async fn stream_resource(&self, id: &str) -> Result<ResourceStream> {
+        self.inner.stream_resource(id).await
+    }
+
+    async fn resource_mime_type(&self, id: &str) -> Result<Option<String>> {
+        self.inner.resource_mime_type(id).await
+    }
+
+    async fn resource_contents(&self, resource: &Resource) -> Result<ResourceContents> {
+        self.inner.resource_contents(resource).await
+    }
+
+    async fn list_resource_templates(&self) -> Result<Vec<ResourceTemplate>> {
+        self.inner.list_resource_templates().await
+    }
+
+    async fn bulk_get_resources(
+        &self,
+        ids: &[&str],
+        if_none_match: Option<&HashMap<String, String>>,
+    ) -> Result<BulkGetResult> {
+        self.inner.bulk_get_resources(ids, if_none_match).await
+    }
+
+    async fn read_resources(&self, ids: &[String]) -> Result<ReadResourcesResult> {
+        self.inner.read_resources(ids).await
+    }
+
+    fn capabilities(&self) -> ResourceCapability {
+        ResourceCapability {
+            subscribe: false,
+            transactions: false,
+            ..self.inner.capabilities()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    struct SingleResource;
+
+    #[async_trait]
+    impl ResourceManager for SingleResource {
+        async fn list_resources(&self) -> Result<Vec<Resource>> {
+            Ok(vec![self.get_resource("readme").await?])
+        }
+
+        async fn get_resource(&self, id: &str) -> Result<Resource> {
+            Ok(Resource {
+                id: id.to_string(),
+                type_: "file".to_string(),
+                metadata: json!({}),
+                content: Some(json!("# Hello")),
+            })
+        }
+
+        async fn create_resource(&self, _resource: Resource) -> Result<()> {
+            Ok(())
+        }
+
+        async fn update_resource(&self, _id: &str, _resource: Resource) -> Result<()> {
+            Ok(())
+        }
+
+        async fn delete_resource(&self, _id: &str) -> Result<()> {
+            Ok(())
+        }
+
+        fn capabilities(&self) -> ResourceCapability {
+            ResourceCapability {
+                subscribe: true,
+                list_changed: true,
+                templates: false,
+                bulk_get: true,
+                transactions: false,
+            }
+        }
+    }
+
+    fn assert_read_only(result: Result<()>) {
+        match result {
+            Err(Error::JsonRpc { code, message }) => {
+                assert_eq!(code, READ_ONLY_ERROR_CODE);
+                assert_eq!(message, "Resource is read-only");
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reads_are_delegated() {
+        let manager = ReadonlyResourceManager::new(SingleResource);
+        assert_eq!(manager.list_resources().await.unwrap().len(), 1);
+        assert_eq!(manager.get_resource("readme").await.unwrap().id, "readme");
+    }
+
+    #[tokio::test]
+    async fn test_mutations_are_rejected() {
+        let manager = ReadonlyResourceManager::new(SingleResource);
+        let resource = manager.get_resource("readme").await.unwrap();
+
+        assert_read_only(manager.create_resource(resource.clone()).await);
+        assert_read_only(manager.update_resource("readme", resource).await);
+        assert_read_only(manager.delete_resource("readme").await);
+    }
+
+    #[test]
+    fn test_subscribe_capability_is_stripped() {
+        let capabilities = ReadonlyResourceManager::new(SingleResource).capabilities();
+        assert!(!capabilities.subscribe);
+        assert!(capabilities.list_changed);
+    }
+}
@@ -0,0 +1,308 @@
+use async_trait::async_trait;
+use lru::LruCache;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+
+use super::pagination::Page;
+use super::resources::{
+    Resource, ResourceAuditEntry, ResourceContents, ResourceManager, ResourceOperation,
+    ResourceStream, ResourceTemplate,
+};
+use crate::{protocol::ResourceCapability, Result};
+
+/// Hit and miss counters of a `ResourceContentCache` or `PromptCache`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Lookups answered from the cache
+    pub hits: u64,
+    /// Lookups forwarded to the wrapped manager
+    pub misses: u64,
+}
+
+impl CacheStats {
+    /// Share of lookups answered from the cache, zero before the first lookup
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            total => self.hits as f64 / total as f64,
+        }
+    }
+}
+
+/// Caching wrapper around a `ResourceManager`
+///
+/// `get_resource` results are kept for `ttl`, with at most `max_entries` resources
+/// cached; the least recently used entry is evicted first. Updating or deleting a
+/// resource through the wrapper invalidates its entry.
+pub struct ResourceContentCache<M> {
+    inner: M,
+    ttl: Duration,
+    entries: Mutex<LruCache<String, (Instant, Resource)>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl<M: ResourceManager> ResourceContentCache<M> {
+    /// Wraps `inner`, caching up to `max_entries` resources for `ttl` each
+    pub fn new(inner: M, max_entries: usize, ttl: Duration) -> Self {
+        let capacity = NonZeroUsize::new(max_entries).unwrap_or(NonZeroUsize::MIN);
+        Self {
+            inner,
+            ttl,
+            entries: Mutex::new(LruCache::new(capacity)),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Cache hits and misses so far
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Drops the cached entry for a resource
+    pub fn invalidate(&self, id: &str) {
+        self.entries.lock().unwrap().pop(id);
+    }
+
+    /// The wrapped manager
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    fn cached(&self, id: &str) -> Option<Resource> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(id) {
+            Some((cached_at, resource)) if cached_at.elapsed() < self.ttl => Some(resource.clone()),
+            Some(_) => {
+                entries.pop(id);
+                None
+            }
+            None => None,
+        }
+    }
+}
+
+#[async_trait]
+impl<M: ResourceManager> ResourceManager for ResourceContentCache<M> {
+    async fn list_resources(&self) -> Result<Vec<Resource>> {
+        self.inner.list_resources().await
+    }
+
+    async fn list_resources_page(&self, cursor: Option<&str>) -> Result<Page<Resource>> {
+        self.inner.list_resources_page(cursor).await
+    }
+
+    async fn get_resource(&self, id: &str) -> Result<Resource> {
+        if let Some(resource) = self.cached(id) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(resource);
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let resource = self.inner.get_resource(id).await?;
+        self.entries
+            .lock()
+            .unwrap()
+            .put(id.to_string(), (Instant::now(), resource.clone()));
+        Ok(resource)
+    }
+
+    async fn create_resource(&self, resource: Resource) -> Result<()> {
+        self.invalidate(&resource.id);
+        self.inner.create_resource(resource).await
+    }
+
+    async fn update_resource(&self, id: &str, resource: Resource) -> Result<()> {
+        self.invalidate(id);
+        self.inner.update_resource(id, resource).await
+    }
+
+    async fn delete_resource(&self, id: &str) -> Result<()> {
+        self.invalidate(id);
+        self.inner.delete_resource(id).await
+    }
+
+    async fn transaction(&self, operations: Vec<ResourceOperation>) -> Result<()> {
+        for operation in &operations {
+            self.invalidate(operation.resource_id());
+        }
+        self.inner.transaction(operations).await
+    }
+
+    async fn audit(&self, entry: ResourceAuditEntry) {
+        self.inner.audit(entry).await
+    }
+
+    async fn stream_resource(&self, id: &str) -> Result<ResourceStream> {
+        self.inner.stream_resource(id).await
+    }
+
+    async fn resource_mime_type(&self, id: &str) -> Result<Option<String>> {
+        self.inner.resource_mime_type(id).await
+    }
+
+    async fn resource_contents(&self, resource: &Resource) -> Result<ResourceContents> {
+        self.inner.resource_contents(resource).await
+    }
+
+    async fn list_resource_templates(&self) -> Result<Vec<ResourceTemplate>> {
+        self.inner.list_resource_templates().await
+    }
+
+    fn capabilities(&self) -> ResourceCapability {
+        self.inner.capabilities()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{protocol::error_codes, Error};
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    /// Resource manager counting `get_resource` calls
+    #[derive(Default)]
+    struct CountingManager {
+        resources: Mutex<HashMap<String, Resource>>,
+        fetches: AtomicU64,
+    }
+
+    impl CountingManager {
+        fn with(ids: &[&str]) -> Self {
+            let manager = Self::default();
+            for id in ids {
+                manager
+                    .resources
+                    .lock()
+                    .unwrap()
+                    .insert(id.to_string(), resource(id, "v1"));
+            }
+            manager
+        }
+
+        fn fetches(&self) -> u64 {
+            self.fetches.load(Ordering::Relaxed)
+        }
+    }
+
+    #[async_trait]
+    impl ResourceManager for CountingManager {
+        async fn list_resources(&self) -> Result<Vec<Resource>> {
+            Ok(self.resources.lock().unwrap().values().cloned().collect())
+        }
+
+        async fn get_resource(&self, id: &str) -> Result<Resource> {
+            self.fetches.fetch_add(1, Ordering::Relaxed);
+            self.resources
+                .lock()
+                .unwrap()
+                .get(id)
+                .cloned()
+                .ok_or_else(|| Error::JsonRpc {
+                    code: error_codes::INVALID_PARAMS,
+                    message: format!("Resource not found: {}", id),
+                })
+        }
+
+        async fn create_resource(&self, resource: Resource) -> Result<()> {
+            self.resources
+                .lock()
+                .unwrap()
+                .insert(resource.id.clone(), resource);
+            Ok(())
+        }
+
+        async fn update_resource(&self, id: &str, resource: Resource) -> Result<()> {
+            self.resources
+                .lock()
+                .unwrap()
+                .insert(id.to_string(), resource);
+            Ok(())
+        }
+
+        async fn delete_resource(&self, id: &str) -> Result<()> {
+            self.resources.lock().unwrap().remove(id);
+            Ok(())
+        }
+    }
+
+    fn resource(id: &str, version: &str) -> Resource {
+        Resource {
+            id: id.to_string(),
+            type_: "file".to_string(),
+            metadata: json!({}),
+            content: Some(json!(version)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hits_within_ttl() {
+        let cache =
+            ResourceContentCache::new(CountingManager::with(&["a"]), 8, Duration::from_secs(60));
+
+        cache.get_resource("a").await.unwrap();
+        cache.get_resource("a").await.unwrap();
+
+        assert_eq!(cache.inner().fetches(), 1);
+        assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 1 });
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_refetches_after_ttl() {
+        let cache =
+            ResourceContentCache::new(CountingManager::with(&["a"]), 8, Duration::from_secs(60));
+
+        cache.get_resource("a").await.unwrap();
+        tokio::time::advance(Duration::from_secs(61)).await;
+        cache.get_resource("a").await.unwrap();
+
+        assert_eq!(cache.inner().fetches(), 2);
+        assert_eq!(cache.stats(), CacheStats { hits: 0, misses: 2 });
+    }
+
+    #[tokio::test]
+    async fn test_evicts_least_recently_used() {
+        let cache = ResourceContentCache::new(
+            CountingManager::with(&["a", "b", "c"]),
+            2,
+            Duration::from_secs(60),
+        );
+
+        cache.get_resource("a").await.unwrap();
+        cache.get_resource("b").await.unwrap();
+        cache.get_resource("a").await.unwrap();
+        cache.get_resource("c").await.unwrap();
+
+        // "b" was least recently used and got evicted, "a" is still cached
+        cache.get_resource("a").await.unwrap();
+        assert_eq!(cache.inner().fetches(), 3);
+        cache.get_resource("b").await.unwrap();
+        assert_eq!(cache.inner().fetches(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_update_and_delete_invalidate() {
+        let cache =
+            ResourceContentCache::new(CountingManager::with(&["a"]), 8, Duration::from_secs(60));
+
+        cache.get_resource("a").await.unwrap();
+        cache
+            .update_resource("a", resource("a", "v2"))
+            .await
+            .unwrap();
+        let updated = cache.get_resource("a").await.unwrap();
+        assert_eq!(updated.content, Some(json!("v2")));
+
+        cache.delete_resource("a").await.unwrap();
+        assert!(cache.get_resource("a").await.is_err());
+        assert_eq!(cache.stats(), CacheStats { hits: 0, misses: 3 });
+    }
+}
@@ -1,8 +1,17 @@
 use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::pin::Pin;
+use tokio::io::AsyncRead;
 
-use crate::Result;
+use super::{auth::AuthContext, pagination::Page};
+use crate::{
+    protocol::{ResourceCapability, ResponseError},
+    Error, Result,
+};
 
 /// Represents a resource
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,12 +26,264 @@ pub struct Resource {
     pub content: Option<Value>,
 }
 
+/// Content of a resource as text or as base64-encoded binary data
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ResourceContents {
+    /// Textual content
+    #[serde(rename_all = "camelCase")]
+    Text {
+        /// URI of the resource
+        uri: String,
+        /// MIME type of the content, if known
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        mime_type: Option<String>,
+        /// The content itself
+        text: String,
+    },
+    /// Binary content
+    #[serde(rename_all = "camelCase")]
+    Blob {
+        /// URI of the resource
+        uri: String,
+        /// MIME type of the content, if known
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        mime_type: Option<String>,
+        /// The content, base64-encoded
+        blob: String,
+    },
+}
+
+impl ResourceContents {
+    /// Binary content, base64-encoding `bytes`
+    pub fn from_bytes(uri: impl Into<String>, mime_type: impl Into<String>, bytes: &[u8]) -> Self {
+        ResourceContents::Blob {
+            uri: uri.into(),
+            mime_type: Some(mime_type.into()),
+            blob: STANDARD.encode(bytes),
+        }
+    }
+
+    /// Contents of a resource's `content` field
+    ///
+    /// Strings become text, other values become JSON text and a missing content
+    /// becomes empty text. The MIME type is taken from the `mimeType` metadata
+    /// entry, defaulting to `application/json` for JSON values.
+    pub fn from_resource(resource: &Resource) -> Result<Self> {
+        let mut mime_type = resource
+            .metadata
+            .get("mimeType")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        let text = match &resource.content {
+            None => String::new(),
+            Some(Value::String(text)) => text.clone(),
+            Some(value) => {
+                mime_type.get_or_insert_with(|| "application/json".to_string());
+                serde_json::to_string(value)?
+            }
+        };
+        Ok(ResourceContents::Text {
+            uri: resource.id.clone(),
+            mime_type,
+            text,
+        })
+    }
+
+    /// URI of the resource
+    pub fn uri(&self) -> &str {
+        match self {
+            ResourceContents::Text { uri, .. } | ResourceContents::Blob { uri, .. } => uri,
+        }
+    }
+
+    /// MIME type of the content, if known
+    pub fn mime_type(&self) -> Option<&str> {
+        match self {
+            ResourceContents::Text { mime_type, .. } | ResourceContents::Blob { mime_type, .. } => {
+                mime_type.as_deref()
+            }
+        }
+    }
+
+    /// Raw bytes of the content: text as UTF-8, blobs base64-decoded
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        match self {
+            ResourceContents::Text { text, .. } => Ok(text.clone().into_bytes()),
+            ResourceContents::Blob { blob, .. } => STANDARD
+                .decode(blob)
+                .map_err(|e| Error::Protocol(format!("Invalid base64 resource blob: {}", e))),
+        }
+    }
+}
+
+/// Result of `resources/get`: the resource together with its typed contents
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GetResourceResult {
+    /// The resource
+    #[serde(flatten)]
+    pub resource: Resource,
+    /// The resource's content as text or binary data
+    #[serde(default)]
+    pub contents: Vec<ResourceContents>,
+}
+
+/// Describes a family of resources addressable by a URI template (e.g. `file:///{path}`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceTemplate {
+    /// RFC 6570 URI template
+    pub uri_template: String,
+    /// Human-readable name
+    pub name: String,
+    /// Optional description of the resources matched by the template
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Optional MIME type shared by all matching resources
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+/// Resource content delivered incrementally
+pub type ResourceStream = Pin<Box<dyn AsyncRead + Send>>;
+
+/// Raw bytes of a resource's content: strings as UTF-8, other values as JSON
+pub fn content_bytes(content: Option<&Value>) -> Result<Vec<u8>> {
+    Ok(match content {
+        None => Vec::new(),
+        Some(Value::String(text)) => text.clone().into_bytes(),
+        Some(value) => serde_json::to_vec(value)?,
+    })
+}
+
+/// Result of `resources/templates/list`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListResourceTemplatesResult {
+    /// Templates supported by the server
+    #[serde(rename = "resourceTemplates")]
+    pub templates: Vec<ResourceTemplate>,
+}
+
+/// Entity tag of a resource: a hash of its serialized form
+pub fn resource_etag(resource: &Resource) -> Result<String> {
+    Ok(hex::encode(Sha256::digest(serde_json::to_vec(resource)?)))
+}
+
+/// Parameters of `resources/bulk_get`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkGetRequest {
+    /// Resources to fetch
+    pub ids: Vec<String>,
+    /// Entity tags the client already has, by resource ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub if_none_match: Option<HashMap<String, String>>,
+}
+
+/// A resource fetched by `resources/bulk_get`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceGetResult {
+    /// Resource ID
+    pub id: String,
+    /// Current entity tag of the resource
+    pub etag: String,
+    /// The resource, omitted when it still matches the client's entity tag
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource: Option<Resource>,
+}
+
+impl ResourceGetResult {
+    /// Whether the resource is unchanged since the client's copy
+    pub fn not_modified(&self) -> bool {
+        self.resource.is_none()
+    }
+}
+
+/// Result of `resources/bulk_get`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BulkGetResult {
+    /// Resources fetched, in request order
+    pub resources: Vec<ResourceGetResult>,
+    /// Errors for the resources that could not be fetched, by resource ID
+    #[serde(default)]
+    pub errors: HashMap<String, ResponseError>,
+}
+
+/// Parameters of `resources/read`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReadResourcesRequest {
+    /// Resources to read
+    pub ids: Vec<String>,
+}
+
+/// Result of `resources/read`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReadResourcesResult {
+    /// Contents of the resources read, in request order
+    pub contents: Vec<ResourceContents>,
+    /// Errors for the resources that could not be read, by resource ID
+    #[serde(default)]
+    pub errors: HashMap<String, ResponseError>,
+}
+
+/// One change applied by `resources/transaction`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ResourceOperation {
+    /// Create a resource
+    Create(Resource),
+    /// Replace the resource with the given ID
+    Update(String, Resource),
+    /// Delete the resource with the given ID
+    Delete(String),
+}
+
+impl ResourceOperation {
+    /// ID of the resource the operation changes
+    pub fn resource_id(&self) -> &str {
+        match self {
+            Self::Create(resource) => &resource.id,
+            Self::Update(id, _) | Self::Delete(id) => id,
+        }
+    }
+}
+
+/// Record of a change made to resources through the server
+///
+/// Handed to `ResourceManager::audit` after the change succeeded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceAuditEntry {
+    /// Method that made the change, e.g. `resources/create`
+    pub method: String,
+    /// IDs of the resources that changed, in the order they were changed
+    pub resource_ids: Vec<String>,
+    /// Who made the change, if the transport authenticated the caller
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<AuthContext>,
+}
+
+/// Parameters of `resources/transaction`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResourceTransactionRequest {
+    /// Changes to apply, in order
+    pub operations: Vec<ResourceOperation>,
+}
+
 /// Resource manager trait
 #[async_trait]
 pub trait ResourceManager: Send + Sync {
     /// Lists available resources
     async fn list_resources(&self) -> Result<Vec<Resource>>;
 
+    /// Lists one page of resources, starting at `cursor`
+    ///
+    /// The default returns everything from `list_resources` as a single page;
+    /// large catalogs should override this, e.g. with `Page::slice`.
+    async fn list_resources_page(&self, _cursor: Option<&str>) -> Result<Page<Resource>> {
+        Ok(Page::all(self.list_resources().await?))
+    }
+
     /// Gets a specific resource by ID
     async fn get_resource(&self, id: &str) -> Result<Resource>;
 
@@ -34,4 +295,488 @@ pub trait ResourceManager: Send + Sync {
 
     /// Deletes a resource
     async fn delete_resource(&self, id: &str) -> Result<()>;
+
+    /// Opens the content of a resource for streaming
+    ///
+    /// By default, `file` resources with a `path` in their metadata are read from
+    /// disk and other resources are buffered from `get_resource`. Managers with
+    /// large content should override this.
+    async fn stream_resource(&self, id: &str) -> Result<ResourceStream> {
+        let resource = self.get_resource(id).await?;
+        if resource.type_ == "file" {
+            if let Some(path) = resource.metadata.get("path").and_then(Value::as_str) {
+                return Ok(Box::pin(tokio::fs::File::open(path).await?));
+            }
+        }
+        Ok(Box::pin(std::io::Cursor::new(content_bytes(
+            resource.content.as_ref(),
+        )?)))
+    }
+
+    /// MIME type of a resource's content, if known
+    ///
+    /// Defaults to the `mimeType` entry of the resource metadata.
+    async fn resource_mime_type(&self, id: &str) -> Result<Option<String>> {
+        let resource = self.get_resource(id).await?;
+        Ok(resource
+            .metadata
+            .get("mimeType")
+            .and_then(Value::as_str)
+            .map(str::to_string))
+    }
+
+    /// Typed contents of a resource fetched with `get_resource`
+    ///
+    /// Defaults to `ResourceContents::from_resource`. Managers serving binary data
+    /// should override this and return `ResourceContents::from_bytes`.
+    async fn resource_contents(&self, resource: &Resource) -> Result<ResourceContents> {
+        ResourceContents::from_resource(resource)
+    }
+
+    /// Lists available resource templates
+    async fn list_resource_templates(&self) -> Result<Vec<ResourceTemplate>> {
+        Ok(Vec::new())
+    }
+
+    /// Fetches several resources at once
+    ///
+    /// Resources whose entity tag matches `if_none_match` are returned without
+    /// their content; failures are collected in `errors` rather than failing the
+    /// whole request. The default implementation calls `get_resource`
+    /// concurrently for every ID.
+    async fn bulk_get_resources(
+        &self,
+        ids: &[&str],
+        if_none_match: Option<&HashMap<String, String>>,
+    ) -> Result<BulkGetResult> {
+        let fetched = futures::future::join_all(ids.iter().map(|id| self.get_resource(id))).await;
+
+        let mut result = BulkGetResult::default();
+        for (id, fetched) in ids.iter().zip(fetched) {
+            match fetched.and_then(|resource| Ok((resource_etag(&resource)?, resource))) {
+                Ok((etag, resource)) => {
+                    let unchanged = if_none_match.and_then(|tags| tags.get(*id)) == Some(&etag);
+                    result.resources.push(ResourceGetResult {
+                        id: id.to_string(),
+                        etag,
+                        resource: (!unchanged).then_some(resource),
+                    });
+                }
+                Err(e) => {
+                    result.errors.insert(id.to_string(), e.into());
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Reads the contents of several resources at once
+    ///
+    /// Failures are collected in `errors` rather than failing the whole request.
+    /// The default implementation calls `get_resource` and `resource_contents`
+    /// concurrently for every ID; backends that can batch should override it.
+    async fn read_resources(&self, ids: &[String]) -> Result<ReadResourcesResult> {
+        let read = futures::future::join_all(ids.iter().map(|id| async move {
+            let resource = self.get_resource(id).await?;
+            self.resource_contents(&resource).await
+        }))
+        .await;
+
+        let mut result = ReadResourcesResult::default();
+        for (id, read) in ids.iter().zip(read) {
+            match read {
+                Ok(contents) => result.contents.push(contents),
+                Err(e) => {
+                    result.errors.insert(id.clone(), e.into());
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Applies several changes as one unit
+    ///
+    /// The default implementation is not atomic: it applies the operations in
+    /// order and, when one fails, undoes the completed ones in reverse order on a
+    /// best-effort basis before returning the failure. Backends with native
+    /// transactions should override it.
+    async fn transaction(&self, operations: Vec<ResourceOperation>) -> Result<()> {
+        // Each completed operation paired with the resource it replaced, if any
+        let mut completed: Vec<(ResourceOperation, Option<Resource>)> = Vec::new();
+        for operation in operations {
+            let applied = match &operation {
+                ResourceOperation::Create(resource) => {
+                    self.create_resource(resource.clone()).await.map(|_| None)
+                }
+                ResourceOperation::Update(id, resource) => match self.get_resource(id).await {
+                    Ok(previous) => self
+                        .update_resource(id, resource.clone())
+                        .await
+                        .map(|_| Some(previous)),
+                    Err(e) => Err(e),
+                },
+                ResourceOperation::Delete(id) => match self.get_resource(id).await {
+                    Ok(previous) => self.delete_resource(id).await.map(|_| Some(previous)),
+                    Err(e) => Err(e),
+                },
+            };
+
+            match applied {
+                Ok(previous) => completed.push((operation, previous)),
+                Err(e) => {
+                    for (operation, previous) in completed.into_iter().rev() {
+                        let _ = match (operation, previous) {
+                            (ResourceOperation::Create(resource), _) => {
+                                self.delete_resource(&resource.id).await
+                            }
+                            (ResourceOperation::Update(id, _), Some(previous)) => {
+                                self.update_resource(&id, previous).await
+                            }
+                            (ResourceOperation::Delete(_), Some(previous)) => {
+                                self.create_resource(previous).await
+                            }
+                            _ => Ok(()),
+                        };
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Records a change the server made through this manager
+    ///
+    /// Called after `resources/create`, `resources/update`, `resources/delete` and
+    /// `resources/transaction` succeed. The default does nothing.
+    async fn audit(&self, _entry: ResourceAuditEntry) {}
+
+    /// Resource capabilities to advertise for this manager
+    ///
+    /// Managers that override `list_resource_templates` should set `templates`;
+    /// `transactions` is off by default because the default `transaction` is not
+    /// atomic.
+    fn capabilities(&self) -> ResourceCapability {
+        ResourceCapability {
+            subscribe: false,
+            list_changed: false,
+            templates: false,
+            bulk_get: true,
+            transactions: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tokio::io::AsyncReadExt;
+
+    struct SingleResource(Resource);
+
+    #[async_trait]
+    impl ResourceManager for SingleResource {
+        async fn list_resources(&self) -> Result<Vec<Resource>> {
+            Ok(vec![self.0.clone()])
+        }
+
+        async fn get_resource(&self, _id: &str) -> Result<Resource> {
+            Ok(self.0.clone())
+        }
+
+        async fn create_resource(&self, _resource: Resource) -> Result<()> {
+            Ok(())
+        }
+
+        async fn update_resource(&self, _id: &str, _resource: Resource) -> Result<()> {
+            Ok(())
+        }
+
+        async fn delete_resource(&self, _id: &str) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    async fn read_all(mut stream: ResourceStream) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        stream.read_to_end(&mut bytes).await.unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_text_contents_round_trip() {
+        let contents = ResourceContents::Text {
+            uri: "file:///notes.txt".to_string(),
+            mime_type: Some("text/plain".to_string()),
+            text: "hello".to_string(),
+        };
+        let value = serde_json::to_value(&contents).unwrap();
+        assert_eq!(
+            value,
+            json!({ "uri": "file:///notes.txt", "mimeType": "text/plain", "text": "hello" })
+        );
+        assert_eq!(
+            serde_json::from_value::<ResourceContents>(value).unwrap(),
+            contents
+        );
+        assert_eq!(contents.to_bytes().unwrap(), b"hello");
+    }
+
+    #[test]
+    fn test_blob_contents_round_trip() {
+        let bytes = [0u8, 159, 146, 150, 255];
+        let contents =
+            ResourceContents::from_bytes("file:///data.bin", "application/octet-stream", &bytes);
+        let value = serde_json::to_value(&contents).unwrap();
+        assert_eq!(value["blob"], json!("AJ+Slv8="));
+        assert!(value.get("text").is_none());
+
+        let decoded: ResourceContents = serde_json::from_value(value).unwrap();
+        assert_eq!(decoded, contents);
+        assert_eq!(decoded.uri(), "file:///data.bin");
+        assert_eq!(decoded.to_bytes().unwrap(), bytes);
+
+        let corrupt = ResourceContents::Blob {
+            uri: "file:///data.bin".to_string(),
+            mime_type: None,
+            blob: "not base64!".to_string(),
+        };
+        assert!(matches!(corrupt.to_bytes(), Err(Error::Protocol(_))));
+    }
+
+    #[test]
+    fn test_contents_from_resource() {
+        let resource = Resource {
+            id: "config".to_string(),
+            type_: "memory".to_string(),
+            metadata: json!({}),
+            content: Some(json!({ "debug": true })),
+        };
+        assert_eq!(
+            ResourceContents::from_resource(&resource).unwrap(),
+            ResourceContents::Text {
+                uri: "config".to_string(),
+                mime_type: Some("application/json".to_string()),
+                text: r#"{"debug":true}"#.to_string(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_default_stream_buffers_content() {
+        let manager = SingleResource(Resource {
+            id: "greeting".to_string(),
+            type_: "text".to_string(),
+            metadata: json!({ "mimeType": "text/plain" }),
+            content: Some(json!("hello")),
+        });
+
+        let stream = manager.stream_resource("greeting").await.unwrap();
+        assert_eq!(read_all(stream).await, b"hello");
+        assert_eq!(
+            manager.resource_mime_type("greeting").await.unwrap(),
+            Some("text/plain".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_file_resources_stream_from_disk() {
+        let path = std::env::temp_dir().join(format!("mcp-stream-{}.bin", std::process::id()));
+        tokio::fs::write(&path, [0u8, 1, 2, 255]).await.unwrap();
+
+        let manager = SingleResource(Resource {
+            id: "blob".to_string(),
+            type_: "file".to_string(),
+            metadata: json!({ "path": path.to_str().unwrap() }),
+            content: None,
+        });
+        let bytes = read_all(manager.stream_resource("blob").await.unwrap()).await;
+        tokio::fs::remove_file(&path).await.unwrap();
+
+        assert_eq!(bytes, [0u8, 1, 2, 255]);
+        assert!(manager.resource_mime_type("blob").await.unwrap().is_none());
+    }
+
+    /// Resources keyed by ID; unknown IDs fail
+    struct Library(HashMap<String, Resource>);
+
+    #[async_trait]
+    impl ResourceManager for Library {
+        async fn list_resources(&self) -> Result<Vec<Resource>> {
+            Ok(self.0.values().cloned().collect())
+        }
+
+        async fn get_resource(&self, id: &str) -> Result<Resource> {
+            self.0
+                .get(id)
+                .cloned()
+                .ok_or_else(|| crate::Error::JsonRpc {
+                    code: -32002,
+                    message: format!("Resource not found: {}", id),
+                })
+        }
+
+        async fn create_resource(&self, _resource: Resource) -> Result<()> {
+            Ok(())
+        }
+
+        async fn update_resource(&self, _id: &str, _resource: Resource) -> Result<()> {
+            Ok(())
+        }
+
+        async fn delete_resource(&self, _id: &str) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    fn text(id: &str, content: &str) -> Resource {
+        Resource {
+            id: id.to_string(),
+            type_: "text".to_string(),
+            metadata: json!({}),
+            content: Some(json!(content)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_transaction_is_rolled_back() {
+        let manager = crate::testing::MockResourceManager::new()
+            .with_resource(text("a", "alpha"))
+            .with_resource(text("b", "beta"));
+
+        let result = manager
+            .transaction(vec![
+                ResourceOperation::Update("a".to_string(), text("a", "ALPHA")),
+                ResourceOperation::Delete("b".to_string()),
+                ResourceOperation::Create(text("c", "gamma")),
+                ResourceOperation::Delete("missing".to_string()),
+            ])
+            .await;
+        assert!(result.is_err());
+
+        let mut ids: Vec<String> = manager
+            .list_resources()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|resource| resource.id)
+            .collect();
+        ids.sort();
+        assert_eq!(ids, ["a", "b"]);
+        let a = manager.get_resource("a").await.unwrap();
+        assert_eq!(a.content, Some(json!("alpha")));
+    }
+
+    #[test]
+    fn test_transaction_wire_format() {
+        let request = ResourceTransactionRequest {
+            operations: vec![
+                ResourceOperation::Update("a".to_string(), text("a", "alpha")),
+                ResourceOperation::Delete("b".to_string()),
+            ],
+        };
+        let value = serde_json::to_value(&request).unwrap();
+        assert_eq!(value["operations"][1], json!({ "delete": "b" }));
+        assert_eq!(value["operations"][0]["update"][0], json!("a"));
+    }
+
+    #[tokio::test]
+    async fn test_default_read_resources() {
+        let library = Library(
+            [text("a", "alpha"), text("b", "beta")]
+                .into_iter()
+                .map(|r| (r.id.clone(), r))
+                .collect(),
+        );
+
+        let ids = ["b", "missing", "a"].map(String::from);
+        let result = library.read_resources(&ids).await.unwrap();
+        let uris: Vec<&str> = result.contents.iter().map(|c| c.uri()).collect();
+        assert_eq!(uris, ["b", "a"]);
+        assert!(matches!(
+            &result.contents[0],
+            ResourceContents::Text { text, .. } if text == "beta"
+        ));
+        assert_eq!(result.errors.len(), 1);
+        assert!(result.errors.contains_key("missing"));
+    }
+
+    #[tokio::test]
+    async fn test_default_bulk_get() {
+        let library = Library(
+            [text("a", "alpha"), text("b", "beta")]
+                .into_iter()
+                .map(|r| (r.id.clone(), r))
+                .collect(),
+        );
+        assert!(library.capabilities().bulk_get);
+
+        let first = library
+            .bulk_get_resources(&["b", "missing", "a"], None)
+            .await
+            .unwrap();
+        let ids: Vec<&str> = first.resources.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, ["b", "a"]);
+        assert!(first.resources.iter().all(|r| !r.not_modified()));
+        assert_eq!(first.errors["missing"].code, -32002);
+
+        // Resending the etag of "a" skips its content
+        let known = HashMap::from([
+            ("a".to_string(), first.resources[1].etag.clone()),
+            ("b".to_string(), "stale".to_string()),
+        ]);
+        let second = library
+            .bulk_get_resources(&["a", "b"], Some(&known))
+            .await
+            .unwrap();
+        assert!(second.resources[0].not_modified());
+        assert_eq!(second.resources[0].etag, first.resources[1].etag);
+        assert_eq!(second.resources[1].resource.as_ref().unwrap().id, "b");
+        assert!(second.errors.is_empty());
+    }
+
+    #[test]
+    fn test_bulk_get_serialization() {
+        let request: BulkGetRequest =
+            serde_json::from_value(json!({ "ids": ["a"], "ifNoneMatch": { "a": "tag" } })).unwrap();
+        assert_eq!(request.if_none_match.unwrap()["a"], "tag");
+
+        let result = BulkGetResult {
+            resources: vec![ResourceGetResult {
+                id: "a".to_string(),
+                etag: "tag".to_string(),
+                resource: None,
+            }],
+            errors: HashMap::new(),
+        };
+        assert_eq!(
+            serde_json::to_value(&result).unwrap(),
+            json!({ "resources": [{ "id": "a", "etag": "tag" }], "errors": {} })
+        );
+    }
+
+    #[test]
+    fn test_resource_template_serialization() {
+        let template = ResourceTemplate {
+            uri_template: "file:///{path}".to_string(),
+            name: "Project files".to_string(),
+            description: None,
+            mime_type: Some("text/plain".to_string()),
+        };
+
+        let json = serde_json::to_value(&template).unwrap();
+        assert_eq!(
+            json,
+            json!({
+                "uriTemplate": "file:///{path}",
+                "name": "Project files",
+                "mimeType": "text/plain"
+            })
+        );
+
+        let parsed: ResourceTemplate = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.uri_template, template.uri_template);
+        assert!(parsed.description.is_none());
+    }
 }
@@ -0,0 +1,203 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Kind of schema violation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ViolationKind {
+    /// A required property is missing
+    MissingRequired,
+    /// A value has the wrong JSON type
+    TypeMismatch,
+    /// A property is not allowed by `additionalProperties: false`
+    UnexpectedProperty,
+    /// A value is not one of the allowed `enum` values
+    NotInEnum,
+}
+
+/// A single place where a value does not conform to its schema
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchemaViolation {
+    /// Location of the offending value, e.g. `$.options.limit`
+    pub path: String,
+    /// Kind of violation
+    pub kind: ViolationKind,
+    /// Human-readable description
+    pub message: String,
+}
+
+/// Validates a value against a JSON Schema
+///
+/// Supports the subset of JSON Schema used to describe MCP parameters: `type`,
+/// `properties`, `required`, `additionalProperties: false`, `items` and `enum`.
+/// Unknown keywords are ignored.
+pub fn validate(schema: &Value, value: &Value) -> Vec<SchemaViolation> {
+    let mut violations = Vec::new();
+    validate_at(schema, value, "$", &mut violations);
+    violations
+}
+
+/// Names of the properties a schema marks as required
+pub fn required_properties(schema: &Value) -> Vec<String> {
+    schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|required| {
+            required
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn validate_at(schema: &Value, value: &Value, path: &str, violations: &mut Vec<SchemaViolation>) {
+    if let Some(expected) = schema.get("type") {
+        if !matches_type(expected, value) {
+            violations.push(SchemaViolation {
+                path: path.to_string(),
+                kind: ViolationKind::TypeMismatch,
+                message: format!("expected {}, found {}", expected, type_name(value)),
+            });
+            return;
+        }
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            violations.push(SchemaViolation {
+                path: path.to_string(),
+                kind: ViolationKind::NotInEnum,
+                message: format!("{} is not one of the allowed values", value),
+            });
+        }
+    }
+
+    match value {
+        Value::Object(object) => validate_object(schema, object, path, violations),
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    validate_at(
+                        item_schema,
+                        item,
+                        &format!("{}[{}]", path, index),
+                        violations,
+                    );
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn validate_object(
+    schema: &Value,
+    object: &Map<String, Value>,
+    path: &str,
+    violations: &mut Vec<SchemaViolation>,
+) {
+    for name in required_properties(schema) {
+        if !object.contains_key(&name) {
+            violations.push(SchemaViolation {
+                path: format!("{}.{}", path, name),
+                kind: ViolationKind::MissingRequired,
+                message: format!("missing required property '{}'", name),
+            });
+        }
+    }
+
+    let properties = schema.get("properties").and_then(Value::as_object);
+    let additional_allowed = schema.get("additionalProperties") != Some(&Value::Bool(false));
+
+    for (name, property) in object {
+        match properties.and_then(|properties| properties.get(name)) {
+            Some(property_schema) => {
+                validate_at(
+                    property_schema,
+                    property,
+                    &format!("{}.{}", path, name),
+                    violations,
+                );
+            }
+            None if !additional_allowed => violations.push(SchemaViolation {
+                path: format!("{}.{}", path, name),
+                kind: ViolationKind::UnexpectedProperty,
+                message: format!("unexpected property '{}'", name),
+            }),
+            None => {}
+        }
+    }
+}
+
+fn matches_type(expected: &Value, value: &Value) -> bool {
+    match expected {
+        Value::String(name) => matches_type_name(name, value),
+        Value::Array(names) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .any(|name| matches_type_name(name, value)),
+        _ => true,
+    }
+}
+
+fn matches_type_name(name: &str, value: &Value) -> bool {
+    match name {
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_nested_values_are_validated() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "tags": { "type": "array", "items": { "type": "string" } },
+                "mode": { "enum": ["fast", "slow"] }
+            }
+        });
+
+        let violations = validate(&schema, &json!({ "tags": ["a", 1], "mode": "medium" }));
+
+        assert_eq!(violations.len(), 2);
+        assert!(violations
+            .iter()
+            .any(|v| v.path == "$.tags[1]" && v.kind == ViolationKind::TypeMismatch));
+        assert!(violations
+            .iter()
+            .any(|v| v.path == "$.mode" && v.kind == ViolationKind::NotInEnum));
+    }
+
+    #[test]
+    fn test_integer_and_union_types() {
+        let schema = json!({ "type": ["integer", "null"] });
+
+        assert!(validate(&schema, &json!(3)).is_empty());
+        assert!(validate(&schema, &Value::Null).is_empty());
+        assert_eq!(validate(&schema, &json!(3.5)).len(), 1);
+    }
+}
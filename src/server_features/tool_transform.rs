@@ -0,0 +1,242 @@
+use async_trait::async_trait;
+use serde_json::Value;
+use std::sync::Arc;
+
+use super::pagination::Page;
+use super::schema::SchemaViolation;
+use super::tools::{Content, ListToolsFilter, Tool, ToolExecutionContext, ToolManager, ToolResult};
+use crate::{protocol::FeatureCapability, Result};
+
+/// Post-processing step applied to tool results before they are returned
+///
+/// Use it to sanitize, enrich or normalize results, e.g. stripping internal paths
+/// or adding provenance metadata. An error replaces the result.
+#[async_trait]
+pub trait ToolResultTransformer: Send + Sync {
+    /// Transforms the result of a tool executed with `context`
+    async fn transform(
+        &self,
+        result: ToolResult,
+        context: &ToolExecutionContext,
+    ) -> Result<ToolResult>;
+}
+
+/// Wrapper running every result of a `ToolManager` through a chain of transformers
+///
+/// Transformers run in the order they were added, each receiving the output of
+/// the previous one. Everything other than execution is delegated unchanged.
+pub struct TransformingToolManager<M> {
+    inner: M,
+    transformers: Vec<Arc<dyn ToolResultTransformer>>,
+}
+
+impl<M: ToolManager> TransformingToolManager<M> {
+    /// Wraps `inner` without any transformers
+    pub fn new(inner: M) -> Self {
+        Self {
+            inner,
+            transformers: Vec::new(),
+        }
+    }
+
+    /// Appends `transformer` to the chain
+    pub fn with_transformer(mut self, transformer: impl ToolResultTransformer + 'static) -> Self {
+        self.transformers.push(Arc::new(transformer));
+        self
+    }
+
+    /// The wrapped manager
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    async fn transform(
+        &self,
+        mut result: ToolResult,
+        context: &ToolExecutionContext,
+    ) -> Result<ToolResult> {
+        for transformer in &self.transformers {
+            result = transformer.transform(result, context).await?;
+        }
+        Ok(result)
+    }
+}
+
+#[async_trait]
+impl<M: ToolManager> ToolManager for TransformingToolManager<M> {
+    async fn list_tools(&self) -> Result<Vec<Tool>> {
+        self.inner.list_tools().await
+    }
+
+    async fn get_tool(&self, id: &str) -> Result<Tool> {
+        self.inner.get_tool(id).await
+    }
+
+    async fn list_tools_filtered(&self, filter: &ListToolsFilter) -> Result<Vec<Tool>> {
+        self.inner.list_tools_filtered(filter).await
+    }
+
+    async fn list_tools_page(
+        &self,
+        filter: &ListToolsFilter,
+        cursor: Option<&str>,
+    ) -> Result<Page<Tool>> {
+        self.inner.list_tools_page(filter, cursor).await
+    }
+
+    async fn execute_tool(&self, id: &str, params: Value) -> Result<ToolResult> {
+        let result = self.inner.execute_tool(id, params).await?;
+        self.transform(result, &ToolExecutionContext::default())
+            .await
+    }
+
+    async fn execute_tool_with_context(
+        &self,
+        id: &str,
+        params: Value,
+        context: &ToolExecutionContext,
+    ) -> Result<ToolResult> {
+        let result = self
+            .inner
+            .execute_tool_with_context(id, params, context)
+            .await?;
+        self.transform(result, context).await
+    }
+
+    async fn validate_tool_params(&self, id: &str, params: &Value) -> Result<Vec<SchemaViolation>> {
+        self.inner.validate_tool_params(id, params).await
+    }
+
+    async fn cancel_tool(&self, id: &str) -> Result<()> {
+        self.inner.cancel_tool(id).await
+    }
+
+    fn capabilities(&self) -> FeatureCapability {
+        self.inner.capabilities()
+    }
+}
+
+/// Transformer shortening text content longer than `max_chars` characters
+///
+/// Truncated text ends with an ellipsis and is `max_chars` characters long
+/// including it. A result with truncated text loses its structured content,
+/// which would otherwise still carry the full output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TruncatingTransformer {
+    /// Longest text kept, in characters
+    pub max_chars: usize,
+}
+
+impl TruncatingTransformer {
+    /// Truncates text content to `max_chars` characters
+    pub fn new(max_chars: usize) -> Self {
+        Self { max_chars }
+    }
+
+    /// `text` cut to `max_chars` characters, or `None` if it already fits
+    fn truncate(&self, text: &str) -> Option<String> {
+        if text.chars().count() <= self.max_chars {
+            return None;
+        }
+        let mut truncated: String = text
+            .chars()
+            .take(self.max_chars.saturating_sub(1))
+            .collect();
+        if self.max_chars > 0 {
+            truncated.push('…');
+        }
+        Some(truncated)
+    }
+}
+
+#[async_trait]
+impl ToolResultTransformer for TruncatingTransformer {
+    async fn transform(
+        &self,
+        mut result: ToolResult,
+        _context: &ToolExecutionContext,
+    ) -> Result<ToolResult> {
+        let mut truncated = false;
+        for content in &mut result.content {
+            if let Content::Text { text } = content {
+                if let Some(shorter) = self.truncate(text) {
+                    *text = shorter;
+                    truncated = true;
+                }
+            }
+        }
+        if truncated {
+            result.structured_content = None;
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockToolManager;
+    use serde_json::json;
+
+    /// Appends a marker to every text item
+    struct Mark(&'static str);
+
+    #[async_trait]
+    impl ToolResultTransformer for Mark {
+        async fn transform(
+            &self,
+            mut result: ToolResult,
+            _context: &ToolExecutionContext,
+        ) -> Result<ToolResult> {
+            for content in &mut result.content {
+                if let Content::Text { text } = content {
+                    text.push_str(self.0);
+                }
+            }
+            Ok(result)
+        }
+    }
+
+    fn tool(id: &str) -> Tool {
+        Tool {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            parameters: json!({}),
+            requires_approval: false,
+            tags: Vec::new(),
+            estimated_duration: None,
+            side_effects: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transformers_run_in_registration_order() {
+        let tools = TransformingToolManager::new(
+            MockToolManager::new().with_tool(tool("greet"), json!("hi")),
+        )
+        .with_transformer(Mark(" [a]"))
+        .with_transformer(Mark(" [b]"));
+
+        let result = tools.execute_tool("greet", json!({})).await.unwrap();
+        assert_eq!(result, ToolResult::text("hi [a] [b]"));
+        assert_eq!(tools.inner().executions().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_truncating_transformer_trims_long_text() {
+        let tools = TransformingToolManager::new(
+            MockToolManager::new()
+                .with_tool(tool("short"), json!("fits"))
+                .with_tool(tool("long"), json!({ "body": "0123456789" })),
+        )
+        .with_transformer(TruncatingTransformer::new(8));
+
+        let result = tools.execute_tool("short", json!({})).await.unwrap();
+        assert_eq!(result, ToolResult::text("fits"));
+
+        let result = tools.execute_tool("long", json!({})).await.unwrap();
+        assert_eq!(result.content, [Content::text("{\"body\"…")]);
+        assert_eq!(result.structured_content, None);
+    }
+}
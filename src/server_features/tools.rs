@@ -1,10 +1,157 @@
 use async_trait::async_trait;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::Value;
+use std::time::Duration;
 
-use crate::Result;
+use super::auth::AuthContext;
+use super::pagination::Page;
+use super::progress::{NoopProgressSink, ProgressSink};
+use super::resources::ResourceContents;
+use super::schema::{self, SchemaViolation};
+use crate::{
+    protocol::{FeatureCapability, OperationId, RequestMeta},
+    Result,
+};
+
+/// A piece of a streamed tool result
+///
+/// Sent by the server in `notifications/tools/chunk` while answering
+/// `tools/execute/stream`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolResultChunk {
+    /// Format of `data`, one of the negotiated streaming formats
+    pub format: String,
+    /// Chunk content
+    pub data: Value,
+}
+
+/// One item of a tool result's content
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Content {
+    /// Plain text
+    Text {
+        /// The text
+        text: String,
+    },
+    /// An image
+    #[serde(rename_all = "camelCase")]
+    Image {
+        /// The image, base64-encoded
+        data: String,
+        /// MIME type of the image
+        mime_type: String,
+    },
+    /// Contents of a resource
+    Resource {
+        /// The embedded contents
+        resource: ResourceContents,
+    },
+}
+
+impl Content {
+    /// Text content
+    pub fn text(text: impl Into<String>) -> Self {
+        Content::Text { text: text.into() }
+    }
+}
+
+/// Result of a tool execution
+///
+/// A tool that fails in a way the caller should see sets `is_error`; the result
+/// is still sent as a successful response, so the caller can read the content.
+/// JSON-RPC errors are reserved for failures to run the tool at all, such as an
+/// unknown ID.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolResult {
+    /// Content of the result
+    pub content: Vec<Content>,
+    /// Whether the tool failed
+    #[serde(default)]
+    pub is_error: bool,
+    /// The result as a JSON value, for callers that process it further
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structured_content: Option<Value>,
+}
+
+impl ToolResult {
+    /// Successful result holding `text`
+    pub fn text(text: impl Into<String>) -> Self {
+        Self {
+            content: vec![Content::text(text)],
+            is_error: false,
+            structured_content: None,
+        }
+    }
+
+    /// Failed result describing the failure in `message`
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            is_error: true,
+            ..Self::text(message)
+        }
+    }
+
+    /// The result as a JSON value
+    ///
+    /// This is the structured content if there is any, the text of a result
+    /// holding a single text item, and the serialized content otherwise.
+    pub fn into_value(self) -> Value {
+        if let Some(value) = self.structured_content {
+            return value;
+        }
+        match self.content.as_slice() {
+            [Content::Text { text }] => Value::String(text.clone()),
+            content => serde_json::to_value(content).unwrap_or(Value::Null),
+        }
+    }
+}
+
+/// Wraps a bare value: strings become text, anything else becomes its JSON text
+/// and is kept as structured content
+impl From<Value> for ToolResult {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::String(text) => Self::text(text),
+            value => Self {
+                structured_content: Some(value.clone()),
+                ..Self::text(value.to_string())
+            },
+        }
+    }
+}
+
+/// Kind of state a tool reads or changes outside of its result
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SideEffect {
+    /// Reads files
+    ReadsFileSystem,
+    /// Creates, changes or deletes files
+    WritesFileSystem,
+    /// Opens network connections
+    NetworkAccess,
+    /// Queries a database
+    DatabaseRead,
+    /// Changes data in a database
+    DatabaseWrite,
+    /// Calls a third-party service
+    ExternalApiCall,
+}
+
+impl SideEffect {
+    /// Whether the side effect leaves all state unchanged
+    pub fn is_read(self) -> bool {
+        matches!(self, SideEffect::ReadsFileSystem | SideEffect::DatabaseRead)
+    }
+}
 
 /// Represents a tool
+///
+/// `estimated_duration` and `side_effects` are reported by the tool itself so
+/// clients can present it appropriately; the runtime neither checks nor enforces
+/// them.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Tool {
     /// Unique identifier for the tool
@@ -17,6 +164,151 @@ pub struct Tool {
     pub parameters: Value,
     /// Whether the tool requires user approval
     pub requires_approval: bool,
+    /// Labels used to group and filter tools
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Typical execution time, sent in milliseconds
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_millis",
+        deserialize_with = "deserialize_millis"
+    )]
+    pub estimated_duration: Option<Duration>,
+    /// State the tool reads or changes
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub side_effects: Vec<SideEffect>,
+}
+
+fn serialize_millis<S>(
+    duration: &Option<Duration>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    duration
+        .map(|duration| u64::try_from(duration.as_millis()).unwrap_or(u64::MAX))
+        .serialize(serializer)
+}
+
+fn deserialize_millis<'de, D>(deserializer: D) -> std::result::Result<Option<Duration>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::<u64>::deserialize(deserializer)?.map(Duration::from_millis))
+}
+
+impl Tool {
+    /// Whether every declared side effect only reads state
+    ///
+    /// A tool declaring no side effects counts as read-only. Like the
+    /// declarations themselves, this is advisory.
+    pub fn is_read_only(&self) -> bool {
+        self.side_effects.iter().all(|effect| effect.is_read())
+    }
+
+    /// Validates parameters against the tool's parameter schema
+    pub fn validate_params(&self, params: &Value) -> Vec<SchemaViolation> {
+        schema::validate(&self.parameters, params)
+    }
+}
+
+/// Criteria for `tools/list`; every criterion that is set must hold
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListToolsFilter {
+    /// Keep tools whose ID starts with this prefix
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name_prefix: Option<String>,
+    /// Keep tools carrying all of these tags
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    /// Keep tools whose `requires_approval` equals this value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requires_approval: Option<bool>,
+}
+
+impl ListToolsFilter {
+    /// Whether `tool` meets every criterion of the filter
+    pub fn matches(&self, tool: &Tool) -> bool {
+        self.name_prefix
+            .as_ref()
+            .is_none_or(|prefix| tool.id.starts_with(prefix.as_str()))
+            && self
+                .tags
+                .as_ref()
+                .is_none_or(|tags| tags.iter().all(|tag| tool.tags.contains(tag)))
+            && self
+                .requires_approval
+                .is_none_or(|required| tool.requires_approval == required)
+    }
+}
+
+/// Result of `tools/validate`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidateToolResult {
+    /// Whether the parameters are valid
+    pub valid: bool,
+    /// Why the parameters are invalid; empty when they are valid
+    pub violations: Vec<SchemaViolation>,
+}
+
+impl From<Vec<SchemaViolation>> for ValidateToolResult {
+    fn from(violations: Vec<SchemaViolation>) -> Self {
+        Self {
+            valid: violations.is_empty(),
+            violations,
+        }
+    }
+}
+
+/// Per-call context handed to a tool execution
+pub struct ToolExecutionContext {
+    /// Where the tool reports its progress
+    pub progress: Box<dyn ProgressSink>,
+    /// Metadata the caller sent in `_meta`
+    pub meta: RequestMeta,
+    /// Who is calling, when the transport authenticated the caller
+    pub auth: Option<AuthContext>,
+    /// Operation the call belongs to, when the request carried an `_operationId`
+    pub operation_id: Option<OperationId>,
+}
+
+impl ToolExecutionContext {
+    /// Creates a context reporting progress to `progress`
+    pub fn new(progress: impl ProgressSink + 'static) -> Self {
+        Self {
+            progress: Box::new(progress),
+            meta: RequestMeta::default(),
+            auth: None,
+            operation_id: None,
+        }
+    }
+
+    /// Sets the request metadata
+    pub fn with_meta(mut self, meta: RequestMeta) -> Self {
+        self.meta = meta;
+        self
+    }
+
+    /// Sets the caller's identity
+    pub fn with_auth(mut self, auth: Option<AuthContext>) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    /// Sets the operation the call belongs to
+    pub fn with_operation_id(mut self, operation_id: Option<OperationId>) -> Self {
+        self.operation_id = operation_id;
+        self
+    }
+}
+
+impl Default for ToolExecutionContext {
+    fn default() -> Self {
+        Self::new(NoopProgressSink)
+    }
 }
 
 /// Tool manager trait
@@ -26,11 +318,139 @@ pub trait ToolManager: Send + Sync {
     async fn list_tools(&self) -> Result<Vec<Tool>>;
 
     /// Gets a specific tool by ID
+    ///
+    /// Implementations should fail with `error_codes::METHOD_NOT_FOUND` for unknown IDs.
     async fn get_tool(&self, id: &str) -> Result<Tool>;
 
+    /// Lists the tools matching `filter`
+    ///
+    /// The default filters the result of `list_tools`; managers with large
+    /// registries can override this to filter at the source.
+    async fn list_tools_filtered(&self, filter: &ListToolsFilter) -> Result<Vec<Tool>> {
+        let mut tools = self.list_tools().await?;
+        tools.retain(|tool| filter.matches(tool));
+        Ok(tools)
+    }
+
+    /// Lists one page of the tools matching `filter`, starting at `cursor`
+    ///
+    /// The default returns everything from `list_tools_filtered` as a single page.
+    async fn list_tools_page(
+        &self,
+        filter: &ListToolsFilter,
+        _cursor: Option<&str>,
+    ) -> Result<Page<Tool>> {
+        Ok(Page::all(self.list_tools_filtered(filter).await?))
+    }
+
     /// Executes a tool with given parameters
-    async fn execute_tool(&self, id: &str, params: Value) -> Result<Value>;
+    ///
+    /// Failures of the tool itself should be reported as a result with `is_error`
+    /// set rather than as an `Err`.
+    async fn execute_tool(&self, id: &str, params: Value) -> Result<ToolResult>;
+
+    /// Executes a tool with access to a per-call context
+    ///
+    /// Tools that report progress override this; the default ignores the context.
+    async fn execute_tool_with_context(
+        &self,
+        id: &str,
+        params: Value,
+        _context: &ToolExecutionContext,
+    ) -> Result<ToolResult> {
+        self.execute_tool(id, params).await
+    }
+
+    /// Validates parameters for a tool without executing it
+    ///
+    /// The default checks them against the schema returned by `get_tool`.
+    async fn validate_tool_params(&self, id: &str, params: &Value) -> Result<Vec<SchemaViolation>> {
+        Ok(self.get_tool(id).await?.validate_params(params))
+    }
 
     /// Cancels a running tool execution
     async fn cancel_tool(&self, id: &str) -> Result<()>;
+
+    /// Tool capabilities to advertise for this manager
+    ///
+    /// Managers whose tools change at runtime should set `list_changed` and publish
+    /// `ServerEvent::ToolChanged` on the server's event bus after each change.
+    fn capabilities(&self) -> FeatureCapability {
+        FeatureCapability {
+            list_changed: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_tool_results_serialize_to_wire_shape() {
+        assert_eq!(
+            serde_json::to_value(ToolResult::text("42")).unwrap(),
+            json!({ "content": [{ "type": "text", "text": "42" }], "isError": false })
+        );
+        assert_eq!(
+            serde_json::to_value(ToolResult::error("division by zero")).unwrap(),
+            json!({
+                "content": [{ "type": "text", "text": "division by zero" }],
+                "isError": true
+            })
+        );
+
+        let image = Content::Image {
+            data: "aGk=".to_string(),
+            mime_type: "image/png".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_value(&image).unwrap(),
+            json!({ "type": "image", "data": "aGk=", "mimeType": "image/png" })
+        );
+    }
+
+    #[test]
+    fn test_tool_hints_round_trip() {
+        let json = json!({
+            "id": "sync",
+            "name": "Sync",
+            "description": "Syncs the mirror",
+            "parameters": {},
+            "requires_approval": false,
+            "estimated_duration": 1500,
+            "side_effects": ["reads_file_system", "network_access"]
+        });
+        let tool: Tool = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(tool.estimated_duration, Some(Duration::from_millis(1500)));
+        assert!(!tool.is_read_only());
+        assert_eq!(serde_json::to_value(&tool).unwrap(), json);
+
+        let tool = Tool {
+            side_effects: vec![SideEffect::ReadsFileSystem, SideEffect::DatabaseRead],
+            estimated_duration: None,
+            ..tool
+        };
+        assert!(tool.is_read_only());
+        let json = serde_json::to_value(&tool).unwrap();
+        assert!(json.get("estimated_duration").is_none());
+    }
+
+    #[test]
+    fn test_bare_values_convert_to_results() {
+        let result = ToolResult::from(json!({ "sum": 3 }));
+        assert!(!result.is_error);
+        assert_eq!(result.content, [Content::text(r#"{"sum":3}"#)]);
+        assert_eq!(result.into_value(), json!({ "sum": 3 }));
+
+        let result = ToolResult::from(json!("done"));
+        assert_eq!(result.structured_content, None);
+        assert_eq!(result.into_value(), json!("done"));
+
+        let parsed: ToolResult =
+            serde_json::from_value(json!({ "content": [{ "type": "text", "text": "ok" }] }))
+                .unwrap();
+        assert_eq!(parsed, ToolResult::text("ok"));
+    }
 }
@@ -0,0 +1,14 @@
+//! Helpers for testing code built on this crate
+//!
+//! Enabled with the `testing` feature. Import everything at once through
+//! [`prelude`].
+
+pub mod prelude;
+
+mod recording;
+mod resources;
+mod tools;
+
+pub use recording::RecordingTransport;
+pub use resources::MockResourceManager;
+pub use tools::MockToolManager;
@@ -0,0 +1,254 @@
+//! Everything needed to write tests against this crate
+//!
+//! ```ignore
+//! use mcprotocol_rs::testing::prelude::*;
+//! ```
+
+use proptest::prelude::*;
+use proptest::strategy::ValueTree;
+use proptest::test_runner::TestRunner;
+use serde_json::Value;
+
+pub use super::{MockResourceManager, MockToolManager, RecordingTransport};
+pub use crate::client::McpClient;
+pub use crate::protocol::{
+    Message, Method, Notification, Request, RequestId, Response, ResponseError,
+};
+pub use crate::server::{McpServer, ServerBuilder};
+pub use crate::transport::{MemoryTransport, Transport};
+
+/// Create a client connected to an in-memory peer
+///
+/// The returned transport is the peer end, used to play the server side.
+pub async fn make_connected_client() -> crate::Result<(McpClient, MemoryTransport)> {
+    let (client, server) = MemoryTransport::pair();
+    let client = McpClient::connect(Box::new(client)).await?;
+    Ok((client, server))
+}
+
+/// Create a server and a client connected to it in memory, with the handshake done
+///
+/// Both sides use default capabilities. The server's dispatcher answers `initialize`,
+/// so the client is initialized although the server is not running yet; spawn
+/// `server.run()` before sending further requests.
+pub async fn make_initialized_server_client_pair() -> (McpServer, McpClient) {
+    let (client, transport) = MemoryTransport::pair();
+    let mut server = McpServer::builder().build();
+    let client = McpClient::connect(Box::new(client))
+        .await
+        .expect("memory transports connect without failing");
+
+    let handshake = async {
+        let Ok(Message::Request(request)) = transport.receive().await else {
+            panic!("expected initialize");
+        };
+        let response = server.dispatcher().handle_request(&request).await;
+        transport.send(Message::Response(response)).await.unwrap();
+        let Ok(Message::Notification(initialized)) = transport.receive().await else {
+            panic!("expected initialized");
+        };
+        server.dispatcher().handle_notification(&initialized).await;
+    };
+    let client_info = crate::protocol::ImplementationInfo {
+        name: "test-client".to_string(),
+        version: "0.0.0".to_string(),
+    };
+    let (initialized, ()) = tokio::join!(
+        client.initialize(client_info, Default::default()),
+        handshake
+    );
+    initialized.expect("the server accepts the default capabilities");
+
+    server.add_transport(Box::new(transport));
+    (server, client)
+}
+
+/// Strategy generating request IDs of either kind
+pub fn arb_request_id() -> impl Strategy<Value = RequestId> {
+    prop_oneof![
+        any::<i64>().prop_map(RequestId::Number),
+        "[a-zA-Z0-9_-]{1,16}".prop_map(RequestId::String),
+        "\\PC{0,16}".prop_map(RequestId::String),
+    ]
+}
+
+/// Strategy generating response IDs, which may also be `null`
+pub fn arb_response_id() -> impl Strategy<Value = RequestId> {
+    prop_oneof![9 => arb_request_id(), 1 => Just(RequestId::Null)]
+}
+
+/// Strategy generating method names, from typical `a/b` paths to arbitrary text
+pub fn arb_method() -> impl Strategy<Value = String> {
+    prop_oneof![
+        "[a-z]{1,10}(/[a-z_]{1,10})?",
+        Just("$/progress".to_string()),
+        "\\PC{1,24}",
+    ]
+}
+
+/// Strategy generating the structured params of requests and notifications
+fn arb_params() -> impl Strategy<Value = Option<Value>> {
+    prop::option::of(prop_oneof![
+        prop::collection::btree_map("[a-z]{1,8}", arb_json_value(), 0..4)
+            .prop_map(|map| Value::Object(map.into_iter().collect())),
+        prop::collection::vec(arb_json_value(), 0..4).prop_map(Value::Array),
+    ])
+}
+
+/// Strategy generating JSON values, including nested arrays and objects
+pub fn arb_json_value() -> impl Strategy<Value = Value> {
+    let leaf = prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::Bool),
+        any::<i64>().prop_map(Value::from),
+        "[ -~]{0,16}".prop_map(Value::String),
+    ];
+    leaf.prop_recursive(3, 32, 4, |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 0..4).prop_map(Value::Array),
+            prop::collection::btree_map("[a-z]{1,8}", inner, 0..4)
+                .prop_map(|map| Value::Object(map.into_iter().collect())),
+        ]
+    })
+}
+
+/// Strategy generating requests with arbitrary methods, IDs and params
+pub fn arb_request() -> impl Strategy<Value = Request> {
+    (arb_method(), arb_params(), arb_request_id()).prop_map(|(method, params, id)| Request {
+        jsonrpc: crate::protocol::JSONRPC_VERSION.to_string(),
+        method,
+        params,
+        id,
+    })
+}
+
+/// Strategy generating notifications with arbitrary methods and params
+pub fn arb_notification() -> impl Strategy<Value = Notification> {
+    (arb_method(), arb_params()).prop_map(|(method, params)| Notification {
+        jsonrpc: crate::protocol::JSONRPC_VERSION.to_string(),
+        method,
+        params,
+    })
+}
+
+/// Strategy generating success and error responses
+pub fn arb_response() -> impl Strategy<Value = Response> {
+    let error = (
+        any::<i32>(),
+        "\\PC{0,24}",
+        prop::option::of(arb_json_value()),
+    )
+        .prop_map(|(code, message, data)| ResponseError {
+            code,
+            message,
+            data,
+        });
+    (
+        prop_oneof![arb_json_value().prop_map(Ok), error.prop_map(Err),],
+        arb_response_id(),
+    )
+        .prop_map(|(outcome, id)| match outcome {
+            Ok(result) => Response::success(result, id),
+            Err(error) => Response::error(error, id),
+        })
+}
+
+/// Strategy generating messages of every kind, including non-empty batches
+pub fn arb_message() -> impl Strategy<Value = Message> {
+    let single = prop_oneof![
+        arb_request().prop_map(Message::Request),
+        arb_response().prop_map(Message::Response),
+        arb_notification().prop_map(Message::Notification),
+    ]
+    .boxed();
+    prop_oneof![
+        3 => single.clone(),
+        1 => prop::collection::vec(single, 1..4).prop_map(Message::Batch),
+    ]
+}
+
+/// Generate a single random request
+pub fn arbitrary_request() -> Request {
+    arb_request()
+        .new_tree(&mut TestRunner::default())
+        .expect("request strategy never rejects")
+        .current()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server_features::{Tool, ToolManager};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_connected_client_reaches_peer() {
+        let (client, server) = make_connected_client().await.unwrap();
+        let server = RecordingTransport::new(server);
+
+        client.notify(Method::Initialized, None).await.unwrap();
+        server.receive().await.unwrap();
+
+        assert_eq!(server.received().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_server_client_pair_is_initialized() {
+        let (server, client) = make_initialized_server_client_pair().await;
+        assert!(client.server_capabilities().is_some());
+
+        let running = tokio::spawn(server.run());
+        client.ping().await.unwrap();
+        running.abort();
+    }
+
+    #[tokio::test]
+    async fn test_mock_tool_manager_records_executions() {
+        let tool = Tool {
+            id: "echo".to_string(),
+            name: "Echo".to_string(),
+            description: "Echoes its input".to_string(),
+            parameters: json!({}),
+            requires_approval: false,
+            tags: Vec::new(),
+            estimated_duration: None,
+            side_effects: Vec::new(),
+        };
+        let tools = MockToolManager::new().with_tool(tool, json!({ "ok": true }));
+
+        let result = tools.execute_tool("echo", json!({ "x": 1 })).await.unwrap();
+        assert_eq!(result.into_value(), json!({ "ok": true }));
+        assert_eq!(
+            tools.executions(),
+            vec![("echo".to_string(), json!({ "x": 1 }))]
+        );
+        assert!(tools.execute_tool("missing", json!({})).await.is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_message_round_trips(message in arb_message()) {
+            let json = serde_json::to_string(&message).unwrap();
+            prop_assert_eq!(serde_json::from_str::<Message>(&json).unwrap(), message);
+        }
+
+        #[test]
+        fn prop_colliding_ids_are_flagged(n in any::<i64>()) {
+            let mut used = std::collections::HashSet::new();
+            let number = Request::new(Method::Ping, None, RequestId::Number(n));
+            let string = Request::new(Method::Ping, None, RequestId::String(n.to_string()));
+            prop_assert!(number.validate_id_uniqueness(&mut used));
+            prop_assert!(!string.validate_id_uniqueness(&mut used));
+        }
+    }
+
+    #[test]
+    fn test_arbitrary_request_serializes() {
+        let request = arbitrary_request();
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(matches!(
+            serde_json::from_str::<Message>(&json).unwrap(),
+            Message::Request(_)
+        ));
+    }
+}
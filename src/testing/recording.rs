@@ -0,0 +1,74 @@
+use async_trait::async_trait;
+use std::sync::Mutex;
+
+use crate::{
+    protocol::{Message, Notification},
+    transport::{ClientFilter, MessageOrigin, Transport, TransportHealth},
+    Result,
+};
+
+/// Transport wrapper that records every message sent and received
+pub struct RecordingTransport<T: Transport> {
+    inner: T,
+    sent: Mutex<Vec<Message>>,
+    received: Mutex<Vec<Message>>,
+}
+
+impl<T: Transport> RecordingTransport<T> {
+    /// Wrap a transport
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            sent: Mutex::new(Vec::new()),
+            received: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Messages successfully sent so far
+    pub fn sent(&self) -> Vec<Message> {
+        self.sent.lock().unwrap().clone()
+    }
+
+    /// Messages received so far
+    pub fn received(&self) -> Vec<Message> {
+        self.received.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl<T: Transport> Transport for RecordingTransport<T> {
+    async fn initialize(&mut self) -> Result<()> {
+        self.inner.initialize().await
+    }
+
+    async fn send(&self, message: Message) -> Result<()> {
+        self.inner.send(message.clone()).await?;
+        self.sent.lock().unwrap().push(message);
+        Ok(())
+    }
+
+    async fn send_filtered(&self, notification: Notification, accept: ClientFilter) -> Result<()> {
+        let message = Message::Notification(notification.clone());
+        self.inner.send_filtered(notification, accept).await?;
+        self.sent.lock().unwrap().push(message);
+        Ok(())
+    }
+
+    async fn receive(&self) -> Result<Message> {
+        Ok(self.receive_with_origin().await?.0)
+    }
+
+    async fn receive_with_origin(&self) -> Result<(Message, MessageOrigin)> {
+        let (message, origin) = self.inner.receive_with_origin().await?;
+        self.received.lock().unwrap().push(message.clone());
+        Ok((message, origin))
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        self.inner.close().await
+    }
+
+    async fn health(&self) -> TransportHealth {
+        self.inner.health().await
+    }
+}
@@ -0,0 +1,123 @@
+use async_trait::async_trait;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::{
+    protocol::ResourceCapability,
+    server_features::{Page, Resource, ResourceAuditEntry, ResourceManager},
+    Error, Result,
+};
+
+/// In-memory resource manager serving its listing in pages
+#[derive(Default)]
+pub struct MockResourceManager {
+    resources: Mutex<Vec<Resource>>,
+    page_size: Option<usize>,
+    page_requests: AtomicUsize,
+    transactions: bool,
+    audits: Mutex<Vec<ResourceAuditEntry>>,
+}
+
+impl MockResourceManager {
+    /// Create a manager without resources, listing everything in one page
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a resource
+    pub fn with_resource(self, resource: Resource) -> Self {
+        self.resources.lock().unwrap().push(resource);
+        self
+    }
+
+    /// List at most `page_size` resources per `resources/list` request
+    pub fn with_page_size(mut self, page_size: usize) -> Self {
+        self.page_size = Some(page_size);
+        self
+    }
+
+    /// Advertise `resources/transaction` support, served by the default `transaction`
+    pub fn with_transactions(mut self) -> Self {
+        self.transactions = true;
+        self
+    }
+
+    /// Number of pages listed so far
+    pub fn page_requests(&self) -> usize {
+        self.page_requests.load(Ordering::SeqCst)
+    }
+
+    /// Changes audited so far, oldest first
+    pub fn audits(&self) -> Vec<ResourceAuditEntry> {
+        self.audits.lock().unwrap().clone()
+    }
+
+    fn not_found(id: &str) -> Error {
+        Error::Protocol(format!("Resource not found: {}", id))
+    }
+}
+
+#[async_trait]
+impl ResourceManager for MockResourceManager {
+    async fn list_resources(&self) -> Result<Vec<Resource>> {
+        Ok(self.resources.lock().unwrap().clone())
+    }
+
+    async fn list_resources_page(&self, cursor: Option<&str>) -> Result<Page<Resource>> {
+        self.page_requests.fetch_add(1, Ordering::SeqCst);
+        let resources = self.list_resources().await?;
+        match self.page_size {
+            Some(page_size) => Page::slice(resources, cursor, page_size),
+            None => Ok(Page::all(resources)),
+        }
+    }
+
+    async fn get_resource(&self, id: &str) -> Result<Resource> {
+        self.resources
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|resource| resource.id == id)
+            .cloned()
+            .ok_or_else(|| Self::not_found(id))
+    }
+
+    async fn create_resource(&self, resource: Resource) -> Result<()> {
+        self.resources.lock().unwrap().push(resource);
+        Ok(())
+    }
+
+    async fn update_resource(&self, id: &str, resource: Resource) -> Result<()> {
+        let mut resources = self.resources.lock().unwrap();
+        let existing = resources
+            .iter_mut()
+            .find(|resource| resource.id == id)
+            .ok_or_else(|| Self::not_found(id))?;
+        *existing = resource;
+        Ok(())
+    }
+
+    async fn delete_resource(&self, id: &str) -> Result<()> {
+        let mut resources = self.resources.lock().unwrap();
+        let before = resources.len();
+        resources.retain(|resource| resource.id != id);
+        if resources.len() == before {
+            return Err(Self::not_found(id));
+        }
+        Ok(())
+    }
+
+    async fn audit(&self, entry: ResourceAuditEntry) {
+        self.audits.lock().unwrap().push(entry);
+    }
+
+    fn capabilities(&self) -> ResourceCapability {
+        ResourceCapability {
+            subscribe: false,
+            list_changed: false,
+            templates: false,
+            bulk_get: true,
+            transactions: self.transactions,
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::{
+    protocol::error_codes,
+    server_features::{Tool, ToolManager, ToolResult},
+    Error, Result,
+};
+
+/// Tool manager returning canned results and recording every execution
+#[derive(Default)]
+pub struct MockToolManager {
+    tools: Vec<Tool>,
+    results: HashMap<String, ToolResult>,
+    executions: Mutex<Vec<(String, Value)>>,
+}
+
+impl MockToolManager {
+    /// Create a manager without tools
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a tool that returns `result` when executed
+    pub fn with_tool(mut self, tool: Tool, result: impl Into<ToolResult>) -> Self {
+        self.results.insert(tool.id.clone(), result.into());
+        self.tools.push(tool);
+        self
+    }
+
+    /// Tool executions so far, as `(tool_id, params)` pairs
+    pub fn executions(&self) -> Vec<(String, Value)> {
+        self.executions.lock().unwrap().clone()
+    }
+
+    fn not_found(id: &str) -> Error {
+        Error::JsonRpc {
+            code: error_codes::INVALID_PARAMS,
+            message: format!("Tool not found: {}", id),
+        }
+    }
+}
+
+#[async_trait]
+impl ToolManager for MockToolManager {
+    async fn list_tools(&self) -> Result<Vec<Tool>> {
+        Ok(self.tools.clone())
+    }
+
+    async fn get_tool(&self, id: &str) -> Result<Tool> {
+        self.tools
+            .iter()
+            .find(|tool| tool.id == id)
+            .cloned()
+            .ok_or_else(|| Error::JsonRpc {
+                code: error_codes::METHOD_NOT_FOUND,
+                message: format!("Tool not found: {}", id),
+            })
+    }
+
+    async fn execute_tool(&self, id: &str, params: Value) -> Result<ToolResult> {
+        let result = self
+            .results
+            .get(id)
+            .cloned()
+            .ok_or_else(|| Self::not_found(id))?;
+        self.executions
+            .lock()
+            .unwrap()
+            .push((id.to_string(), params));
+        Ok(result)
+    }
+
+    async fn cancel_tool(&self, id: &str) -> Result<()> {
+        self.get_tool(id).await.map(|_| ())
+    }
+}
@@ -0,0 +1,308 @@
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tokio::task::JoinHandle;
+
+use super::{ClientFilter, HealthStatus, MessageOrigin, Transport, TransportHealth};
+use crate::{
+    protocol::{Message, Notification},
+    Error, Result,
+};
+
+/// What `send` does when the buffer is full
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BackpressureMode {
+    /// Wait until there is room in the buffer
+    #[default]
+    Block,
+    /// Fail with `Error::Transport("Send buffer full")`
+    Drop,
+}
+
+/// Backpressure configuration
+#[derive(Debug, Clone)]
+pub struct BackpressureConfig {
+    /// Number of outgoing messages that can be queued
+    pub buffer_size: usize,
+    /// Behaviour when the buffer is full
+    pub mode: BackpressureMode,
+    /// How long `close` waits for queued messages to be sent
+    pub drain_timeout: Duration,
+}
+
+impl Default for BackpressureConfig {
+    fn default() -> Self {
+        Self {
+            buffer_size: 64,
+            mode: BackpressureMode::default(),
+            drain_timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Message waiting in the send buffer, with the clients it is for
+enum Outgoing {
+    /// Passed to `send`
+    Message(Message),
+    /// Passed to `send_filtered`
+    Filtered(Notification, ClientFilter),
+}
+
+/// Transport wrapper queueing outgoing messages in a bounded buffer
+///
+/// A background task started by `initialize` sends queued messages through the
+/// inner transport one at a time, so a slow peer fills the buffer instead of
+/// stalling or growing memory without limit. Receiving is not buffered.
+pub struct BackpressureTransport<T: Transport> {
+    inner: Arc<T>,
+    config: BackpressureConfig,
+    sender: Mutex<Option<mpsc::Sender<Outgoing>>>,
+    drain: Option<JoinHandle<()>>,
+}
+
+impl<T: Transport + 'static> BackpressureTransport<T> {
+    /// Wrap a transport
+    pub fn new(inner: T, config: BackpressureConfig) -> Self {
+        Self {
+            inner: Arc::new(inner),
+            config,
+            sender: Mutex::new(None),
+            drain: None,
+        }
+    }
+
+    /// Fraction of the buffer currently occupied, from 0.0 to 1.0
+    pub fn buffer_utilization(&self) -> f32 {
+        match self.sender.lock().unwrap().as_ref() {
+            Some(sender) => {
+                let queued = sender.max_capacity() - sender.capacity();
+                queued as f32 / sender.max_capacity() as f32
+            }
+            None => 0.0,
+        }
+    }
+
+    fn sender(&self) -> Result<mpsc::Sender<Outgoing>> {
+        self.sender
+            .lock()
+            .unwrap()
+            .clone()
+            .ok_or(Error::NotInitialized)
+    }
+
+    async fn enqueue(&self, outgoing: Outgoing) -> Result<()> {
+        let sender = self.sender()?;
+        match self.config.mode {
+            BackpressureMode::Block => sender
+                .send(outgoing)
+                .await
+                .map_err(|_| Error::Transport("Transport closed".into())),
+            BackpressureMode::Drop => sender.try_send(outgoing).map_err(|e| match e {
+                TrySendError::Full(_) => Error::Transport("Send buffer full".into()),
+                TrySendError::Closed(_) => Error::Transport("Transport closed".into()),
+            }),
+        }
+    }
+}
+
+#[async_trait]
+impl<T: Transport + 'static> Transport for BackpressureTransport<T> {
+    async fn initialize(&mut self) -> Result<()> {
+        Arc::get_mut(&mut self.inner)
+            .ok_or_else(|| Error::Transport("Transport already initialized".into()))?
+            .initialize()
+            .await?;
+
+        let (tx, mut rx) = mpsc::channel::<Outgoing>(self.config.buffer_size.max(1));
+        let inner = Arc::clone(&self.inner);
+        self.drain = Some(tokio::spawn(async move {
+            while let Some(outgoing) = rx.recv().await {
+                let sent = match outgoing {
+                    Outgoing::Message(message) => inner.send(message).await,
+                    Outgoing::Filtered(notification, accept) => {
+                        inner.send_filtered(notification, accept).await
+                    }
+                };
+                if let Err(e) = sent {
+                    tracing::warn!("failed to send buffered message: {}", e);
+                }
+            }
+        }));
+        *self.sender.lock().unwrap() = Some(tx);
+        Ok(())
+    }
+
+    async fn send(&self, message: Message) -> Result<()> {
+        self.enqueue(Outgoing::Message(message)).await
+    }
+
+    async fn send_filtered(&self, notification: Notification, accept: ClientFilter) -> Result<()> {
+        self.enqueue(Outgoing::Filtered(notification, accept)).await
+    }
+
+    async fn receive(&self) -> Result<Message> {
+        self.inner.receive().await
+    }
+
+    async fn receive_with_origin(&self) -> Result<(Message, MessageOrigin)> {
+        self.inner.receive_with_origin().await
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        // Dropping the sender lets the drain task finish once the buffer is empty
+        self.sender.lock().unwrap().take();
+
+        if let Some(mut drain) = self.drain.take() {
+            if tokio::time::timeout(self.config.drain_timeout, &mut drain)
+                .await
+                .is_err()
+            {
+                tracing::warn!("discarding buffered messages after drain timeout");
+                drain.abort();
+                let _ = drain.await;
+            }
+        }
+
+        Arc::get_mut(&mut self.inner)
+            .ok_or_else(|| Error::Transport("Transport still in use".into()))?
+            .close()
+            .await
+    }
+
+    /// Health of the inner transport, `Degraded` while the buffer is full
+    async fn health(&self) -> TransportHealth {
+        let mut health = self.inner.health().await;
+        if self.buffer_utilization() >= 1.0 && health.status != HealthStatus::Unhealthy {
+            health.status = HealthStatus::Degraded;
+            health.error = Some("Send buffer full".into());
+        }
+        health
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{Method, Notification};
+    use tokio::sync::Semaphore;
+
+    /// Transport whose `send` only completes when a permit is released
+    struct SlowTransport {
+        gate: Arc<Semaphore>,
+        sent: Arc<Mutex<Vec<Message>>>,
+    }
+
+    #[async_trait]
+    impl Transport for SlowTransport {
+        async fn initialize(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn send(&self, message: Message) -> Result<()> {
+            self.gate.acquire().await.unwrap().forget();
+            self.sent.lock().unwrap().push(message);
+            Ok(())
+        }
+
+        async fn receive(&self) -> Result<Message> {
+            std::future::pending().await
+        }
+
+        async fn close(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    fn slow(
+        mode: BackpressureMode,
+    ) -> (
+        BackpressureTransport<SlowTransport>,
+        Arc<Semaphore>,
+        Arc<Mutex<Vec<Message>>>,
+    ) {
+        let gate = Arc::new(Semaphore::new(0));
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let transport = BackpressureTransport::new(
+            SlowTransport {
+                gate: Arc::clone(&gate),
+                sent: Arc::clone(&sent),
+            },
+            BackpressureConfig {
+                buffer_size: 4,
+                mode,
+                drain_timeout: Duration::from_secs(1),
+            },
+        );
+        (transport, gate, sent)
+    }
+
+    fn notification() -> Message {
+        Message::Notification(Notification::new(Method::Initialized, None))
+    }
+
+    #[tokio::test]
+    async fn test_drop_mode_rejects_when_full() {
+        let (mut transport, gate, sent) = slow(BackpressureMode::Drop);
+        assert!(matches!(
+            transport.send(notification()).await,
+            Err(Error::NotInitialized)
+        ));
+        transport.initialize().await.unwrap();
+
+        let mut accepted = 0;
+        let error = loop {
+            match transport.send(notification()).await {
+                Ok(()) => accepted += 1,
+                Err(e) => break e,
+            }
+        };
+        assert!(matches!(error, Error::Transport(ref m) if m == "Send buffer full"));
+        // The buffer is full, plus at most one message held by the slow peer
+        assert!((4..=5).contains(&accepted));
+        assert_eq!(transport.buffer_utilization(), 1.0);
+        assert_eq!(transport.health().await.status, HealthStatus::Degraded);
+
+        gate.add_permits(accepted);
+        transport.close().await.unwrap();
+        assert_eq!(sent.lock().unwrap().len(), accepted);
+    }
+
+    #[tokio::test]
+    async fn test_block_mode_waits_for_room() {
+        let (mut transport, gate, sent) = slow(BackpressureMode::Block);
+        transport.initialize().await.unwrap();
+
+        for _ in 0..5 {
+            let _ = tokio::time::timeout(Duration::from_millis(20), transport.send(notification()))
+                .await;
+        }
+        assert!(
+            tokio::time::timeout(Duration::from_millis(20), transport.send(notification()))
+                .await
+                .is_err()
+        );
+
+        gate.add_permits(1);
+        tokio::time::timeout(Duration::from_secs(1), transport.send(notification()))
+            .await
+            .unwrap()
+            .unwrap();
+
+        gate.add_permits(100);
+        transport.close().await.unwrap();
+        assert!(sent.lock().unwrap().len() >= 5);
+        assert_eq!(transport.buffer_utilization(), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_close_gives_up_after_drain_timeout() {
+        let (mut transport, _gate, sent) = slow(BackpressureMode::Drop);
+        transport.config.drain_timeout = Duration::from_millis(20);
+        transport.initialize().await.unwrap();
+        transport.send(notification()).await.unwrap();
+
+        transport.close().await.unwrap();
+        assert!(sent.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,202 @@
+use async_trait::async_trait;
+use std::time::Duration;
+
+use super::{ClientFilter, HealthStatus, MessageOrigin, Transport, TransportHealth};
+use crate::{
+    protocol::{Message, Notification},
+    Error, Result,
+};
+
+/// Transport trying several transports in order until one initializes
+///
+/// `initialize` moves on to the next transport when one fails or does not finish
+/// within `connect_timeout`. Afterwards every call goes to the transport that
+/// connected. If none does, the error lists why each one failed.
+pub struct FallbackTransport {
+    transports: Vec<Box<dyn Transport>>,
+    connect_timeout: Duration,
+    active: Option<usize>,
+}
+
+impl FallbackTransport {
+    /// Try `transports` in order, giving each `connect_timeout` to initialize
+    pub fn new(transports: Vec<Box<dyn Transport>>, connect_timeout: Duration) -> Self {
+        Self {
+            transports,
+            connect_timeout,
+            active: None,
+        }
+    }
+
+    /// Index of the transport that initialized, if any
+    pub fn active_index(&self) -> Option<usize> {
+        self.active
+    }
+
+    fn active(&self) -> Result<&dyn Transport> {
+        self.active
+            .map(|index| &*self.transports[index])
+            .ok_or(Error::NotInitialized)
+    }
+}
+
+#[async_trait]
+impl Transport for FallbackTransport {
+    async fn initialize(&mut self) -> Result<()> {
+        if self.transports.is_empty() {
+            return Err(Error::Configuration("No transports to fall back on".into()));
+        }
+
+        let mut failures = Vec::new();
+        for (index, transport) in self.transports.iter_mut().enumerate() {
+            let error =
+                match tokio::time::timeout(self.connect_timeout, transport.initialize()).await {
+                    Ok(Ok(())) => {
+                        self.active = Some(index);
+                        return Ok(());
+                    }
+                    Ok(Err(e)) => e.to_string(),
+                    Err(_) => format!("timed out after {:?}", self.connect_timeout),
+                };
+            tracing::warn!("Transport {} failed to initialize: {}", index, error);
+            failures.push(format!("[{}] {}", index, error));
+        }
+
+        Err(Error::Transport(format!(
+            "All transports failed: {}",
+            failures.join("; ")
+        )))
+    }
+
+    async fn send(&self, message: Message) -> Result<()> {
+        self.active()?.send(message).await
+    }
+
+    async fn send_raw(&self, json: &str) -> Result<()> {
+        self.active()?.send_raw(json).await
+    }
+
+    async fn send_filtered(&self, notification: Notification, accept: ClientFilter) -> Result<()> {
+        self.active()?.send_filtered(notification, accept).await
+    }
+
+    async fn receive(&self) -> Result<Message> {
+        self.active()?.receive().await
+    }
+
+    async fn receive_with_origin(&self) -> Result<(Message, MessageOrigin)> {
+        self.active()?.receive_with_origin().await
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        match self.active.take() {
+            Some(index) => self.transports[index].close().await,
+            None => Ok(()),
+        }
+    }
+
+    /// Health of the transport that connected, `Unhealthy` before one has
+    async fn health(&self) -> TransportHealth {
+        match self.active() {
+            Ok(transport) => transport.health().await,
+            Err(e) => TransportHealth {
+                error: Some(e.to_string()),
+                ..TransportHealth::with_status(HealthStatus::Unhealthy)
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{Method, Notification};
+    use crate::transport::MemoryTransport;
+
+    /// Transport whose initialization fails or never completes
+    struct Unreachable {
+        hang: bool,
+    }
+
+    #[async_trait]
+    impl Transport for Unreachable {
+        async fn initialize(&mut self) -> Result<()> {
+            if self.hang {
+                std::future::pending::<()>().await;
+            }
+            Err(Error::Transport("connection refused".into()))
+        }
+
+        async fn send(&self, _message: Message) -> Result<()> {
+            unreachable!()
+        }
+
+        async fn receive(&self) -> Result<Message> {
+            unreachable!()
+        }
+
+        async fn close(&mut self) -> Result<()> {
+            unreachable!()
+        }
+    }
+
+    fn notification() -> Message {
+        Message::Notification(Notification::new(Method::Initialized, None))
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_to_next_transport() {
+        let (working, peer) = MemoryTransport::pair();
+        let mut transport = FallbackTransport::new(
+            vec![
+                Box::new(Unreachable { hang: false }),
+                Box::new(Unreachable { hang: true }),
+                Box::new(working),
+            ],
+            Duration::from_millis(50),
+        );
+        assert!(matches!(
+            transport.send(notification()).await,
+            Err(Error::NotInitialized)
+        ));
+        assert!(matches!(
+            transport.receive().await,
+            Err(Error::NotInitialized)
+        ));
+        assert_eq!(transport.health().await.status, HealthStatus::Unhealthy);
+
+        transport.initialize().await.unwrap();
+        assert_eq!(transport.active_index(), Some(2));
+        assert_eq!(transport.health().await, TransportHealth::unknown());
+
+        transport.send(notification()).await.unwrap();
+        assert!(matches!(
+            peer.receive().await.unwrap(),
+            Message::Notification(_)
+        ));
+        peer.send(notification()).await.unwrap();
+        assert!(matches!(
+            transport.receive().await.unwrap(),
+            Message::Notification(_)
+        ));
+
+        transport.close().await.unwrap();
+        assert!(peer.receive().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reports_every_failure() {
+        let mut transport = FallbackTransport::new(
+            vec![
+                Box::new(Unreachable { hang: false }),
+                Box::new(Unreachable { hang: true }),
+            ],
+            Duration::from_millis(50),
+        );
+
+        let err = transport.initialize().await.unwrap_err().to_string();
+        assert!(err.contains("[0] Transport error: connection refused"));
+        assert!(err.contains("[1] timed out"));
+        assert_eq!(transport.active_index(), None);
+    }
+}
@@ -0,0 +1,433 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex as StdMutex};
+
+use futures::Stream;
+use tokio::sync::{broadcast, mpsc, oneshot, Mutex};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tonic::transport::{server::TcpIncoming, Channel, ClientTlsConfig, Endpoint};
+use tonic::Status;
+
+use super::Transport;
+use crate::{
+    protocol::{Message, RequestId},
+    Error, Result,
+};
+
+/// Code generated from `proto/mcp.proto`
+pub mod proto {
+    tonic::include_proto!("mcp");
+}
+
+use proto::mcp_service_client::McpServiceClient;
+use proto::mcp_service_server::{McpService, McpServiceServer};
+
+/// Messages a server may push before the slowest subscriber falls behind
+const SUBSCRIBER_CAPACITY: usize = 256;
+
+fn encode(message: &Message) -> Result<proto::Message> {
+    Ok(proto::Message {
+        json: serde_json::to_string(message)?,
+    })
+}
+
+/// The MCP message carried by `message`, or `None` if it is empty
+fn decode(message: proto::Message) -> Result<Option<Message>> {
+    if message.json.is_empty() {
+        return Ok(None);
+    }
+    serde_json::from_str(&message.json)
+        .map(Some)
+        .map_err(|e| Error::Parse(e.to_string()))
+}
+
+fn status_error(status: Status) -> Error {
+    Error::Transport(format!("gRPC call failed: {}", status))
+}
+
+/// Client side of the gRPC transport
+///
+/// Requests are sent with the `Send` RPC, which returns their response; messages
+/// the server pushes arrive over a `Subscribe` stream opened by `initialize`.
+pub struct GrpcClientTransport {
+    endpoint: String,
+    tls: bool,
+    client: Option<McpServiceClient<Channel>>,
+    sender: StdMutex<Option<mpsc::UnboundedSender<Result<Message>>>>,
+    receiver: Mutex<mpsc::UnboundedReceiver<Result<Message>>>,
+    subscription: Option<JoinHandle<()>>,
+}
+
+impl GrpcClientTransport {
+    /// Transport connecting to `endpoint`, e.g. `http://127.0.0.1:50051`
+    ///
+    /// With `tls` the server certificate is checked against the webpki roots.
+    pub fn new(endpoint: impl Into<String>, tls: bool) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        Self {
+            endpoint: endpoint.into(),
+            tls,
+            client: None,
+            sender: StdMutex::new(Some(sender)),
+            receiver: Mutex::new(receiver),
+            subscription: None,
+        }
+    }
+
+    fn deliver(&self, message: Result<Message>) {
+        if let Some(sender) = self.sender.lock().unwrap().as_ref() {
+            let _ = sender.send(message);
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for GrpcClientTransport {
+    async fn initialize(&mut self) -> Result<()> {
+        let mut endpoint = Endpoint::from_shared(self.endpoint.clone()).map_err(|e| {
+            Error::Configuration(format!("Invalid gRPC endpoint {}: {}", self.endpoint, e))
+        })?;
+        if self.tls {
+            endpoint = endpoint
+                .tls_config(ClientTlsConfig::new().with_webpki_roots())
+                .map_err(|e| Error::Configuration(format!("Invalid TLS configuration: {}", e)))?;
+        }
+        let channel = endpoint.connect().await.map_err(|e| {
+            Error::Transport(format!("Failed to connect to {}: {}", self.endpoint, e))
+        })?;
+        let mut client = McpServiceClient::new(channel);
+
+        let mut stream = client
+            .subscribe(proto::Empty {})
+            .await
+            .map_err(status_error)?
+            .into_inner();
+        let sender = self
+            .sender
+            .lock()
+            .unwrap()
+            .clone()
+            .ok_or_else(|| Error::Transport("Transport closed".into()))?;
+        self.subscription = Some(tokio::spawn(async move {
+            loop {
+                let message = match stream.message().await {
+                    Ok(Some(message)) => match decode(message) {
+                        Ok(Some(message)) => Ok(message),
+                        Ok(None) => continue,
+                        Err(e) => Err(e),
+                    },
+                    Ok(None) => {
+                        let _ = sender.send(Err(Error::Transport("Subscription closed".into())));
+                        return;
+                    }
+                    Err(status) => {
+                        let _ = sender.send(Err(status_error(status)));
+                        return;
+                    }
+                };
+                if sender.send(message).is_err() {
+                    return;
+                }
+            }
+        }));
+        self.client = Some(client);
+        Ok(())
+    }
+
+    async fn send(&self, message: Message) -> Result<()> {
+        // The generated client is a cheap handle onto the shared channel
+        let mut client = self.client.clone().ok_or(Error::NotInitialized)?;
+        let reply = client
+            .send(encode(&message)?)
+            .await
+            .map_err(status_error)?
+            .into_inner();
+        if let Some(reply) = decode(reply)? {
+            self.deliver(Ok(reply));
+        }
+        Ok(())
+    }
+
+    async fn receive(&self) -> Result<Message> {
+        self.receiver
+            .lock()
+            .await
+            .recv()
+            .await
+            .unwrap_or_else(|| Err(Error::Transport("Connection closed".into())))
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        if let Some(subscription) = self.subscription.take() {
+            subscription.abort();
+        }
+        self.client = None;
+        self.sender.lock().unwrap().take();
+        Ok(())
+    }
+}
+
+/// State shared between a `GrpcServerTransport` and the service it runs
+struct GrpcService {
+    incoming: StdMutex<Option<mpsc::UnboundedSender<Message>>>,
+    /// `Send` calls waiting for the response to their request
+    pending: StdMutex<HashMap<RequestId, oneshot::Sender<Message>>>,
+    outgoing: broadcast::Sender<Message>,
+    /// Cancelled on close to end the `Subscribe` streams, which would otherwise
+    /// keep the server from shutting down
+    closed: CancellationToken,
+}
+
+#[async_trait]
+impl McpService for GrpcService {
+    async fn send(
+        &self,
+        request: tonic::Request<proto::Message>,
+    ) -> std::result::Result<tonic::Response<proto::Message>, Status> {
+        let message =
+            decode(request.into_inner()).map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let Some(message) = message else {
+            return Ok(tonic::Response::new(proto::Message::default()));
+        };
+
+        let reply = match &message {
+            Message::Request(request) => {
+                let mut pending = self.pending.lock().unwrap();
+                if pending.contains_key(&request.id) {
+                    return Err(Status::already_exists(format!(
+                        "Request {:?} is already in flight",
+                        request.id
+                    )));
+                }
+                let (sender, receiver) = oneshot::channel();
+                pending.insert(request.id.clone(), sender);
+                Some(receiver)
+            }
+            _ => None,
+        };
+
+        let closed = || Status::unavailable("Server transport closed");
+        self.incoming
+            .lock()
+            .unwrap()
+            .as_ref()
+            .ok_or_else(closed)?
+            .send(message)
+            .map_err(|_| closed())?;
+
+        let reply = match reply {
+            Some(receiver) => {
+                let response = receiver.await.map_err(|_| closed())?;
+                encode(&response).map_err(|e| Status::internal(e.to_string()))?
+            }
+            None => proto::Message::default(),
+        };
+        Ok(tonic::Response::new(reply))
+    }
+
+    type SubscribeStream =
+        Pin<Box<dyn Stream<Item = std::result::Result<proto::Message, Status>> + Send>>;
+
+    async fn subscribe(
+        &self,
+        _request: tonic::Request<proto::Empty>,
+    ) -> std::result::Result<tonic::Response<Self::SubscribeStream>, Status> {
+        let mut outgoing = self.outgoing.subscribe();
+        let closed = self.closed.clone();
+        let stream = async_stream::stream! {
+            loop {
+                let next = tokio::select! {
+                    next = outgoing.recv() => next,
+                    _ = closed.cancelled() => break,
+                };
+                match next {
+                    Ok(message) => {
+                        yield encode(&message).map_err(|e| Status::internal(e.to_string()))
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!("gRPC subscriber fell behind, skipped {} messages", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        };
+        Ok(tonic::Response::new(Box::pin(stream)))
+    }
+}
+
+/// Server side of the gRPC transport
+///
+/// Responses are returned from the `Send` call that carried their request, and
+/// every other message is pushed to all `Subscribe` streams, the gRPC equivalent
+/// of SSE. Request IDs must be unique across clients while in flight; batches are
+/// not awaited, so their responses are pushed to the subscribers.
+pub struct GrpcServerTransport {
+    addr: SocketAddr,
+    local_addr: Option<SocketAddr>,
+    service: Arc<GrpcService>,
+    receiver: Mutex<mpsc::UnboundedReceiver<Message>>,
+    shutdown: Option<oneshot::Sender<()>>,
+    server: Option<JoinHandle<std::result::Result<(), tonic::transport::Error>>>,
+}
+
+impl GrpcServerTransport {
+    /// Transport listening on `addr` once initialized
+    pub fn new(addr: SocketAddr) -> Self {
+        let (incoming, receiver) = mpsc::unbounded_channel();
+        let (outgoing, _) = broadcast::channel(SUBSCRIBER_CAPACITY);
+        Self {
+            addr,
+            local_addr: None,
+            service: Arc::new(GrpcService {
+                incoming: StdMutex::new(Some(incoming)),
+                pending: StdMutex::new(HashMap::new()),
+                outgoing,
+                closed: CancellationToken::new(),
+            }),
+            receiver: Mutex::new(receiver),
+            shutdown: None,
+            server: None,
+        }
+    }
+
+    /// Address the server is listening on, which tells the port picked for port 0
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.local_addr
+    }
+}
+
+#[async_trait]
+impl Transport for GrpcServerTransport {
+    async fn initialize(&mut self) -> Result<()> {
+        let incoming = TcpIncoming::bind(self.addr)?;
+        self.local_addr = Some(incoming.local_addr()?);
+
+        let (shutdown, signal) = oneshot::channel();
+        let service = McpServiceServer::from_arc(self.service.clone());
+        self.server = Some(tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(service)
+                .serve_with_incoming_shutdown(incoming, async {
+                    let _ = signal.await;
+                }),
+        ));
+        self.shutdown = Some(shutdown);
+        Ok(())
+    }
+
+    async fn send(&self, message: Message) -> Result<()> {
+        if let Message::Response(response) = &message {
+            let waiting = self.service.pending.lock().unwrap().remove(&response.id);
+            if let Some(waiting) = waiting {
+                // The caller may have given up; there is nobody else to tell
+                let _ = waiting.send(message);
+                return Ok(());
+            }
+        }
+        // Having no subscribers is not an error
+        let _ = self.service.outgoing.send(message);
+        Ok(())
+    }
+
+    async fn receive(&self) -> Result<Message> {
+        self.receiver
+            .lock()
+            .await
+            .recv()
+            .await
+            .ok_or_else(|| Error::Transport("Connection closed".into()))
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        self.service.incoming.lock().unwrap().take();
+        self.service.pending.lock().unwrap().clear();
+        self.service.closed.cancel();
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        if let Some(server) = self.server.take() {
+            server
+                .await
+                .map_err(|e| Error::Transport(format!("gRPC server task failed: {}", e)))?
+                .map_err(|e| Error::Transport(format!("gRPC server failed: {}", e)))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{Method, Notification, Request, Response};
+    use serde_json::json;
+
+    async fn connected() -> (GrpcServerTransport, GrpcClientTransport) {
+        let mut server = GrpcServerTransport::new("127.0.0.1:0".parse().unwrap());
+        server.initialize().await.unwrap();
+        let endpoint = format!("http://{}", server.local_addr().unwrap());
+        let mut client = GrpcClientTransport::new(endpoint, false);
+        client.initialize().await.unwrap();
+        (server, client)
+    }
+
+    #[tokio::test]
+    async fn test_request_is_answered_through_send() {
+        let (server, client) = connected().await;
+        let request = Request::new(Method::Ping, None, RequestId::Number(1));
+
+        let answer = async {
+            let Message::Request(request) = server.receive().await.unwrap() else {
+                panic!("expected a request");
+            };
+            server
+                .send(Message::Response(Response::success(json!({}), request.id)))
+                .await
+                .unwrap();
+        };
+        let (sent, ()) = tokio::join!(client.send(Message::Request(request)), answer);
+        sent.unwrap();
+
+        match client.receive().await.unwrap() {
+            Message::Response(response) => assert_eq!(response.id, RequestId::Number(1)),
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_server_messages_are_pushed_to_subscribers() {
+        let (server, client) = connected().await;
+
+        server
+            .send(Message::Notification(Notification::tools_list_changed()))
+            .await
+            .unwrap();
+        match client.receive().await.unwrap() {
+            Message::Notification(notification) => {
+                assert_eq!(notification.method, "notifications/tools/list_changed")
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+
+        client
+            .send(Message::Notification(Notification::new(
+                Method::Initialized,
+                None,
+            )))
+            .await
+            .unwrap();
+        assert!(matches!(
+            server.receive().await.unwrap(),
+            Message::Notification(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_closing_the_server_ends_the_subscription() {
+        let (mut server, client) = connected().await;
+        server.close().await.unwrap();
+        assert!(matches!(client.receive().await, Err(Error::Transport(_))));
+    }
+}
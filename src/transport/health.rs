@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+/// Overall condition of a transport
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HealthStatus {
+    /// Working normally
+    Healthy,
+    /// Working, but slowly or with reduced capacity
+    Degraded,
+    /// Not expected to deliver messages
+    Unhealthy,
+    /// The transport does not track its health
+    #[default]
+    Unknown,
+}
+
+/// Health report returned by `Transport::health`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransportHealth {
+    /// Overall condition
+    pub status: HealthStatus,
+    /// Typical time an operation currently takes, if measured
+    pub latency_hint: Option<Duration>,
+    /// Why the transport is not healthy, if known
+    pub error: Option<String>,
+}
+
+impl TransportHealth {
+    /// Report of a transport that does not track its health
+    pub fn unknown() -> Self {
+        Self::default()
+    }
+
+    /// Report of a transport working normally
+    pub fn healthy() -> Self {
+        Self::with_status(HealthStatus::Healthy)
+    }
+
+    /// Report with the given status and no further details
+    pub fn with_status(status: HealthStatus) -> Self {
+        Self {
+            status,
+            ..Self::default()
+        }
+    }
+
+    /// Whether the transport is expected to deliver messages
+    pub fn is_usable(&self) -> bool {
+        !matches!(self.status, HealthStatus::Unhealthy)
+    }
+}
@@ -0,0 +1,170 @@
+use axum::http::{header, HeaderMap, HeaderName, HeaderValue};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use subtle::ConstantTimeEq;
+
+use crate::{server_features::AuthContext, Error, Result};
+
+/// How HTTP requests are authenticated
+/// HTTP 请求的认证方式
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum AuthScheme {
+    /// `Authorization: Bearer <auth_token>`
+    #[default]
+    Bearer,
+    /// `<header_name>: <auth_token>`
+    ApiKeyHeader {
+        /// Header carrying the API key
+        /// 携带 API 密钥的请求头
+        header_name: String,
+    },
+    /// `Authorization: Basic <base64(user:pass)>`
+    ///
+    /// Credentials come from the scheme itself; `auth_token` is ignored.
+    /// 凭据来自认证方式本身；忽略 `auth_token`。
+    Basic {
+        /// User name
+        /// 用户名
+        user: String,
+        /// Password
+        /// 密码
+        pass: String,
+    },
+}
+
+impl AuthScheme {
+    /// Header to send for this scheme, or `None` if there are no credentials
+    /// 该认证方式应发送的请求头；没有凭据时返回 `None`
+    pub fn header(&self, auth_token: Option<&str>) -> Result<Option<(HeaderName, HeaderValue)>> {
+        let (name, value) = match (self, auth_token) {
+            (AuthScheme::Bearer, Some(token)) => {
+                (header::AUTHORIZATION, format!("Bearer {}", token))
+            }
+            (AuthScheme::ApiKeyHeader { header_name }, Some(token)) => {
+                let name = HeaderName::try_from(header_name.as_str())
+                    .map_err(|e| Error::Configuration(format!("Invalid header name: {}", e)))?;
+                (name, token.to_string())
+            }
+            (AuthScheme::Basic { user, pass }, _) => (
+                header::AUTHORIZATION,
+                format!("Basic {}", STANDARD.encode(format!("{}:{}", user, pass))),
+            ),
+            (_, None) => return Ok(None),
+        };
+
+        let mut value = HeaderValue::from_str(&value)
+            .map_err(|e| Error::Configuration(format!("Invalid credentials: {}", e)))?;
+        value.set_sensitive(true);
+        Ok(Some((name, value)))
+    }
+
+    /// Checks request headers against the expected credentials
+    /// 根据预期凭据检查请求头
+    ///
+    /// Requests are accepted without checks when the scheme needs a token and none is configured.
+    /// Credentials are compared in constant time, so timing does not reveal how much of
+    /// them matched.
+    /// 当认证方式需要令牌但未配置时，请求不经检查即被接受。凭据以常量时间比较，
+    /// 因此耗时不会泄露其匹配了多少。
+    pub fn validate(&self, headers: &HeaderMap, auth_token: Option<&str>) -> Result<()> {
+        let Some((name, expected)) = self.header(auth_token)? else {
+            return Ok(());
+        };
+
+        match headers.get(&name) {
+            Some(value) if bool::from(value.as_bytes().ct_eq(expected.as_bytes())) => Ok(()),
+            Some(_) => Err(Error::Transport("Invalid credentials".into())),
+            None => Err(Error::Transport(format!("Missing {} header", name))),
+        }
+    }
+
+    /// Identity of a caller whose credentials passed `validate`
+    /// 凭据已通过 `validate` 的调用方身份
+    ///
+    /// Basic credentials identify their user and a JWT bearer token the claims of its
+    /// payload; other credentials, and requests accepted without checks, carry no identity.
+    /// Basic 凭据标识其用户，JWT 持有者令牌标识其载荷中的声明；其他凭据以及
+    /// 未经检查即被接受的请求不携带身份。
+    pub fn identify(&self, auth_token: Option<&str>) -> Option<AuthContext> {
+        match (self, auth_token) {
+            (AuthScheme::Basic { user, .. }, _) => Some(AuthContext::new(user.clone())),
+            (AuthScheme::Bearer, Some(token)) => AuthContext::from_jwt(token),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(name: &str, value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            HeaderName::try_from(name).unwrap(),
+            HeaderValue::from_str(value).unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn test_bearer_header() {
+        let (name, value) = AuthScheme::Bearer.header(Some("secret")).unwrap().unwrap();
+        assert_eq!(name, header::AUTHORIZATION);
+        assert_eq!(value, "Bearer secret");
+        assert!(AuthScheme::Bearer.header(None).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_api_key_header() {
+        let scheme = AuthScheme::ApiKeyHeader {
+            header_name: "X-API-Key".to_string(),
+        };
+        let (name, value) = scheme.header(Some("secret")).unwrap().unwrap();
+        assert_eq!(name, "x-api-key");
+        assert_eq!(value, "secret");
+    }
+
+    #[test]
+    fn test_basic_header() {
+        let scheme = AuthScheme::Basic {
+            user: "Aladdin".to_string(),
+            pass: "open sesame".to_string(),
+        };
+        let (_, value) = scheme.header(None).unwrap().unwrap();
+        assert_eq!(value, "Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ==");
+    }
+
+    #[test]
+    fn test_validate() {
+        let scheme = AuthScheme::ApiKeyHeader {
+            header_name: "X-API-Key".to_string(),
+        };
+        assert!(scheme
+            .validate(&headers("X-API-Key", "secret"), Some("secret"))
+            .is_ok());
+        assert!(scheme
+            .validate(&headers("X-API-Key", "wrong"), Some("secret"))
+            .is_err());
+        assert!(scheme
+            .validate(&headers("X-API-Key", "secre"), Some("secret"))
+            .is_err());
+        assert!(scheme
+            .validate(&headers("X-API-Key", "secrets"), Some("secret"))
+            .is_err());
+        assert!(scheme
+            .validate(&headers("Authorization", "Bearer secret"), Some("secret"))
+            .is_err());
+        assert!(scheme.validate(&HeaderMap::new(), None).is_ok());
+    }
+
+    #[test]
+    fn test_identify() {
+        let basic = AuthScheme::Basic {
+            user: "Aladdin".to_string(),
+            pass: "open sesame".to_string(),
+        };
+        assert_eq!(basic.identify(None).unwrap().subject, "Aladdin");
+        assert!(AuthScheme::Bearer.identify(Some("secret")).is_none());
+        assert!(AuthScheme::Bearer.identify(None).is_none());
+    }
+}
@@ -1,16 +1,79 @@
-use crate::{protocol::Message, Result};
+use super::auth::AuthScheme;
+use super::tls::TlsClientConfig;
+use super::EndpointEvent;
+use crate::server_features::ResourceStream;
+use crate::{protocol::Message, transport::Backoff, Result};
 use async_trait::async_trait;
 use futures::StreamExt;
-use reqwest::{header, Client};
+use reqwest::{
+    header::{self, HeaderName, HeaderValue},
+    Client, RequestBuilder,
+};
 use serde_json;
 use std::sync::{Arc, Mutex};
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, Mutex as AsyncMutex};
 
 /// HTTP client configuration
 /// HTTP 客户端配置
 pub struct HttpClientConfig {
     pub base_url: String,
     pub auth_token: Option<String>,
+    /// How credentials are sent; must match the server's scheme
+    /// 凭据的发送方式；必须与服务器的认证方式一致
+    pub auth_scheme: AuthScheme,
+    /// TLS settings, including certificate pinning
+    /// TLS 设置，包括证书固定
+    pub tls: TlsClientConfig,
+    /// Delay policy while polling for the endpoint event
+    /// 轮询 endpoint 事件时的延迟策略
+    pub backoff: Backoff,
+    /// How long to wait for the endpoint event after connecting
+    /// 连接后等待 endpoint 事件的时长
+    pub endpoint_timeout: std::time::Duration,
+    /// Replace invalid UTF-8 in SSE events with U+FFFD instead of reporting an error
+    /// 将 SSE 事件中的无效 UTF-8 替换为 U+FFFD，而不是报告错误
+    pub lossy_utf8: bool,
+    /// Largest accepted SSE event; longer ones are skipped with an error
+    /// 可接受的最大 SSE 事件；更长的事件将被跳过并报告错误
+    pub max_message_bytes: usize,
+    /// Path of the server's SSE stream, appended to `base_url`
+    /// 服务器 SSE 流的路径，附加在 `base_url` 之后
+    ///
+    /// The message path is not configured here; it comes from the endpoint event.
+    /// 消息路径无需在此配置；它来自 endpoint 事件。
+    pub events_path: String,
+    /// `Content-Type` of posted messages, `application/json` when `None`
+    /// 提交消息的 `Content-Type`，为 `None` 时使用 `application/json`
+    ///
+    /// Only the header changes; the body is always JSON.
+    /// 只改变请求头；请求体始终是 JSON。
+    pub content_type: Option<String>,
+    /// Speak HTTP/2 without negotiation (prior knowledge)
+    /// 不经协商直接使用 HTTP/2（预先知晓）
+    ///
+    /// The server must accept HTTP/2, e.g. an `AxumHttpServer` with `http2` set.
+    /// 服务器必须接受 HTTP/2，例如设置了 `http2` 的 `AxumHttpServer`。
+    #[cfg(feature = "http2")]
+    pub http2: bool,
+}
+
+impl Default for HttpClientConfig {
+    fn default() -> Self {
+        Self {
+            base_url: "http://127.0.0.1:3000".to_string(),
+            auth_token: None,
+            auth_scheme: AuthScheme::default(),
+            tls: TlsClientConfig::default(),
+            backoff: Backoff::default(),
+            endpoint_timeout: std::time::Duration::from_secs(1),
+            lossy_utf8: false,
+            max_message_bytes: crate::transport::stdio::DEFAULT_MAX_MESSAGE_BYTES,
+            events_path: "/events".to_string(),
+            content_type: None,
+            #[cfg(feature = "http2")]
+            http2: false,
+        }
+    }
 }
 
 /// HTTP client implementation
@@ -18,8 +81,12 @@ pub struct HttpClientConfig {
 pub struct HttpClient {
     config: HttpClientConfig,
     client: Client,
+    auth_header: Option<(HeaderName, HeaderValue)>,
     message_endpoint: Arc<Mutex<Option<String>>>,
-    receiver: Mutex<Option<mpsc::Receiver<Message>>>,
+    /// Messages read from the SSE stream; locked across `recv` rather than taken out,
+    /// so a cancelled `receive` leaves it in place
+    /// 从 SSE 流读取的消息；在 `recv` 期间保持加锁而不是取出，因此取消的 `receive` 不会丢失它
+    receiver: AsyncMutex<Option<mpsc::Receiver<Result<Message>>>>,
     client_id: Arc<Mutex<Option<String>>>,
 }
 
@@ -27,32 +94,83 @@ impl HttpClient {
     /// Create a new HTTP client
     /// 创建一个新的 HTTP 客户端
     pub fn new(config: HttpClientConfig) -> Result<Self> {
-        let mut headers = header::HeaderMap::new();
-        if let Some(token) = &config.auth_token {
-            headers.insert(
-                header::AUTHORIZATION,
-                header::HeaderValue::from_str(&format!("Bearer {}", token))
-                    .map_err(|e| crate::Error::Transport(e.to_string()))?,
-            );
-        }
-
-        let client = Client::builder()
-            .default_headers(headers)
+        let builder = config.tls.apply(Client::builder())?;
+        #[cfg(feature = "http2")]
+        let builder = if config.http2 {
+            builder.http2_prior_knowledge()
+        } else {
+            builder
+        };
+        let client = builder
             .build()
             .map_err(|e| crate::Error::Transport(e.to_string()))?;
+        Self::with_client(config, client)
+    }
+
+    /// Create an HTTP client that sends its requests through `client`
+    /// 创建一个通过 `client` 发送请求的 HTTP 客户端
+    ///
+    /// Useful for custom proxies, connection pools or timeouts. The auth header
+    /// is still added to every request, but `config.tls` is ignored because TLS
+    /// is already set up in `client`.
+    /// 适用于自定义代理、连接池或超时。认证请求头仍会添加到每个请求中，
+    /// 但会忽略 `config.tls`，因为 TLS 已在 `client` 中配置。
+    pub fn with_client(config: HttpClientConfig, client: Client) -> Result<Self> {
+        let auth_header = config.auth_scheme.header(config.auth_token.as_deref())?;
 
         Ok(Self {
             config,
             client,
+            auth_header,
             message_endpoint: Arc::new(Mutex::new(None)),
-            receiver: Mutex::new(None),
+            receiver: AsyncMutex::new(None),
             client_id: Arc::new(Mutex::new(None)),
         })
     }
 
+    /// Stream the content of a resource from the server's `/resources/content` route
+    /// 从服务器的 `/resources/content` 路由流式读取资源内容
+    ///
+    /// Returns the content together with its MIME type, taken from `Content-Type`.
+    /// 返回内容及其 MIME 类型（取自 `Content-Type`）。
+    pub async fn stream_resource(&self, id: &str) -> Result<(ResourceStream, Option<String>)> {
+        let url = format!("{}/resources/content", self.config.base_url);
+        let response = self
+            .request(reqwest::Method::GET, &url)
+            .query(&[("id", id)])
+            .send()
+            .await
+            .map_err(|e| crate::Error::Transport(e.to_string()))?
+            .error_for_status()
+            .map_err(|e| crate::Error::Transport(e.to_string()))?;
+
+        let mime_type = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let stream = response
+            .bytes_stream()
+            .map(|chunk| chunk.map_err(std::io::Error::other));
+        Ok((
+            Box::pin(tokio_util::io::StreamReader::new(stream)),
+            mime_type,
+        ))
+    }
+
+    /// Start a request carrying the auth header
+    /// 创建一个带有认证请求头的请求
+    fn request(&self, method: reqwest::Method, url: &str) -> RequestBuilder {
+        let request = self.client.request(method, url);
+        match &self.auth_header {
+            Some((name, value)) => request.header(name, value),
+            None => request,
+        }
+    }
+
     /// Wait for and get endpoint event
     /// 等待并获取 endpoint 事件
-    fn wait_for_endpoint(event: &str) -> Option<(String, String)> {
+    fn wait_for_endpoint(event: &str) -> Option<EndpointEvent> {
         if event.trim().starts_with("event: endpoint\ndata:") {
             let data = event
                 .lines()
@@ -61,25 +179,91 @@ impl HttpClient {
 
             // 解析 JSON 数据
             // Parse JSON data
-            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&data) {
-                let endpoint = json["endpoint"].as_str()?.to_string();
-                let client_id = json["clientId"].as_str()?.to_string();
-                return Some((endpoint, client_id));
-            }
+            return serde_json::from_str(&data).ok();
         }
         None
     }
 }
 
+/// Byte buffer that splits an SSE stream into events
+/// 将 SSE 字节流拆分为事件的缓冲区
+///
+/// Events are decoded only once complete, so a multi-byte character split across
+/// network chunks is reassembled rather than lost.
+/// 仅在事件完整后才进行解码，因此跨网络块拆分的多字节字符会被重新组合而不会丢失。
+struct SseBuffer {
+    buffer: Vec<u8>,
+    lossy: bool,
+    /// Largest event kept in memory
+    /// 内存中保留的最大事件
+    limit: usize,
+    /// Whether the rest of an oversized event is being skipped
+    /// 是否正在跳过超大事件的剩余部分
+    discarding: bool,
+}
+
+impl SseBuffer {
+    fn new(lossy: bool, limit: usize) -> Self {
+        Self {
+            buffer: Vec::new(),
+            lossy,
+            limit,
+            discarding: false,
+        }
+    }
+
+    fn push(&mut self, chunk: &[u8]) {
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    /// Take the next complete event, if any
+    /// 取出下一个完整事件（如果有）
+    ///
+    /// An event growing past the limit is reported once as an error and then
+    /// skipped up to the next event boundary.
+    /// 超过限制的事件会报告一次错误，然后被跳过直到下一个事件边界。
+    fn next_event(&mut self) -> Option<Result<String>> {
+        loop {
+            let Some(end) = self.buffer.windows(2).position(|w| w == b"\n\n") else {
+                if self.discarding {
+                    // Keep a trailing newline that may start the boundary
+                    // 保留可能构成边界开头的末尾换行符
+                    let keep = usize::from(self.buffer.last() == Some(&b'\n'));
+                    self.buffer.drain(..self.buffer.len() - keep);
+                } else if self.buffer.len() > self.limit {
+                    self.discarding = true;
+                    self.buffer.clear();
+                    return Some(Err(too_large()));
+                }
+                return None;
+            };
+
+            let event: Vec<u8> = self.buffer.drain(..end + 2).take(end).collect();
+            if std::mem::take(&mut self.discarding) {
+                continue;
+            }
+            if event.len() > self.limit {
+                return Some(Err(too_large()));
+            }
+            return Some(crate::transport::decode_utf8(event, self.lossy));
+        }
+    }
+}
+
+/// Error reported for an oversized event
+/// 超大事件的错误
+fn too_large() -> crate::Error {
+    crate::Error::Protocol("message too large".into())
+}
+
 #[async_trait]
 impl super::HttpTransport for HttpClient {
     async fn initialize(&mut self) -> Result<()> {
         // Connect to SSE endpoint
         // 连接到 SSE 端点
-        let url = format!("{}/events", self.config.base_url);
+        let url = format!("{}{}", self.config.base_url, self.config.events_path);
         let response = self
-            .client
-            .get(&url)
+            .request(reqwest::Method::GET, &url)
             .header(header::ACCEPT, "text/event-stream")
             .send()
             .await
@@ -88,55 +272,58 @@ impl super::HttpTransport for HttpClient {
         // Create message receiving channel
         // 创建消息接收通道
         let (tx, rx) = mpsc::channel(32);
-        *self.receiver.lock().unwrap() = Some(rx);
+        *self.receiver.get_mut() = Some(rx);
 
         // Handle SSE event stream
         // 处理 SSE 事件流
         let mut stream = response.bytes_stream();
-        let mut buffer = String::new();
+        let mut buffer = SseBuffer::new(self.config.lossy_utf8, self.config.max_message_bytes);
         let message_endpoint = Arc::clone(&self.message_endpoint);
         let client_id = Arc::clone(&self.client_id);
 
         tokio::spawn(async move {
             while let Some(Ok(chunk)) = stream.next().await {
-                if let Ok(text) = String::from_utf8(chunk.to_vec()) {
-                    buffer.push_str(&text);
-
-                    // Process complete events
-                    // 处理完整的事件
-                    while let Some(event_end) = buffer.find("\n\n") {
-                        let event = buffer[..event_end].to_string();
-                        buffer.drain(..event_end + 2);
-
-                        // Skip keepalive ping
-                        // 跳过保活 ping
-                        if event.trim() == "data: ping" {
+                buffer.push(&chunk);
+
+                // Process complete events
+                // 处理完整的事件
+                while let Some(event) = buffer.next_event() {
+                    let event = match event {
+                        Ok(event) => event,
+                        Err(e) => {
+                            if tx.send(Err(e)).await.is_err() {
+                                return;
+                            }
                             continue;
                         }
+                    };
 
-                        // Handle endpoint event
-                        // 处理 endpoint 事件
-                        if event.contains("event: endpoint") {
-                            if let Some((endpoint, id)) = HttpClient::wait_for_endpoint(&event) {
-                                *message_endpoint.lock().unwrap() = Some(endpoint);
-                                *client_id.lock().unwrap() = Some(id);
-                                continue;
-                            }
+                    // Skip keepalive ping
+                    // 跳过保活 ping
+                    if event.trim() == "data: ping" {
+                        continue;
+                    }
+
+                    // Handle endpoint event
+                    // 处理 endpoint 事件
+                    if event.contains("event: endpoint") {
+                        if let Some(endpoint) = HttpClient::wait_for_endpoint(&event) {
+                            *message_endpoint.lock().unwrap() = Some(endpoint.endpoint);
+                            *client_id.lock().unwrap() = Some(endpoint.client_id);
+                            continue;
                         }
+                    }
 
-                        // Handle message event
-                        // 处理消息事件
-                        if event.contains("event: message") {
-                            if let Some(data) =
-                                event.lines().find(|line| line.starts_with("data: "))
-                            {
-                                let data = &data[6..];
-                                if let Ok(message) = serde_json::from_str(data) {
-                                    // Send all messages to the receiver channel
-                                    // 发送所有消息到接收通道
-                                    if tx.send(message).await.is_err() {
-                                        return;
-                                    }
+                    // Handle message event
+                    // 处理消息事件
+                    if event.contains("event: message") {
+                        if let Some(data) = event.lines().find(|line| line.starts_with("data: ")) {
+                            let data = &data[6..];
+                            if let Ok(message) = serde_json::from_str(data) {
+                                // Send all messages to the receiver channel
+                                // 发送所有消息到接收通道
+                                if tx.send(Ok(message)).await.is_err() {
+                                    return;
                                 }
                             }
                         }
@@ -147,10 +334,15 @@ impl super::HttpTransport for HttpClient {
 
         // Wait for endpoint
         // 等待接收 endpoint
-        let mut retries = 0;
-        while self.message_endpoint.lock().unwrap().is_none() && retries < 10 {
-            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-            retries += 1;
+        let deadline = tokio::time::Instant::now() + self.config.endpoint_timeout;
+        let mut backoff = self.config.backoff.clone();
+        while self.message_endpoint.lock().unwrap().is_none()
+            && tokio::time::Instant::now() < deadline
+        {
+            tokio::time::sleep_until(
+                (tokio::time::Instant::now() + backoff.next_delay()).min(deadline),
+            )
+            .await;
         }
 
         if self.message_endpoint.lock().unwrap().is_none() {
@@ -163,12 +355,16 @@ impl super::HttpTransport for HttpClient {
     }
 
     async fn send(&self, message: Message) -> Result<()> {
+        self.send_raw(&serde_json::to_string(&message)?).await
+    }
+
+    async fn send_raw(&self, json: &str) -> Result<()> {
         let endpoint = self
             .message_endpoint
             .lock()
             .unwrap()
             .as_ref()
-            .ok_or_else(|| crate::Error::Transport("Message endpoint not initialized".into()))?
+            .ok_or(crate::Error::NotInitialized)?
             .clone();
 
         let client_id = self
@@ -176,13 +372,19 @@ impl super::HttpTransport for HttpClient {
             .lock()
             .unwrap()
             .as_ref()
-            .ok_or_else(|| crate::Error::Transport("Client ID not initialized".into()))?
+            .ok_or(crate::Error::NotInitialized)?
             .clone();
 
-        self.client
-            .post(&endpoint)
+        self.request(reqwest::Method::POST, &endpoint)
             .header("X-Client-ID", client_id)
-            .json(&message)
+            .header(
+                header::CONTENT_TYPE,
+                self.config
+                    .content_type
+                    .as_deref()
+                    .unwrap_or("application/json"),
+            )
+            .body(json.to_string())
             .send()
             .await
             .map_err(|e| crate::Error::Transport(e.to_string()))?
@@ -193,26 +395,38 @@ impl super::HttpTransport for HttpClient {
     }
 
     async fn receive(&self) -> Result<Message> {
-        let mut receiver = self
-            .receiver
-            .lock()
-            .unwrap()
-            .take()
+        if self.message_endpoint.lock().unwrap().is_none() {
+            return Err(crate::Error::NotInitialized);
+        }
+        let mut receiver = self.receiver.lock().await;
+        let receiver = receiver
+            .as_mut()
             .ok_or_else(|| crate::Error::Transport("SSE connection not established".into()))?;
 
-        let message = receiver
+        receiver
             .recv()
             .await
-            .ok_or_else(|| crate::Error::Transport("SSE connection closed".into()))?;
-
-        *self.receiver.lock().unwrap() = Some(receiver);
-        Ok(message)
+            .ok_or_else(|| crate::Error::Transport("SSE connection closed".into()))?
     }
 
     async fn close(&mut self) -> Result<()> {
-        *self.message_endpoint.lock().unwrap() = None;
-        *self.client_id.lock().unwrap() = None;
-        *self.receiver.lock().unwrap() = None;
+        let endpoint = self.message_endpoint.lock().unwrap().take();
+        let client_id = self.client_id.lock().unwrap().take();
+        *self.receiver.get_mut() = None;
+
+        // Tell the server to drop this client now rather than after its timeout
+        // 通知服务器立即移除该客户端，而不是等待超时
+        if let (Some(endpoint), Some(client_id)) = (endpoint, client_id) {
+            let result = self
+                .request(reqwest::Method::DELETE, &endpoint)
+                .header("X-Client-ID", client_id)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+            if let Err(e) = result {
+                tracing::warn!("Failed to notify server of disconnect: {}", e);
+            }
+        }
         Ok(())
     }
 }
@@ -220,3 +434,322 @@ impl super::HttpTransport for HttpClient {
 /// Default HTTP client type
 /// 默认 HTTP 客户端类型
 pub type DefaultHttpClient = HttpClient;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::http::HttpTransport;
+
+    #[test]
+    fn test_multibyte_character_split_across_chunks() {
+        let event = "event: message\ndata: {\"text\":\"héllo 世界\"}\n\n".as_bytes();
+        let split = event.iter().position(|&b| b == 0xe4).unwrap() + 1;
+
+        let mut buffer = SseBuffer::new(false, usize::MAX);
+        buffer.push(&event[..split]);
+        assert!(buffer.next_event().is_none());
+        buffer.push(&event[split..]);
+
+        let decoded = buffer.next_event().unwrap().unwrap();
+        assert_eq!(decoded, "event: message\ndata: {\"text\":\"héllo 世界\"}");
+        assert!(buffer.next_event().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_use_before_initialize_fails() {
+        let client = HttpClient::new(HttpClientConfig::default()).unwrap();
+        let message = Message::Notification(crate::protocol::Notification::new(
+            crate::protocol::Method::Initialized,
+            None,
+        ));
+        assert!(matches!(
+            client.send(message).await,
+            Err(crate::Error::NotInitialized)
+        ));
+        assert!(matches!(
+            client.receive().await,
+            Err(crate::Error::NotInitialized)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_with_client_uses_supplied_client_and_auth() {
+        use tokio::io::AsyncReadExt;
+
+        // Records the request and never answers it
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = vec![0; 4096];
+            let n = socket.read(&mut request).await.unwrap();
+            let _ = tx.send(String::from_utf8_lossy(&request[..n]).to_lowercase());
+            std::future::pending::<()>().await;
+            drop(socket);
+        });
+
+        let client = Client::builder()
+            .timeout(std::time::Duration::from_millis(200))
+            .build()
+            .unwrap();
+        let http = HttpClient::with_client(
+            HttpClientConfig {
+                base_url: format!("http://{}", addr),
+                auth_token: Some("secret".to_string()),
+                ..Default::default()
+            },
+            client,
+        )
+        .unwrap();
+
+        let started = std::time::Instant::now();
+        let Err(err) = http.stream_resource("slow").await else {
+            panic!("request should time out");
+        };
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+        assert!(matches!(err, crate::Error::Transport(_)));
+        assert!(rx.await.unwrap().contains("authorization: bearer secret"));
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn test_stream_resource_over_http() {
+        use crate::server_features::{Resource, ResourceManager};
+        use crate::transport::http::server::{AxumHttpServer, HttpServerConfig};
+        use tokio::io::AsyncReadExt;
+
+        struct Blob;
+
+        #[async_trait]
+        impl ResourceManager for Blob {
+            async fn list_resources(&self) -> Result<Vec<Resource>> {
+                Ok(vec![])
+            }
+
+            async fn get_resource(&self, id: &str) -> Result<Resource> {
+                Ok(Resource {
+                    id: id.to_string(),
+                    type_: "blob".to_string(),
+                    metadata: serde_json::json!({ "mimeType": "text/plain" }),
+                    content: Some(serde_json::json!(format!("content of {}", id))),
+                })
+            }
+
+            async fn create_resource(&self, _: Resource) -> Result<()> {
+                Ok(())
+            }
+
+            async fn update_resource(&self, _: &str, _: Resource) -> Result<()> {
+                Ok(())
+            }
+
+            async fn delete_resource(&self, _: &str) -> Result<()> {
+                Ok(())
+            }
+        }
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = AxumHttpServer::new(HttpServerConfig {
+            addr,
+            resources: Some(Arc::new(Blob)),
+            ..Default::default()
+        });
+        let router = AxumHttpServer::create_router(Arc::new(server));
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let client = HttpClient::new(HttpClientConfig {
+            base_url: format!("http://{}", addr),
+            ..Default::default()
+        })
+        .unwrap();
+        let (mut stream, mime_type) = client.stream_resource("a b").await.unwrap();
+        let mut content = String::new();
+        stream.read_to_string(&mut content).await.unwrap();
+
+        assert_eq!(content, "content of a b");
+        assert_eq!(mime_type.as_deref(), Some("text/plain"));
+    }
+
+    #[tokio::test]
+    async fn test_close_removes_client_from_server() {
+        use crate::transport::http::server::{AxumHttpServer, HttpServerConfig};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = AxumHttpServer::new(HttpServerConfig {
+            addr,
+            ..Default::default()
+        });
+        let router = AxumHttpServer::create_router(Arc::new(server.clone()));
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let mut client = HttpClient::new(HttpClientConfig {
+            base_url: format!("http://{}", addr),
+            ..Default::default()
+        })
+        .unwrap();
+        client.initialize().await.unwrap();
+        assert_eq!(server.client_count().await, 1);
+
+        client.close().await.unwrap();
+        assert_eq!(server.client_count().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_custom_content_type_is_accepted() {
+        use crate::protocol::{Request, RequestId};
+        use crate::transport::http::server::{AxumHttpServer, HttpServerConfig};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = AxumHttpServer::new(HttpServerConfig {
+            addr,
+            ..Default::default()
+        });
+        let router = AxumHttpServer::create_router(Arc::new(server));
+        tokio::spawn(async move {
+            axum::serve(
+                listener,
+                router.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+            )
+            .await
+        });
+
+        let mut client = HttpClient::new(HttpClientConfig {
+            base_url: format!("http://{}", addr),
+            content_type: Some("application/json-rpc".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        client.initialize().await.unwrap();
+        client
+            .send(Message::Request(Request::ping(RequestId::Number(1))))
+            .await
+            .unwrap();
+        match client.receive().await.unwrap() {
+            Message::Response(response) => assert_eq!(response.id, RequestId::Number(1)),
+            other => panic!("unexpected message: {:?}", other),
+        }
+        client.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_receive_after_receive_many_timed_out() {
+        use crate::protocol::{Method, Notification};
+        use crate::transport::http::server::{AxumHttpServer, HttpServerConfig};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = AxumHttpServer::new(HttpServerConfig {
+            addr,
+            ..Default::default()
+        });
+        let router = AxumHttpServer::create_router(Arc::new(server.clone()));
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let mut client = HttpClient::new(HttpClientConfig {
+            base_url: format!("http://{}", addr),
+            ..Default::default()
+        })
+        .unwrap()
+        .into_transport();
+        client.initialize().await.unwrap();
+
+        // Nothing arrives in time, cancelling the pending receive
+        let received = client
+            .receive_many(1, std::time::Duration::from_millis(50))
+            .await
+            .unwrap();
+        assert!(received.is_empty());
+
+        server
+            .send(Message::Notification(Notification::new(
+                Method::Initialized,
+                None,
+            )))
+            .await
+            .unwrap();
+        match client.receive().await.unwrap() {
+            Message::Notification(n) => assert_eq!(n.method, "initialized"),
+            other => panic!("unexpected message: {:?}", other),
+        }
+        client.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_client_follows_relocated_routes() {
+        use crate::transport::http::server::{AxumHttpServer, HttpServerConfig};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = AxumHttpServer::new(HttpServerConfig {
+            addr,
+            events_path: "/mcp/events".to_string(),
+            messages_path: "/mcp/messages".to_string(),
+            ..Default::default()
+        });
+        let router = AxumHttpServer::create_router(Arc::new(server.clone()));
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let base_url = format!("http://{}", addr);
+        let old = reqwest::get(format!("{}/events", base_url)).await.unwrap();
+        assert_eq!(old.status(), reqwest::StatusCode::NOT_FOUND);
+
+        let mut client = HttpClient::new(HttpClientConfig {
+            base_url,
+            events_path: "/mcp/events".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+        client.initialize().await.unwrap();
+        assert_eq!(
+            client.message_endpoint.lock().unwrap().as_deref(),
+            Some(format!("http://{}/mcp/messages", addr).as_str())
+        );
+
+        let initialized =
+            crate::protocol::Notification::new(crate::protocol::Method::Initialized, None);
+        client
+            .send(Message::Notification(initialized))
+            .await
+            .unwrap();
+        client.close().await.unwrap();
+        assert_eq!(server.client_count().await, 0);
+    }
+
+    #[test]
+    fn test_oversized_event_is_skipped() {
+        let mut buffer = SseBuffer::new(false, 32);
+        buffer.push(format!("data: {}", "x".repeat(40)).as_bytes());
+        assert!(matches!(
+            buffer.next_event(),
+            Some(Err(crate::Error::Protocol(ref m))) if m == "message too large"
+        ));
+
+        // The rest of the oversized event is dropped without a second error
+        // 超大事件的剩余部分被丢弃，且不会再次报错
+        buffer.push(format!("{}\n", "x".repeat(100)).as_bytes());
+        assert!(buffer.next_event().is_none());
+        buffer.push(b"\ndata: ok\n\n");
+        assert_eq!(buffer.next_event().unwrap().unwrap(), "data: ok");
+        assert!(buffer.next_event().is_none());
+    }
+
+    #[test]
+    fn test_invalid_utf8_is_reported_unless_lossy() {
+        let event = b"data: \xff\n\n";
+
+        let mut strict = SseBuffer::new(false, usize::MAX);
+        strict.push(event);
+        assert!(matches!(
+            strict.next_event(),
+            Some(Err(crate::Error::Protocol(_)))
+        ));
+
+        let mut lossy = SseBuffer::new(true, usize::MAX);
+        lossy.push(event);
+        assert_eq!(lossy.next_event().unwrap().unwrap(), "data: \u{fffd}");
+    }
+}
@@ -0,0 +1,198 @@
+use crate::protocol::{RequestId, Response};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::OnceCell;
+
+/// Cached response entry, created before the request runs so duplicates can wait for it
+/// 缓存的响应条目，在请求执行前创建，以便重复请求等待其结果
+#[derive(Default)]
+struct CachedResponse {
+    /// Response and the time it was stored, empty while the request is in flight
+    /// 响应及其存储时间，请求执行期间为空
+    response: OnceCell<(Response, Instant)>,
+}
+
+impl CachedResponse {
+    /// Whether the response was stored more than `ttl` ago; in-flight entries never expire
+    /// 响应是否已存储超过 `ttl`；执行中的条目永不过期
+    fn is_expired(&self, ttl: Duration) -> bool {
+        self.response
+            .get()
+            .is_some_and(|(_, stored_at)| stored_at.elapsed() >= ttl)
+    }
+}
+
+/// Idempotency cache for replaying responses to retried requests
+/// 用于重放重试请求响应的幂等缓存
+///
+/// Entries are keyed by `(client_id, request_id)` and expire after the configured TTL.
+/// 条目以 `(client_id, request_id)` 为键，并在配置的 TTL 后过期。
+pub struct IdempotencyCache {
+    /// Time to keep a response around for replay
+    /// 响应保留用于重放的时间
+    ttl: Duration,
+    /// Cached responses
+    /// 已缓存的响应
+    entries: Mutex<HashMap<(u64, RequestId), Arc<CachedResponse>>>,
+}
+
+impl IdempotencyCache {
+    /// Create a new idempotency cache
+    /// 创建新的幂等缓存
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Get the cached response for a request, if it has not expired
+    /// 获取请求的缓存响应（如果尚未过期）
+    pub fn get(&self, client_id: u64, request_id: &RequestId) -> Option<Response> {
+        let entries = self.entries.lock().unwrap();
+        let entry = entries.get(&(client_id, request_id.clone()))?;
+        if entry.is_expired(self.ttl) {
+            return None;
+        }
+        entry.response.get().map(|(response, _)| response.clone())
+    }
+
+    /// Store the response computed for a request
+    /// 存储为请求计算的响应
+    pub fn insert(&self, client_id: u64, request_id: RequestId, response: Response) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, entry| !entry.is_expired(self.ttl));
+        entries.insert(
+            (client_id, request_id),
+            Arc::new(CachedResponse {
+                response: OnceCell::new_with(Some((response, Instant::now()))),
+            }),
+        );
+    }
+
+    /// Return the cached response for a request, or execute it and cache the result
+    /// 返回请求的缓存响应，或执行请求并缓存结果
+    ///
+    /// Duplicates arriving while the request runs wait for its response instead of
+    /// executing it again. If the execution is cancelled, the next duplicate runs it.
+    /// 请求执行期间到达的重复请求会等待其响应而不是再次执行。若执行被取消，下一个重复请求会执行它。
+    pub async fn get_or_execute<F, Fut>(
+        &self,
+        client_id: u64,
+        request_id: &RequestId,
+        execute: F,
+    ) -> Response
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Response>,
+    {
+        let entry = {
+            let mut entries = self.entries.lock().unwrap();
+            entries.retain(|_, entry| !entry.is_expired(self.ttl));
+            entries
+                .entry((client_id, request_id.clone()))
+                .or_default()
+                .clone()
+        };
+
+        let (response, _) = entry
+            .response
+            .get_or_init(|| async { (execute().await, Instant::now()) })
+            .await;
+        response.clone()
+    }
+
+    /// Number of cached entries, including ones that have expired but not yet been pruned
+    /// 缓存条目数量，包括已过期但尚未清理的条目
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    /// Whether the cache is empty
+    /// 缓存是否为空
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    async fn execute(
+        cache: &IdempotencyCache,
+        client_id: u64,
+        id: RequestId,
+        executions: &AtomicUsize,
+    ) -> Response {
+        cache
+            .get_or_execute(client_id, &id, || async {
+                let count = executions.fetch_add(1, Ordering::SeqCst) + 1;
+                Response::success(json!({ "execution": count }), id.clone())
+            })
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_request_is_replayed() {
+        let cache = IdempotencyCache::new(Duration::from_secs(60));
+        let executions = AtomicUsize::new(0);
+
+        let first = execute(&cache, 1, RequestId::Number(7), &executions).await;
+        let second = execute(&cache, 1, RequestId::Number(7), &executions).await;
+
+        assert_eq!(executions.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            serde_json::to_string(&first).unwrap(),
+            serde_json::to_string(&second).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_duplicates_wait_for_the_first_execution() {
+        let cache = IdempotencyCache::new(Duration::from_secs(60));
+        let executions = AtomicUsize::new(0);
+        let slow = || async {
+            executions.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            Response::success(json!({}), RequestId::Number(7))
+        };
+
+        let (first, second) = tokio::join!(
+            cache.get_or_execute(1, &RequestId::Number(7), slow),
+            cache.get_or_execute(1, &RequestId::Number(7), slow)
+        );
+
+        assert_eq!(executions.load(Ordering::SeqCst), 1);
+        assert_eq!(first, second);
+    }
+
+    #[tokio::test]
+    async fn test_same_request_id_from_different_clients() {
+        let cache = IdempotencyCache::new(Duration::from_secs(60));
+        let executions = AtomicUsize::new(0);
+
+        execute(&cache, 1, RequestId::Number(7), &executions).await;
+        execute(&cache, 2, RequestId::Number(7), &executions).await;
+
+        assert_eq!(executions.load(Ordering::SeqCst), 2);
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_expired_entry_is_executed_again() {
+        let cache = IdempotencyCache::new(Duration::from_millis(10));
+        let executions = AtomicUsize::new(0);
+
+        execute(&cache, 1, RequestId::String("a".into()), &executions).await;
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        execute(&cache, 1, RequestId::String("a".into()), &executions).await;
+
+        assert_eq!(executions.load(Ordering::SeqCst), 2);
+        assert_eq!(cache.len(), 1);
+    }
+}
@@ -1,8 +1,27 @@
 use crate::{protocol::Message, Result};
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 
+pub mod auth;
 pub mod client;
+pub mod idempotency;
+mod queue;
+pub mod rate_limit;
 pub mod server;
+pub mod tls;
+
+/// Payload of the SSE `endpoint` event, telling a client where to post messages
+/// SSE `endpoint` 事件的负载，告知客户端向何处提交消息
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EndpointEvent {
+    /// URL that accepts the client's messages
+    /// 接收客户端消息的 URL
+    pub endpoint: String,
+    /// ID the client sends back in the `X-Client-ID` header
+    /// 客户端在 `X-Client-ID` 请求头中回传的 ID
+    pub client_id: String,
+}
 
 /// HTTP transport trait
 #[async_trait]
@@ -10,10 +29,20 @@ pub trait HttpTransport: Send + Sync {
     /// Initialize the transport
     async fn initialize(&mut self) -> Result<()>;
     /// Send a message
+    #[must_use = "transport errors must be handled"]
     async fn send(&self, message: Message) -> Result<()>;
+    /// Send a pre-serialized JSON message
+    ///
+    /// The default implementation parses the message and calls `send`.
+    #[must_use = "transport errors must be handled"]
+    async fn send_raw(&self, json: &str) -> Result<()> {
+        self.send(serde_json::from_str(json)?).await
+    }
     /// Receive a message
+    #[must_use = "transport errors must be handled"]
     async fn receive(&self) -> Result<Message>;
     /// Close the connection
+    #[must_use = "transport errors must be handled"]
     async fn close(&mut self) -> Result<()>;
 }
 
@@ -0,0 +1,265 @@
+use crate::protocol::{error_codes, Message, RequestId, Response, ResponseError};
+use crate::transport::MessageOrigin;
+use crate::{Error, Result};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tokio::sync::{mpsc, oneshot, Mutex as AsyncMutex};
+use tokio_util::sync::CancellationToken;
+
+/// Queue handing posted messages to `receive` and responses back to their requests
+/// 将提交的消息交给 `receive`，并将响应交回其请求的队列
+///
+/// The queue is inactive until something receives from it; until then the server
+/// answers posted messages with its built-in handlers.
+/// 在有调用方从队列接收消息之前，队列处于非活动状态；在此之前服务器使用内置处理器响应提交的消息。
+///
+/// Clients number their requests independently, so queued requests get an ID
+/// prefixed with their client's ID, and responses get the original ID back.
+/// 客户端各自独立编号请求，因此入队的请求会获得带有其客户端 ID 前缀的 ID，
+/// 响应则恢复为原始 ID。
+pub(crate) struct MessageQueue {
+    /// Whether `receive` has been called
+    /// 是否已调用过 `receive`
+    active: AtomicBool,
+    /// Sending half of the queue, carrying each message with its origin
+    /// 队列的发送端，每条消息附带其来源
+    sender: mpsc::UnboundedSender<(Message, MessageOrigin)>,
+    /// Receiving half of the queue
+    /// 队列的接收端
+    receiver: AsyncMutex<mpsc::UnboundedReceiver<(Message, MessageOrigin)>>,
+    /// Queued requests waiting for their response, keyed by their queued ID, with
+    /// the ID their client sent
+    /// 等待响应的已入队请求，以入队 ID 为键，并附带客户端发送的 ID
+    pending: Mutex<HashMap<RequestId, (RequestId, oneshot::Sender<Response>)>>,
+    /// Cancelled when the server closes
+    /// 服务器关闭时取消
+    closed: CancellationToken,
+}
+
+impl MessageQueue {
+    /// Create an inactive queue
+    /// 创建非活动队列
+    pub(crate) fn new() -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        Self {
+            active: AtomicBool::new(false),
+            sender,
+            receiver: AsyncMutex::new(receiver),
+            pending: Mutex::new(HashMap::new()),
+            closed: CancellationToken::new(),
+        }
+    }
+
+    /// Whether posted messages go to `receive`
+    /// 提交的消息是否交给 `receive`
+    pub(crate) fn is_active(&self) -> bool {
+        self.active.load(Ordering::SeqCst)
+    }
+
+    /// Next queued message and its origin, activating the queue
+    /// 下一条入队的消息及其来源，并激活队列
+    pub(crate) async fn receive(&self) -> Result<(Message, MessageOrigin)> {
+        self.active.store(true, Ordering::SeqCst);
+        let mut receiver = self.receiver.lock().await;
+        tokio::select! {
+            message = receiver.recv() => message,
+            _ = self.closed.cancelled() => None,
+        }
+        .ok_or_else(|| Error::Transport("Server closed".into()))
+    }
+
+    /// Queue a message that expects no response
+    /// 将不需要响应的消息入队
+    pub(crate) fn push(&self, message: Message, origin: MessageOrigin) {
+        let _ = self.sender.send((message, origin));
+    }
+
+    /// Queue a request or batch and wait for the responses to its requests
+    /// 将请求或批处理入队，并等待其中请求的响应
+    ///
+    /// Fails if the client already has a request with one of its IDs waiting for a
+    /// response.
+    /// 如果该客户端已有使用其中某个 ID 的请求在等待响应，则失败。
+    pub(crate) async fn forward(
+        &self,
+        mut message: Message,
+        origin: MessageOrigin,
+    ) -> Result<Vec<Response>> {
+        let client = origin.client_id.unwrap_or_default();
+        let mut requests: Vec<&mut RequestId> = match &mut message {
+            Message::Request(request) => vec![&mut request.id],
+            Message::Batch(batch) => batch
+                .iter_mut()
+                .filter_map(|message| match message {
+                    Message::Request(request) => Some(&mut request.id),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        let mut waiting = Vec::with_capacity(requests.len());
+        {
+            let mut pending = self.pending.lock().unwrap();
+            let queued: Vec<RequestId> = requests.iter().map(|id| queued_id(client, id)).collect();
+            if let Some(index) = queued.iter().position(|id| pending.contains_key(id)) {
+                return Err(Error::JsonRpc {
+                    code: error_codes::INVALID_REQUEST,
+                    message: format!("Request {:?} is already in flight", requests[index]),
+                });
+            }
+            for (id, queued) in requests.iter_mut().zip(queued) {
+                let original = std::mem::replace(*id, queued.clone());
+                let (sender, receiver) = oneshot::channel();
+                pending.insert(queued, (original.clone(), sender));
+                waiting.push((original, receiver));
+            }
+        }
+        self.push(message, origin);
+
+        let mut responses = Vec::with_capacity(waiting.len());
+        for (id, receiver) in waiting {
+            responses.push(receiver.await.unwrap_or_else(|_| {
+                Response::error(
+                    ResponseError {
+                        code: error_codes::INTERNAL_ERROR,
+                        message: "Request was not answered".to_string(),
+                        data: None,
+                    },
+                    id,
+                )
+            }));
+        }
+        Ok(responses)
+    }
+
+    /// Hand a response to the request waiting for it, restoring the ID its client sent
+    /// 将响应交给等待它的请求，并恢复其客户端发送的 ID
+    ///
+    /// Returns the response if no queued request is waiting for it.
+    /// 若没有入队的请求在等待该响应，则将其返回。
+    pub(crate) fn respond(&self, mut response: Response) -> Option<Response> {
+        let waiting = self.pending.lock().unwrap().remove(&response.id);
+        match waiting {
+            Some((original, waiting)) => {
+                response.id = original;
+                // The client may have gone away; nobody else wants the response
+                // 客户端可能已离开；没有其他人需要该响应
+                let _ = waiting.send(response);
+                None
+            }
+            None => Some(response),
+        }
+    }
+
+    /// Stop receiving and fail every request still waiting for a response
+    /// 停止接收，并使所有仍在等待响应的请求失败
+    pub(crate) fn close(&self) {
+        self.closed.cancel();
+        self.pending.lock().unwrap().clear();
+    }
+}
+
+/// ID a request from `client` is queued under, unique across clients
+/// 来自 `client` 的请求入队时使用的 ID，在所有客户端之间唯一
+fn queued_id(client: u64, id: &RequestId) -> RequestId {
+    let id = serde_json::to_string(id).unwrap_or_default();
+    RequestId::String(format!("{}:{}", client, id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{Method, Notification, Request};
+    use serde_json::json;
+
+    fn from(client: u64) -> MessageOrigin {
+        MessageOrigin {
+            client_id: Some(client),
+            auth: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_forwarded_request_gets_its_response() {
+        let queue = MessageQueue::new();
+        assert!(!queue.is_active());
+
+        let request = Request::ping(RequestId::Number(1));
+        let answer = async {
+            let (Message::Request(request), _) = queue.receive().await.unwrap() else {
+                panic!("expected a request");
+            };
+            assert!(queue.is_active());
+            assert!(queue
+                .respond(Response::success(json!({}), request.id.clone()))
+                .is_none());
+            request.id
+        };
+        let (responses, queued) =
+            tokio::join!(queue.forward(Message::Request(request), from(7)), answer);
+        assert_eq!(responses.unwrap()[0].id, RequestId::Number(1));
+
+        // Nobody waits for a second response with the same ID
+        // 没有人等待具有相同 ID 的第二个响应
+        assert!(queue
+            .respond(Response::success(json!({}), queued))
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn test_clients_may_use_the_same_ids() {
+        let queue = MessageQueue::new();
+        let request = || Message::Request(Request::ping(RequestId::Number(1)));
+
+        let answer = async {
+            for _ in 0..2 {
+                let (Message::Request(request), _) = queue.receive().await.unwrap() else {
+                    panic!("expected a request");
+                };
+                let result = json!({ "queued": request.id });
+                queue.respond(Response::success(result, request.id));
+            }
+        };
+        let (first, second, ()) = tokio::join!(
+            queue.forward(request(), from(1)),
+            queue.forward(request(), from(2)),
+            answer
+        );
+        let (first, second) = (&first.unwrap()[0], &second.unwrap()[0]);
+        assert_eq!(first.id, RequestId::Number(1));
+        assert_eq!(second.id, RequestId::Number(1));
+        assert_ne!(first.result, second.result);
+
+        // One client may not reuse an ID that is still in flight
+        // 同一客户端不能重用仍在执行中的 ID
+        let held = queue.forward(request(), from(3));
+        tokio::pin!(held);
+        assert!(futures::poll!(held.as_mut()).is_pending());
+        assert!(queue.forward(request(), from(3)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_closing_ends_receive_and_pending_requests() {
+        let queue = MessageQueue::new();
+        queue.push(
+            Message::Notification(Notification::new(Method::Initialized, None)),
+            from(1),
+        );
+        assert!(queue.receive().await.is_ok());
+
+        let request = Message::Request(Request::ping(RequestId::Number(2)));
+        let close = async {
+            queue.receive().await.unwrap();
+            queue.close();
+        };
+        let (responses, ()) = tokio::join!(queue.forward(request, from(1)), close);
+        let response = &responses.unwrap()[0];
+        assert_eq!(
+            response.error.as_ref().unwrap().code,
+            error_codes::INTERNAL_ERROR
+        );
+        assert!(queue.receive().await.is_err());
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Rate limit settings
+/// 限流设置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    /// Requests allowed per window, also the burst size
+    /// 每个时间窗口允许的请求数，同时也是突发容量
+    pub requests: u32,
+    /// Length of the window
+    /// 时间窗口长度
+    pub per: Duration,
+}
+
+/// Token bucket of a single key
+/// 单个键的令牌桶
+struct Bucket {
+    /// Tokens currently available
+    /// 当前可用的令牌数
+    tokens: f64,
+    /// Last time the bucket was refilled
+    /// 上次补充令牌的时间
+    refilled_at: Instant,
+}
+
+/// Token-bucket rate limiter keyed by client
+/// 按客户端划分的令牌桶限流器
+///
+/// Each key starts with `requests` tokens, which refill continuously at `requests`
+/// per `per`. Every allowed request takes one token.
+/// 每个键初始拥有 `requests` 个令牌，并以每 `per` 补充 `requests` 个的速率持续补充。
+/// 每个被允许的请求消耗一个令牌。
+pub struct RateLimiter {
+    /// Configured limit
+    /// 配置的限制
+    limit: RateLimit,
+    /// Buckets by key
+    /// 按键划分的令牌桶
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateLimiter {
+    /// Create a new rate limiter
+    /// 创建新的限流器
+    pub fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Tokens added per second
+    /// 每秒补充的令牌数
+    fn refill_rate(&self) -> f64 {
+        self.limit.requests as f64 / self.limit.per.as_secs_f64().max(f64::EPSILON)
+    }
+
+    /// Take a token for `key`, or return how long to wait until one is available
+    /// 为 `key` 取一个令牌，否则返回需要等待多久才有可用令牌
+    pub fn check(&self, key: &str) -> std::result::Result<(), Duration> {
+        let capacity = self.limit.requests as f64;
+        let rate = self.refill_rate();
+        let now = Instant::now();
+
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.entry(key.to_string()).or_insert(Bucket {
+            tokens: capacity,
+            refilled_at: now,
+        });
+
+        let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(capacity);
+        bucket.refilled_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / rate))
+        }
+    }
+
+    /// Drop buckets that have refilled completely, as they behave like new ones
+    /// 移除已完全补满的令牌桶，因为它们与新建的桶行为相同
+    pub fn prune(&self) {
+        let capacity = self.limit.requests as f64;
+        let rate = self.refill_rate();
+        let now = Instant::now();
+
+        self.buckets.lock().unwrap().retain(|_, bucket| {
+            let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+            bucket.tokens + elapsed * rate < capacity
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_allows_burst_then_limits() {
+        let limiter = RateLimiter::new(RateLimit {
+            requests: 3,
+            per: Duration::from_secs(60),
+        });
+
+        for _ in 0..3 {
+            assert!(limiter.check("a").is_ok());
+        }
+        let retry_after = limiter.check("a").unwrap_err();
+        assert!(retry_after > Duration::from_secs(19) && retry_after <= Duration::from_secs(20));
+
+        // Other keys have their own bucket
+        // 其他键拥有各自的令牌桶
+        assert!(limiter.check("b").is_ok());
+    }
+
+    #[test]
+    fn test_tokens_refill_over_time() {
+        let limiter = RateLimiter::new(RateLimit {
+            requests: 1,
+            per: Duration::from_millis(20),
+        });
+
+        assert!(limiter.check("a").is_ok());
+        assert!(limiter.check("a").is_err());
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(limiter.check("a").is_ok());
+
+        std::thread::sleep(Duration::from_millis(30));
+        limiter.prune();
+        assert!(limiter.buckets.lock().unwrap().is_empty());
+    }
+}
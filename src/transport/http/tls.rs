@@ -0,0 +1,345 @@
+use reqwest::ClientBuilder;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::client::WebPkiServerVerifier;
+use rustls::crypto::{self, CryptoProvider};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{DigitallySignedStruct, RootCertStore, SignatureScheme};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::sync::Arc;
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+use crate::{Error, Result};
+
+/// TLS settings of the HTTP client
+/// HTTP 客户端的 TLS 设置
+#[derive(Debug, Clone, Default)]
+pub struct TlsClientConfig {
+    /// Hex-encoded SHA-256 fingerprints of pinned SubjectPublicKeyInfo structures
+    /// 固定的 SubjectPublicKeyInfo 结构的十六进制 SHA-256 指纹
+    ///
+    /// When non-empty, connections are rejected unless the server certificate's
+    /// public key matches one of the pins.
+    /// 非空时，除非服务器证书的公钥与其中一个指纹匹配，否则拒绝连接。
+    pub certificate_pins: Vec<String>,
+    /// Skip certificate chain and hostname verification
+    /// 跳过证书链和主机名验证
+    pub danger_accept_invalid_certs: bool,
+}
+
+impl TlsClientConfig {
+    /// Derives the SPKI fingerprint of a PEM-encoded certificate, suitable for `certificate_pins`
+    /// 计算 PEM 编码证书的 SPKI 指纹，可用于 `certificate_pins`
+    pub fn pin_from_pem(pem: &str) -> Result<String> {
+        let cert = CertificateDer::from_pem_slice(pem.as_bytes())
+            .map_err(|e| Error::Configuration(format!("Invalid PEM certificate: {}", e)))?;
+        spki_fingerprint(&cert)
+            .map_err(|e| Error::Configuration(format!("Invalid certificate: {}", e)))
+    }
+
+    /// Applies the settings to a reqwest client builder
+    /// 将设置应用到 reqwest 客户端构建器
+    pub(crate) fn apply(&self, builder: ClientBuilder) -> Result<ClientBuilder> {
+        if self.certificate_pins.is_empty() {
+            return Ok(builder.danger_accept_invalid_certs(self.danger_accept_invalid_certs));
+        }
+
+        if self.danger_accept_invalid_certs {
+            tracing::warn!(
+                "TLS certificate pins are configured while certificate verification is disabled; \
+                 only the pinned keys are checked"
+            );
+        }
+
+        let provider = Arc::new(crypto::ring::default_provider());
+        let inner = if self.danger_accept_invalid_certs {
+            None
+        } else {
+            let roots = RootCertStore {
+                roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+            };
+            let verifier =
+                WebPkiServerVerifier::builder_with_provider(Arc::new(roots), provider.clone())
+                    .build()
+                    .map_err(|e| Error::Configuration(e.to_string()))?;
+            Some(verifier)
+        };
+
+        let verifier = PinnedCertVerifier {
+            pins: normalize_pins(&self.certificate_pins)?,
+            inner,
+            provider: provider.clone(),
+        };
+        let tls = rustls::ClientConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .map_err(|e| Error::Configuration(e.to_string()))?
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(verifier))
+            .with_no_client_auth();
+        Ok(builder.use_preconfigured_tls(tls))
+    }
+}
+
+/// Lowercases pins and strips `:` separators, rejecting anything that is not a SHA-256 hash
+/// 将指纹转为小写并去除 `:` 分隔符，拒绝任何不是 SHA-256 哈希的内容
+fn normalize_pins(pins: &[String]) -> Result<HashSet<String>> {
+    pins.iter()
+        .map(|pin| {
+            let normalized = pin.replace(':', "").to_ascii_lowercase();
+            if normalized.len() == 64 && normalized.chars().all(|c| c.is_ascii_hexdigit()) {
+                Ok(normalized)
+            } else {
+                Err(Error::Configuration(format!(
+                    "Invalid certificate pin (expected hex SHA-256): {}",
+                    pin
+                )))
+            }
+        })
+        .collect()
+}
+
+/// Hex-encoded SHA-256 hash of a certificate's SubjectPublicKeyInfo
+/// 证书 SubjectPublicKeyInfo 的十六进制 SHA-256 哈希
+fn spki_fingerprint(cert: &CertificateDer<'_>) -> std::result::Result<String, String> {
+    let (_, parsed) = X509Certificate::from_der(cert.as_ref()).map_err(|e| e.to_string())?;
+    Ok(hex::encode(Sha256::digest(parsed.public_key().raw)))
+}
+
+/// Certificate verifier that additionally requires the server key to match a pin
+/// 额外要求服务器密钥与固定指纹匹配的证书验证器
+#[derive(Debug)]
+struct PinnedCertVerifier {
+    /// Normalized pins
+    /// 规范化后的指纹
+    pins: HashSet<String>,
+    /// Regular WebPKI verification, unless disabled
+    /// 常规 WebPKI 验证（除非已禁用）
+    inner: Option<Arc<WebPkiServerVerifier>>,
+    /// Provider used to check handshake signatures
+    /// 用于检查握手签名的提供者
+    provider: Arc<CryptoProvider>,
+}
+
+impl ServerCertVerifier for PinnedCertVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> std::result::Result<ServerCertVerified, rustls::Error> {
+        if let Some(inner) = &self.inner {
+            inner.verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)?;
+        }
+
+        let fingerprint = spki_fingerprint(end_entity).map_err(rustls::Error::General)?;
+        if self.pins.contains(&fingerprint) {
+            Ok(ServerCertVerified::assertion())
+        } else {
+            Err(rustls::Error::General(format!(
+                "Server certificate key {} does not match any pinned key",
+                fingerprint
+            )))
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+        crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+        crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rcgen::{CertifiedKey, KeyPair};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio_rustls::TlsAcceptor;
+
+    fn self_signed() -> CertifiedKey {
+        rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap()
+    }
+
+    fn pin_of(key: &KeyPair) -> String {
+        hex::encode(Sha256::digest(key.public_key_der()))
+    }
+
+    fn verifier(pins: &[String], verify_chain: bool) -> PinnedCertVerifier {
+        let provider = Arc::new(crypto::ring::default_provider());
+        let inner = verify_chain.then(|| {
+            let roots = RootCertStore {
+                roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+            };
+            WebPkiServerVerifier::builder_with_provider(Arc::new(roots), provider.clone())
+                .build()
+                .unwrap()
+        });
+        PinnedCertVerifier {
+            pins: normalize_pins(pins).unwrap(),
+            inner,
+            provider,
+        }
+    }
+
+    fn verify(verifier: &PinnedCertVerifier, cert: &CertificateDer<'_>) -> bool {
+        verifier
+            .verify_server_cert(
+                cert,
+                &[],
+                &ServerName::try_from("localhost").unwrap(),
+                &[],
+                UnixTime::now(),
+            )
+            .is_ok()
+    }
+
+    /// Serve a minimal HTTPS endpoint with the given certificate
+    /// 使用给定证书提供一个最小的 HTTPS 端点
+    async fn serve_https(certified: CertifiedKey) -> u16 {
+        let key =
+            rustls::pki_types::PrivateKeyDer::try_from(certified.key_pair.serialize_der()).unwrap();
+        let config =
+            rustls::ServerConfig::builder_with_provider(Arc::new(crypto::ring::default_provider()))
+                .with_safe_default_protocol_versions()
+                .unwrap()
+                .with_no_client_auth()
+                .with_single_cert(vec![certified.cert.der().clone()], key)
+                .unwrap();
+        let acceptor = TlsAcceptor::from(Arc::new(config));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let acceptor = acceptor.clone();
+                tokio::spawn(async move {
+                    if let Ok(mut tls) = acceptor.accept(stream).await {
+                        let mut buf = [0u8; 1024];
+                        let _ = tls.read(&mut buf).await;
+                        let _ = tls
+                            .write_all(
+                                b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\nconnection: close\r\n\r\nok",
+                            )
+                            .await;
+                        let _ = tls.shutdown().await;
+                    }
+                });
+            }
+        });
+        port
+    }
+
+    #[test]
+    fn test_pin_from_pem_matches_public_key() {
+        let certified = self_signed();
+        let pin = TlsClientConfig::pin_from_pem(&certified.cert.pem()).unwrap();
+        assert_eq!(pin, pin_of(&certified.key_pair));
+    }
+
+    #[test]
+    fn test_pin_from_pem_rejects_garbage() {
+        assert!(matches!(
+            TlsClientConfig::pin_from_pem("not a certificate"),
+            Err(Error::Configuration(_))
+        ));
+    }
+
+    #[test]
+    fn test_pins_are_normalized_and_validated() {
+        let pin = pin_of(&self_signed().key_pair).to_ascii_uppercase();
+        let with_colons: String = pin
+            .as_bytes()
+            .chunks(2)
+            .map(|pair| std::str::from_utf8(pair).unwrap())
+            .collect::<Vec<_>>()
+            .join(":");
+        assert_eq!(
+            normalize_pins(&[with_colons]).unwrap(),
+            HashSet::from([pin.to_ascii_lowercase()])
+        );
+        assert!(normalize_pins(&["abc".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_verifier_checks_pins() {
+        let certified = self_signed();
+        let cert = certified.cert.der().clone();
+
+        assert!(verify(
+            &verifier(&[pin_of(&certified.key_pair)], false),
+            &cert
+        ));
+        assert!(!verify(
+            &verifier(&[pin_of(&self_signed().key_pair)], false),
+            &cert
+        ));
+    }
+
+    #[test]
+    fn test_pin_does_not_bypass_chain_verification() {
+        let certified = self_signed();
+        let cert = certified.cert.der().clone();
+
+        assert!(!verify(
+            &verifier(&[pin_of(&certified.key_pair)], true),
+            &cert
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_https_connection_is_pinned() {
+        let certified = self_signed();
+        let pin = TlsClientConfig::pin_from_pem(&certified.cert.pem()).unwrap();
+        let port = serve_https(certified).await;
+        let url = format!("https://localhost:{}/", port);
+
+        let client = |pins: Vec<String>| {
+            TlsClientConfig {
+                certificate_pins: pins,
+                danger_accept_invalid_certs: true,
+            }
+            .apply(reqwest::Client::builder())
+            .unwrap()
+            .build()
+            .unwrap()
+        };
+
+        let response = client(vec![pin]).get(&url).send().await.unwrap();
+        assert_eq!(response.text().await.unwrap(), "ok");
+
+        let wrong_pin = pin_of(&self_signed().key_pair);
+        assert!(client(vec![wrong_pin]).get(&url).send().await.is_err());
+    }
+}
@@ -0,0 +1,147 @@
+use async_trait::async_trait;
+use std::sync::Mutex as StdMutex;
+use tokio::sync::{mpsc, Mutex};
+
+use super::Transport;
+use crate::{protocol::Message, Result};
+
+/// In-memory transport connecting two endpoints in the same process
+pub struct MemoryTransport {
+    sender: StdMutex<Option<mpsc::UnboundedSender<Message>>>,
+    receiver: Mutex<mpsc::UnboundedReceiver<Message>>,
+}
+
+impl MemoryTransport {
+    /// Create a pair of connected transports
+    ///
+    /// Messages sent on one end are received on the other.
+    pub fn pair() -> (Self, Self) {
+        let (a_tx, a_rx) = mpsc::unbounded_channel();
+        let (b_tx, b_rx) = mpsc::unbounded_channel();
+        (Self::new(a_tx, b_rx), Self::new(b_tx, a_rx))
+    }
+
+    fn new(
+        sender: mpsc::UnboundedSender<Message>,
+        receiver: mpsc::UnboundedReceiver<Message>,
+    ) -> Self {
+        Self {
+            sender: StdMutex::new(Some(sender)),
+            receiver: Mutex::new(receiver),
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for MemoryTransport {
+    async fn initialize(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn send(&self, message: Message) -> Result<()> {
+        self.sender
+            .lock()
+            .unwrap()
+            .as_ref()
+            .ok_or_else(|| crate::Error::Transport("Transport closed".into()))?
+            .send(message)
+            .map_err(|_| crate::Error::Transport("Peer closed".into()))
+    }
+
+    async fn receive(&self) -> Result<Message> {
+        self.receiver
+            .lock()
+            .await
+            .recv()
+            .await
+            .ok_or_else(|| crate::Error::Transport("Connection closed".into()))
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        // Dropping the sender lets the peer observe the end of the stream
+        self.sender.lock().unwrap().take();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{Method, Notification};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_pair_delivers_messages_both_ways() {
+        let (a, b) = MemoryTransport::pair();
+
+        a.send(Message::Notification(Notification::new(
+            Method::Initialized,
+            None,
+        )))
+        .await
+        .unwrap();
+        assert!(matches!(
+            b.receive().await.unwrap(),
+            Message::Notification(_)
+        ));
+
+        b.send(Message::Notification(Notification::new(Method::Exit, None)))
+            .await
+            .unwrap();
+        assert!(matches!(
+            a.receive().await.unwrap(),
+            Message::Notification(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_receive_many_returns_what_arrived_in_time() {
+        let (producer, consumer) = MemoryTransport::pair();
+        for _ in 0..3 {
+            producer
+                .send(Message::Notification(Notification::new(
+                    Method::Initialized,
+                    None,
+                )))
+                .await
+                .unwrap();
+        }
+
+        let received = consumer
+            .receive_many(5, Duration::from_millis(50))
+            .await
+            .unwrap();
+        assert_eq!(received.len(), 3);
+        let none = consumer
+            .receive_many(5, Duration::from_millis(10))
+            .await
+            .unwrap();
+        assert!(none.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_send_raw_parses_by_default() {
+        let (a, b) = MemoryTransport::pair();
+
+        a.send_raw(r#"{"jsonrpc":"2.0","method":"ping","id":1}"#)
+            .await
+            .unwrap();
+        assert!(matches!(
+            b.receive().await.unwrap(),
+            Message::Request(request) if request.method == "ping"
+        ));
+        assert!(a.send_raw("not json").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_close_ends_peer_stream() {
+        let (mut a, b) = MemoryTransport::pair();
+        a.close().await.unwrap();
+
+        assert!(b.receive().await.is_err());
+        assert!(a
+            .send(Message::Notification(Notification::new(Method::Exit, None)))
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,521 @@
+use async_trait::async_trait;
+use serde_json::Value;
+use std::sync::Arc;
+
+use super::{ClientFilter, MessageOrigin, Transport, TransportHealth};
+use crate::{
+    protocol::{Message, Notification, PrettyMessage},
+    Error, Result,
+};
+
+/// Hook that observes or rewrites messages passing through a transport
+///
+/// Returning `Ok(None)` drops the message; returning an error fails the `send` or
+/// `receive` call that triggered the hook.
+#[async_trait]
+pub trait TransportMiddleware: Send + Sync {
+    /// Called before a message is sent
+    async fn on_send(&self, message: Message) -> Result<Option<Message>> {
+        Ok(Some(message))
+    }
+
+    /// Called after a message is received
+    async fn on_receive(&self, message: Message) -> Result<Option<Message>> {
+        Ok(Some(message))
+    }
+}
+
+/// Middleware logging every message with `tracing`
+///
+/// Messages are logged at `DEBUG` and pretty-printed at `TRACE`.
+#[derive(Debug, Clone, Default)]
+pub struct LoggingMiddleware {
+    label: Option<String>,
+}
+
+impl LoggingMiddleware {
+    /// Create a logging middleware
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Prefix log lines with a label, e.g. the name of the transport
+    pub fn with_label(label: &str) -> Self {
+        Self {
+            label: Some(label.to_string()),
+        }
+    }
+
+    fn log(&self, direction: &str, message: &Message) {
+        let label = self.label.as_deref().unwrap_or("transport");
+        if tracing::enabled!(tracing::Level::TRACE) {
+            tracing::trace!("{} {}: {}", label, direction, PrettyMessage(message));
+        } else {
+            tracing::debug!("{} {}: {:?}", label, direction, message);
+        }
+    }
+}
+
+#[async_trait]
+impl TransportMiddleware for LoggingMiddleware {
+    async fn on_send(&self, message: Message) -> Result<Option<Message>> {
+        self.log("send", &message);
+        Ok(Some(message))
+    }
+
+    async fn on_receive(&self, message: Message) -> Result<Option<Message>> {
+        self.log("receive", &message);
+        Ok(Some(message))
+    }
+}
+
+/// Which parts of a message `RedactingMiddleware` hides
+#[derive(Debug, Clone, Default)]
+pub struct RedactionConfig {
+    /// Hide every string in the `result` of responses
+    pub redact_response_results: bool,
+    /// JSONPath-like patterns selecting sensitive values
+    ///
+    /// Patterns start at `$`, the message root, followed by `.name` or `['name']`
+    /// for object keys, `[0]` for array elements, `*` or `[*]` for any key or
+    /// element, and `..name` for a key at any depth below. For example
+    /// `$.result.api_key` or `$..password`.
+    pub sensitive_field_patterns: Vec<String>,
+}
+
+/// Placeholder replacing redacted strings
+pub const REDACTED: &str = "[REDACTED]";
+
+#[derive(Debug, Clone, PartialEq)]
+enum PathSegment {
+    Key(String),
+    Any,
+    Descendant(String),
+}
+
+fn parse_pattern(pattern: &str) -> Result<Vec<PathSegment>> {
+    let invalid = || Error::Configuration(format!("Invalid redaction pattern: {}", pattern));
+    let mut rest = pattern.strip_prefix('$').ok_or_else(invalid)?;
+    let mut segments = Vec::new();
+
+    let take_name = |rest: &str| {
+        let end = rest.find(['.', '[']).unwrap_or(rest.len());
+        (rest[..end].to_string(), end)
+    };
+
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("..") {
+            let (name, end) = take_name(after);
+            if name.is_empty() || name == "*" {
+                return Err(invalid());
+            }
+            segments.push(PathSegment::Descendant(name));
+            rest = &after[end..];
+        } else if let Some(after) = rest.strip_prefix('.') {
+            let (name, end) = take_name(after);
+            segments.push(match name.as_str() {
+                "" => return Err(invalid()),
+                "*" => PathSegment::Any,
+                _ => PathSegment::Key(name),
+            });
+            rest = &after[end..];
+        } else if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']').ok_or_else(invalid)?;
+            let inner = &after[..end];
+            segments.push(match inner {
+                "*" => PathSegment::Any,
+                _ => PathSegment::Key(inner.trim_matches(|c| c == '\'' || c == '"').to_string()),
+            });
+            rest = &after[end + 1..];
+        } else {
+            return Err(invalid());
+        }
+    }
+    Ok(segments)
+}
+
+fn redact_strings(value: &mut Value) {
+    match value {
+        Value::String(s) => *s = REDACTED.to_string(),
+        Value::Array(items) => items.iter_mut().for_each(redact_strings),
+        Value::Object(map) => map.values_mut().for_each(redact_strings),
+        _ => {}
+    }
+}
+
+fn redact_path(value: &mut Value, path: &[PathSegment]) {
+    let Some((segment, rest)) = path.split_first() else {
+        redact_strings(value);
+        return;
+    };
+
+    match segment {
+        PathSegment::Key(key) => {
+            let child = match value {
+                Value::Object(map) => map.get_mut(key),
+                Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get_mut(i)),
+                _ => None,
+            };
+            if let Some(child) = child {
+                redact_path(child, rest);
+            }
+        }
+        PathSegment::Any => match value {
+            Value::Object(map) => map.values_mut().for_each(|v| redact_path(v, rest)),
+            Value::Array(items) => items.iter_mut().for_each(|v| redact_path(v, rest)),
+            _ => {}
+        },
+        PathSegment::Descendant(key) => {
+            if let Some(child) = value.as_object_mut().and_then(|map| map.get_mut(key)) {
+                redact_path(child, rest);
+            }
+            match value {
+                Value::Object(map) => map.values_mut().for_each(|v| redact_path(v, path)),
+                Value::Array(items) => items.iter_mut().for_each(|v| redact_path(v, path)),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Middleware showing an inner observer, such as `LoggingMiddleware`, a redacted
+/// copy of every message
+///
+/// The message passed on through the stack is the original; whatever the inner
+/// middleware returns is ignored, apart from errors.
+pub struct RedactingMiddleware {
+    paths: Vec<Vec<PathSegment>>,
+    inner: Arc<dyn TransportMiddleware>,
+}
+
+impl RedactingMiddleware {
+    /// Redact messages according to `config` before handing them to `inner`
+    ///
+    /// Fails with `Error::Configuration` if a pattern cannot be parsed.
+    pub fn new(config: RedactionConfig, inner: Arc<dyn TransportMiddleware>) -> Result<Self> {
+        let mut paths = config
+            .sensitive_field_patterns
+            .iter()
+            .map(|pattern| parse_pattern(pattern))
+            .collect::<Result<Vec<_>>>()?;
+        if config.redact_response_results {
+            paths.push(vec![PathSegment::Key("result".to_string())]);
+        }
+        Ok(Self { paths, inner })
+    }
+
+    /// Redacted copy of a message
+    pub fn redact(&self, message: &Message) -> Result<Message> {
+        let mut value = serde_json::to_value(message)?;
+        for path in &self.paths {
+            redact_path(&mut value, path);
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+}
+
+#[async_trait]
+impl TransportMiddleware for RedactingMiddleware {
+    async fn on_send(&self, message: Message) -> Result<Option<Message>> {
+        self.inner.on_send(self.redact(&message)?).await?;
+        Ok(Some(message))
+    }
+
+    async fn on_receive(&self, message: Message) -> Result<Option<Message>> {
+        self.inner.on_receive(self.redact(&message)?).await?;
+        Ok(Some(message))
+    }
+}
+
+/// Transport wrapper running a middleware stack on every message
+///
+/// Outgoing messages pass through the stack in order, incoming messages in reverse
+/// order, so the first middleware is the one closest to the application.
+pub struct MiddlewareTransport<T: Transport> {
+    inner: T,
+    middleware: Vec<Arc<dyn TransportMiddleware>>,
+}
+
+impl<T: Transport> MiddlewareTransport<T> {
+    /// Wrap a transport with an empty stack
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            middleware: Vec::new(),
+        }
+    }
+
+    /// Append a middleware to the stack
+    pub fn with(mut self, middleware: Arc<dyn TransportMiddleware>) -> Self {
+        self.middleware.push(middleware);
+        self
+    }
+
+    /// Run `message` through the send hooks, `None` if one of them dropped it
+    async fn outgoing(&self, mut message: Message) -> Result<Option<Message>> {
+        for middleware in &self.middleware {
+            match middleware.on_send(message).await? {
+                Some(next) => message = next,
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(message))
+    }
+}
+
+#[async_trait]
+impl<T: Transport> Transport for MiddlewareTransport<T> {
+    async fn initialize(&mut self) -> Result<()> {
+        self.inner.initialize().await
+    }
+
+    async fn send(&self, message: Message) -> Result<()> {
+        match self.outgoing(message).await? {
+            Some(message) => self.inner.send(message).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Runs the send hooks; a notification that stays one still reaches only the
+    /// clients `accept` selects
+    async fn send_filtered(&self, notification: Notification, accept: ClientFilter) -> Result<()> {
+        match self.outgoing(Message::Notification(notification)).await? {
+            Some(Message::Notification(notification)) => {
+                self.inner.send_filtered(notification, accept).await
+            }
+            Some(message) => self.inner.send(message).await,
+            None => Ok(()),
+        }
+    }
+
+    async fn receive(&self) -> Result<Message> {
+        Ok(self.receive_with_origin().await?.0)
+    }
+
+    async fn receive_with_origin(&self) -> Result<(Message, MessageOrigin)> {
+        'next: loop {
+            let (mut message, origin) = self.inner.receive_with_origin().await?;
+            for middleware in self.middleware.iter().rev() {
+                match middleware.on_receive(message).await? {
+                    Some(next) => message = next,
+                    None => continue 'next,
+                }
+            }
+            return Ok((message, origin));
+        }
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        self.inner.close().await
+    }
+
+    async fn health(&self) -> TransportHealth {
+        self.inner.health().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{Method, Notification};
+    use crate::transport::MemoryTransport;
+
+    /// Drops pings and renames everything else to `exit`
+    struct Rewrite;
+
+    #[async_trait]
+    impl TransportMiddleware for Rewrite {
+        async fn on_send(&self, message: Message) -> Result<Option<Message>> {
+            Ok(match message {
+                Message::Notification(n) if n.method == "ping" => None,
+                Message::Notification(mut n) => {
+                    n.method = Method::Exit.to_string();
+                    Some(Message::Notification(n))
+                }
+                other => Some(other),
+            })
+        }
+
+        async fn on_receive(&self, message: Message) -> Result<Option<Message>> {
+            self.on_send(message).await
+        }
+    }
+
+    fn notification(method: Method) -> Message {
+        Message::Notification(Notification::new(method, None))
+    }
+
+    fn method(message: Message) -> String {
+        match message {
+            Message::Notification(n) => n.method,
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_path_rewrites_and_drops() {
+        let (a, b) = MemoryTransport::pair();
+        let a = MiddlewareTransport::new(a).with(Arc::new(Rewrite));
+
+        a.send(notification(Method::Ping)).await.unwrap();
+        a.send(notification(Method::Initialized)).await.unwrap();
+        assert_eq!(method(b.receive().await.unwrap()), "exit");
+    }
+
+    /// Reports every message as coming from client 7
+    struct Stamped(MemoryTransport);
+
+    #[async_trait]
+    impl Transport for Stamped {
+        async fn initialize(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn send(&self, message: Message) -> Result<()> {
+            self.0.send(message).await
+        }
+
+        async fn receive(&self) -> Result<Message> {
+            self.0.receive().await
+        }
+
+        async fn receive_with_origin(&self) -> Result<(Message, MessageOrigin)> {
+            let origin = MessageOrigin {
+                client_id: Some(7),
+                auth: None,
+            };
+            Ok((self.0.receive().await?, origin))
+        }
+
+        async fn close(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_receive_path_keeps_the_origin() {
+        let (a, b) = MemoryTransport::pair();
+        let a = MiddlewareTransport::new(Stamped(a)).with(Arc::new(Rewrite));
+
+        b.send(notification(Method::Ping)).await.unwrap();
+        b.send(notification(Method::Initialized)).await.unwrap();
+        let (message, origin) = a.receive_with_origin().await.unwrap();
+        assert_eq!(method(message), "exit");
+        assert_eq!(origin.client_id, Some(7));
+    }
+
+    /// Records every message it observes
+    #[derive(Default)]
+    struct Recorder(std::sync::Mutex<Vec<Message>>);
+
+    #[async_trait]
+    impl TransportMiddleware for Recorder {
+        async fn on_send(&self, message: Message) -> Result<Option<Message>> {
+            self.0.lock().unwrap().push(message.clone());
+            Ok(Some(message))
+        }
+
+        async fn on_receive(&self, message: Message) -> Result<Option<Message>> {
+            self.on_send(message).await
+        }
+    }
+
+    fn response(result: Value) -> Message {
+        Message::Response(crate::protocol::Response::success(
+            result,
+            crate::protocol::RequestId::Number(1),
+        ))
+    }
+
+    fn result_of(message: &Message) -> Value {
+        match message {
+            Message::Response(response) => response.result.clone().unwrap(),
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_only_the_observed_copy_is_redacted() {
+        let recorder = Arc::new(Recorder::default());
+        let config = RedactionConfig {
+            redact_response_results: false,
+            sensitive_field_patterns: vec!["$.result.api_key".to_string()],
+        };
+        let redacting = RedactingMiddleware::new(config, recorder.clone()).unwrap();
+        let (a, b) = MemoryTransport::pair();
+        let a = MiddlewareTransport::new(a).with(Arc::new(redacting));
+
+        let original = serde_json::json!({ "api_key": "sk-123", "name": "demo" });
+        a.send(response(original.clone())).await.unwrap();
+
+        assert_eq!(result_of(&b.receive().await.unwrap()), original);
+        assert_eq!(
+            result_of(&recorder.0.lock().unwrap()[0]),
+            serde_json::json!({ "api_key": REDACTED, "name": "demo" })
+        );
+    }
+
+    #[test]
+    fn test_redaction_patterns() {
+        let config = RedactionConfig {
+            redact_response_results: false,
+            sensitive_field_patterns: vec![
+                "$..password".to_string(),
+                "$.result.items[*].token".to_string(),
+                "$.result['nested'].list[1]".to_string(),
+            ],
+        };
+        let redacting = RedactingMiddleware::new(config, Arc::new(Recorder::default())).unwrap();
+        let message = response(serde_json::json!({
+            "user": { "password": "hunter2", "count": 3 },
+            "items": [{ "token": "a" }, { "token": "b", "keep": "c" }],
+            "nested": { "list": ["x", "y"] }
+        }));
+
+        assert_eq!(
+            result_of(&redacting.redact(&message).unwrap()),
+            serde_json::json!({
+                "user": { "password": REDACTED, "count": 3 },
+                "items": [{ "token": REDACTED }, { "token": REDACTED, "keep": "c" }],
+                "nested": { "list": ["x", REDACTED] }
+            })
+        );
+    }
+
+    #[test]
+    fn test_redact_response_results_and_invalid_patterns() {
+        let config = RedactionConfig {
+            redact_response_results: true,
+            sensitive_field_patterns: vec![],
+        };
+        let redacting = RedactingMiddleware::new(config, Arc::new(Recorder::default())).unwrap();
+        let message = response(serde_json::json!({ "data": ["a", 1], "text": "b" }));
+        assert_eq!(
+            result_of(&redacting.redact(&message).unwrap()),
+            serde_json::json!({ "data": [REDACTED, 1], "text": REDACTED })
+        );
+
+        for pattern in ["result.api_key", "$.", "$..", "$[0"] {
+            let config = RedactionConfig {
+                redact_response_results: false,
+                sensitive_field_patterns: vec![pattern.to_string()],
+            };
+            assert!(matches!(
+                RedactingMiddleware::new(config, Arc::new(Recorder::default())),
+                Err(Error::Configuration(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_receive_path_skips_dropped_messages() {
+        let (a, b) = MemoryTransport::pair();
+        let a = MiddlewareTransport::new(a)
+            .with(Arc::new(LoggingMiddleware::new()))
+            .with(Arc::new(Rewrite));
+
+        b.send(notification(Method::Ping)).await.unwrap();
+        b.send(notification(Method::Initialized)).await.unwrap();
+        assert_eq!(method(a.receive().await.unwrap()), "exit");
+    }
+}
@@ -1,14 +1,40 @@
 use async_trait::async_trait;
 use serde_json::Value;
+use std::env::VarError;
+use std::sync::Arc;
+use std::time::Duration;
 
 use crate::transport::{http::HttpTransport, stdio::StdioTransport};
-use crate::{protocol::Message, Result};
+use crate::{
+    protocol::{Message, Notification},
+    server_features::AuthContext,
+    Result,
+};
 
+pub mod backpressure;
+pub mod fallback;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod health;
 pub mod http;
+pub mod memory;
+pub mod middleware;
+pub mod proxy;
 pub mod stdio;
 
 // Re-export default implementations
+pub use backpressure::{BackpressureConfig, BackpressureMode, BackpressureTransport};
+pub use fallback::FallbackTransport;
+#[cfg(feature = "grpc")]
+pub use grpc::{GrpcClientTransport, GrpcServerTransport};
+pub use health::{HealthStatus, TransportHealth};
 pub use http::{client::DefaultHttpClient as HttpClient, server::DefaultHttpServer as HttpServer};
+pub use memory::MemoryTransport;
+pub use middleware::{
+    LoggingMiddleware, MiddlewareTransport, RedactingMiddleware, RedactionConfig,
+    TransportMiddleware,
+};
+pub use proxy::{proxy, proxy_with_middleware};
 pub use stdio::{
     client::DefaultStdioClient as StdioClient, server::DefaultStdioServer as StdioServer,
 };
@@ -22,6 +48,131 @@ pub struct TransportConfig {
     pub parameters: Option<Value>,
 }
 
+impl TransportConfig {
+    /// Build a configuration from `MCP_*` environment variables
+    ///
+    /// `MCP_TRANSPORT` selects `stdio` (the default) or `http`. Stdio reads the optional
+    /// `MCP_SERVER_PATH` and whitespace-separated `MCP_SERVER_ARGS`; HTTP requires
+    /// `MCP_BASE_URL` and reads the optional `MCP_AUTH_TOKEN`.
+    pub fn from_env() -> Result<Self> {
+        Self::from_lookup(|key| std::env::var(key))
+    }
+
+    /// Build a configuration from a URI
+    ///
+    /// - `stdio:///path/to/server --arg` runs the server at that path with the
+    ///   whitespace-separated arguments after it; a bare `stdio://` configures a
+    ///   stdio server
+    /// - `http://host:port` and `https://...` select HTTP, taking the auth token from
+    ///   a `?token=` query
+    /// - `grpc://host:port` selects gRPC, and `grpcs://` gRPC over TLS
+    ///
+    /// There is no WebSocket transport, so `ws://` and `wss://` are rejected.
+    pub fn from_uri(uri: &str) -> Result<Self> {
+        let invalid = |reason: String| {
+            crate::Error::Configuration(format!("Invalid transport URI {}: {}", uri, reason))
+        };
+        let (scheme, rest) = uri
+            .split_once("://")
+            .ok_or_else(|| invalid("missing scheme".into()))?;
+
+        let transport_type = match scheme.to_ascii_lowercase().as_str() {
+            "stdio" => {
+                let mut words = rest.split_whitespace();
+                let server_path = words.next().map(String::from);
+                let server_args: Vec<String> = words.map(String::from).collect();
+                TransportType::Stdio {
+                    server_path,
+                    server_args: (!server_args.is_empty()).then_some(server_args),
+                }
+            }
+            "http" | "https" => {
+                let (base_url, auth_token) = split_token(uri).map_err(invalid)?;
+                TransportType::Http {
+                    base_url,
+                    auth_token,
+                }
+            }
+            scheme @ ("grpc" | "grpcs") => {
+                let (endpoint, token) = split_token(uri).map_err(invalid)?;
+                if token.is_some() {
+                    return Err(invalid("the gRPC transport does not take a token".into()));
+                }
+                let tls = scheme == "grpcs";
+                TransportType::Grpc {
+                    endpoint: format!(
+                        "{}{}",
+                        if tls { "https" } else { "http" },
+                        &endpoint[scheme.len()..]
+                    ),
+                    tls,
+                }
+            }
+            "ws" | "wss" => return Err(invalid("no WebSocket transport is available".into())),
+            other => return Err(invalid(format!("unknown scheme {}", other))),
+        };
+
+        Ok(Self {
+            transport_type,
+            parameters: None,
+        })
+    }
+
+    fn from_lookup(lookup: impl Fn(&str) -> std::result::Result<String, VarError>) -> Result<Self> {
+        let var = |key: &str| match lookup(key) {
+            Ok(value) => Ok(Some(value)),
+            Err(VarError::NotPresent) => Ok(None),
+            Err(VarError::NotUnicode(_)) => Err(crate::Error::Configuration(format!(
+                "{} is not valid unicode",
+                key
+            ))),
+        };
+
+        let transport_type = match var("MCP_TRANSPORT")?.as_deref() {
+            None | Some("stdio") => TransportType::Stdio {
+                server_path: var("MCP_SERVER_PATH")?,
+                server_args: var("MCP_SERVER_ARGS")?
+                    .map(|args| args.split_whitespace().map(String::from).collect()),
+            },
+            Some("http") => TransportType::Http {
+                base_url: var("MCP_BASE_URL")?.ok_or_else(|| {
+                    crate::Error::Configuration(
+                        "MCP_BASE_URL is required for the http transport".into(),
+                    )
+                })?,
+                auth_token: var("MCP_AUTH_TOKEN")?,
+            },
+            Some(other) => {
+                return Err(crate::Error::Configuration(format!(
+                    "Unknown transport type: {}",
+                    other
+                )))
+            }
+        };
+
+        Ok(Self {
+            transport_type,
+            parameters: None,
+        })
+    }
+}
+
+/// `uri` without its query, and the `token` query parameter if present
+///
+/// The base URL has paths appended to it, so any other query parameter is an error.
+fn split_token(uri: &str) -> std::result::Result<(String, Option<String>), String> {
+    let url = reqwest::Url::parse(uri).map_err(|e| e.to_string())?;
+    let mut token = None;
+    for (key, value) in url.query_pairs() {
+        match key.as_ref() {
+            "token" => token = Some(value.into_owned()),
+            other => return Err(format!("unknown query parameter {}", other)),
+        }
+    }
+    let base = uri.split(['?', '#']).next().unwrap_or_default();
+    Ok((base.trim_end_matches('/').to_string(), token))
+}
+
 /// Transport type
 #[derive(Debug, Clone)]
 pub enum TransportType {
@@ -39,19 +190,217 @@ pub enum TransportType {
         /// Optional authentication token
         auth_token: Option<String>,
     },
+    /// gRPC transport, available with the `grpc` feature
+    Grpc {
+        /// Server endpoint, e.g. `http://127.0.0.1:50051`
+        endpoint: String,
+        /// Whether to connect over TLS (only supported by clients)
+        tls: bool,
+    },
 }
 
+/// Exponential backoff with full jitter
+///
+/// Each delay is drawn uniformly from zero up to a ceiling that starts at `base` and
+/// is multiplied by `multiplier` after every attempt, capped at `max`. Randomizing
+/// the whole interval keeps many clients retrying against the same server from
+/// doing so in lockstep.
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    /// Ceiling of the first delay
+    pub base: Duration,
+    /// Upper bound for every delay
+    pub max: Duration,
+    /// Factor applied to the ceiling after each attempt
+    pub multiplier: f64,
+    attempt: u32,
+}
+
+impl Backoff {
+    /// Create a backoff policy
+    pub fn new(base: Duration, max: Duration, multiplier: f64) -> Self {
+        Self {
+            base,
+            max,
+            multiplier,
+            attempt: 0,
+        }
+    }
+
+    /// Ceiling of the next delay
+    pub fn ceiling(&self) -> Duration {
+        let factor = self.multiplier.max(1.0).powi(self.attempt as i32);
+        self.base.mul_f64(factor).min(self.max)
+    }
+
+    /// Draw the next delay and advance to the following attempt
+    pub fn next_delay(&mut self) -> Duration {
+        let delay = self.ceiling().mul_f64(fastrand::f64());
+        self.attempt = self.attempt.saturating_add(1);
+        delay
+    }
+
+    /// Start over from the first attempt
+    pub fn reset(&mut self) {
+        self.attempt = 0;
+    }
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(50), Duration::from_secs(2), 2.0)
+    }
+}
+
+/// Decode a complete message frame as UTF-8
+///
+/// Frames are only decoded once all of their bytes have arrived, so multi-byte
+/// characters split across reads are reassembled first. Invalid sequences are an
+/// error unless `lossy` is set, in which case they become U+FFFD.
+pub(crate) fn decode_utf8(bytes: Vec<u8>, lossy: bool) -> Result<String> {
+    match String::from_utf8(bytes) {
+        Ok(text) => Ok(text),
+        Err(e) if lossy => Ok(String::from_utf8_lossy(e.as_bytes()).into_owned()),
+        Err(e) => Err(crate::Error::Protocol(format!(
+            "Invalid UTF-8 in message: {}",
+            e.utf8_error()
+        ))),
+    }
+}
+
+/// Where a received message came from
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MessageOrigin {
+    /// Client that sent it, on transports serving several clients, such as the HTTP
+    /// server
+    pub client_id: Option<u64>,
+    /// Identity the transport established for the sender
+    pub auth: Option<AuthContext>,
+}
+
+/// Chooses the clients a notification sent with `Transport::send_filtered` reaches
+///
+/// Called with the ID of each candidate client, or with `None` for the peer of a
+/// transport that serves a single client.
+pub type ClientFilter = Arc<dyn Fn(Option<u64>) -> bool + Send + Sync>;
+
 /// Base trait for transport layers
+///
+/// Sending, receiving and closing report transport failures that callers must
+/// handle, so ignoring their result warns that "transport errors must be handled"
+/// (see `tests/ui/unhandled_send.rs`).
 #[async_trait]
 pub trait Transport: Send + Sync {
     /// Initialize the transport
+    ///
+    /// Transports that connect here fail `send` and `receive` with
+    /// `Error::NotInitialized` until this has succeeded. Transports that are
+    /// connected when constructed, such as `MemoryTransport`, can be used right away.
     async fn initialize(&mut self) -> Result<()>;
     /// Send a message
+    #[must_use = "transport errors must be handled"]
     async fn send(&self, message: Message) -> Result<()>;
+    /// Send a pre-serialized JSON message without re-encoding it
+    ///
+    /// The default implementation parses the message and calls `send`; transports
+    /// that write bytes directly override it to skip the round trip.
+    #[must_use = "transport errors must be handled"]
+    async fn send_raw(&self, json: &str) -> Result<()> {
+        self.send(serde_json::from_str(json)?).await
+    }
+    /// Send a notification to the clients `accept` selects
+    ///
+    /// Transports serving several clients, such as the HTTP server, send it to each
+    /// client whose ID `accept` returns `true` for. The default sends it to the
+    /// single peer if `accept(None)` holds.
+    #[must_use = "transport errors must be handled"]
+    async fn send_filtered(&self, notification: Notification, accept: ClientFilter) -> Result<()> {
+        match accept(None) {
+            true => self.send(Message::Notification(notification)).await,
+            false => Ok(()),
+        }
+    }
     /// Receive a message
+    #[must_use = "transport errors must be handled"]
     async fn receive(&self) -> Result<Message>;
+    /// Receive a message together with where it came from
+    ///
+    /// Transports serving several clients, such as the HTTP server, tell which client
+    /// sent it and the identity they established for that client. The default
+    /// receives a message of unknown origin.
+    #[must_use = "transport errors must be handled"]
+    async fn receive_with_origin(&self) -> Result<(Message, MessageOrigin)> {
+        Ok((self.receive().await?, MessageOrigin::default()))
+    }
     /// Close the transport
+    #[must_use = "transport errors must be handled"]
     async fn close(&mut self) -> Result<()>;
+    /// Receive up to `max` messages, waiting at most `within` in total
+    ///
+    /// Returns whatever arrived before the deadline, possibly nothing. A receive
+    /// error ends the collection; it is returned only if no message was collected,
+    /// so messages already received are never dropped. The pending `receive` is
+    /// cancelled at the deadline, so transports whose `receive` is not cancel-safe
+    /// should override this.
+    #[must_use = "transport errors must be handled"]
+    async fn receive_many(&self, max: usize, within: Duration) -> Result<Vec<Message>> {
+        let deadline = tokio::time::Instant::now() + within;
+        let mut messages = Vec::new();
+        while messages.len() < max {
+            match tokio::time::timeout_at(deadline, self.receive()).await {
+                Ok(Ok(message)) => messages.push(message),
+                Ok(Err(e)) if messages.is_empty() => return Err(e),
+                Ok(Err(_)) | Err(_) => break,
+            }
+        }
+        Ok(messages)
+    }
+    /// Report the transport's health without sending anything
+    ///
+    /// The default reports `HealthStatus::Unknown`; wrappers forward the report of
+    /// the transport they wrap.
+    async fn health(&self) -> TransportHealth {
+        TransportHealth::unknown()
+    }
+}
+
+#[async_trait]
+impl<T: Transport + ?Sized> Transport for Box<T> {
+    async fn initialize(&mut self) -> Result<()> {
+        (**self).initialize().await
+    }
+
+    async fn send(&self, message: Message) -> Result<()> {
+        (**self).send(message).await
+    }
+
+    async fn send_raw(&self, json: &str) -> Result<()> {
+        (**self).send_raw(json).await
+    }
+
+    async fn send_filtered(&self, notification: Notification, accept: ClientFilter) -> Result<()> {
+        (**self).send_filtered(notification, accept).await
+    }
+
+    async fn receive(&self) -> Result<Message> {
+        (**self).receive().await
+    }
+
+    async fn receive_with_origin(&self) -> Result<(Message, MessageOrigin)> {
+        (**self).receive_with_origin().await
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        (**self).close().await
+    }
+
+    async fn receive_many(&self, max: usize, within: Duration) -> Result<Vec<Message>> {
+        (**self).receive_many(max, within).await
+    }
+
+    async fn health(&self) -> TransportHealth {
+        (**self).health().await
+    }
 }
 
 /// Client transport factory
@@ -74,7 +423,7 @@ impl ClientTransportFactory {
                     ..Default::default()
                 };
                 let client = StdioClient::new(config);
-                Ok(Box::new(StdioClientTransport(client)))
+                Ok(client.into_transport())
             }
             TransportType::Http {
                 base_url,
@@ -84,14 +433,36 @@ impl ClientTransportFactory {
                 let config = HttpClientConfig {
                     base_url,
                     auth_token,
+                    ..Default::default()
                 };
                 let client = HttpClient::new(config)?;
-                Ok(Box::new(HttpClientTransport(client)))
+                Ok(client.into_transport())
             }
+            #[cfg(feature = "grpc")]
+            TransportType::Grpc { endpoint, tls } => {
+                Ok(Box::new(grpc::GrpcClientTransport::new(endpoint, tls)))
+            }
+            #[cfg(not(feature = "grpc"))]
+            TransportType::Grpc { .. } => Err(grpc_disabled()),
         }
     }
 }
 
+/// Parse the address a server should listen on
+///
+/// Accepts a bare `host:port` as well as a full URL such as
+/// `http://127.0.0.1:3000/`, so servers and clients can share one setting.
+/// The scheme and any path are ignored.
+pub fn parse_bind_addr(base_url: &str) -> Result<std::net::SocketAddr> {
+    let authority = base_url
+        .split_once("://")
+        .map_or(base_url, |(_, rest)| rest);
+    let authority = authority.split('/').next().unwrap_or_default();
+    authority
+        .parse()
+        .map_err(|e| crate::Error::Transport(format!("Invalid address {}: {}", base_url, e)))
+}
+
 /// Server transport factory
 pub struct ServerTransportFactory;
 
@@ -102,24 +473,43 @@ impl ServerTransportFactory {
             TransportType::Stdio { .. } => {
                 use stdio::server::{StdioServer, StdioServerConfig};
                 let server = StdioServer::new(StdioServerConfig::default());
-                Ok(Box::new(StdioServerTransport(server)))
+                Ok(server.into_transport())
             }
             TransportType::Http {
                 base_url,
                 auth_token,
             } => {
                 use http::server::{AxumHttpServer, HttpServerConfig};
-                let addr = base_url
-                    .parse()
-                    .map_err(|e| crate::Error::Transport(format!("Invalid address: {}", e)))?;
-                let config = HttpServerConfig { addr, auth_token };
+                let addr = parse_bind_addr(&base_url)?;
+                let config = HttpServerConfig {
+                    addr,
+                    auth_token,
+                    ..Default::default()
+                };
                 let server = AxumHttpServer::new(config);
-                Ok(Box::new(HttpServerTransport(server)))
+                Ok(server.into_transport())
+            }
+            #[cfg(feature = "grpc")]
+            TransportType::Grpc { endpoint, tls } => {
+                if tls {
+                    return Err(crate::Error::Configuration(
+                        "The gRPC server does not terminate TLS; put a proxy in front of it".into(),
+                    ));
+                }
+                let addr = parse_bind_addr(&endpoint)?;
+                Ok(Box::new(grpc::GrpcServerTransport::new(addr)))
             }
+            #[cfg(not(feature = "grpc"))]
+            TransportType::Grpc { .. } => Err(grpc_disabled()),
         }
     }
 }
 
+#[cfg(not(feature = "grpc"))]
+fn grpc_disabled() -> crate::Error {
+    crate::Error::Configuration("The gRPC transport requires the grpc feature".into())
+}
+
 // Wrapper types for implementing Transport trait
 struct StdioClientTransport(stdio::client::StdioClient);
 struct StdioServerTransport(stdio::server::StdioServer);
@@ -128,7 +518,14 @@ struct HttpServerTransport(http::server::AxumHttpServer);
 
 // Implement Transport trait for wrapper types
 macro_rules! impl_transport {
-    ($wrapper:ident, $inner:ident) => {
+    ($wrapper:ident, $inner:ty $(, $extra:item)*) => {
+        impl $inner {
+            /// Box this transport as a `dyn Transport`
+            pub fn into_transport(self) -> Box<dyn Transport> {
+                Box::new($wrapper(self))
+            }
+        }
+
         #[async_trait]
         impl Transport for $wrapper {
             async fn initialize(&mut self) -> Result<()> {
@@ -139,6 +536,10 @@ macro_rules! impl_transport {
                 self.0.send(message).await
             }
 
+            async fn send_raw(&self, json: &str) -> Result<()> {
+                self.0.send_raw(json).await
+            }
+
             async fn receive(&self) -> Result<Message> {
                 self.0.receive().await
             }
@@ -146,11 +547,232 @@ macro_rules! impl_transport {
             async fn close(&mut self) -> Result<()> {
                 self.0.close().await
             }
+
+            $($extra)*
         }
     };
 }
 
-impl_transport!(StdioClientTransport, StdioClient);
-impl_transport!(StdioServerTransport, StdioServer);
-impl_transport!(HttpClientTransport, HttpClient);
-impl_transport!(HttpServerTransport, AxumHttpServer);
+impl_transport!(StdioClientTransport, stdio::client::StdioClient);
+impl_transport!(StdioServerTransport, stdio::server::StdioServer);
+impl_transport!(HttpClientTransport, http::client::HttpClient);
+impl_transport!(
+    HttpServerTransport,
+    http::server::AxumHttpServer,
+    async fn send_filtered(&self, notification: Notification, accept: ClientFilter) -> Result<()> {
+        self.0.send_filtered(notification, accept).await
+    },
+    async fn receive_with_origin(&self) -> Result<(Message, MessageOrigin)> {
+        self.0.receive_with_origin().await
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn config_from(vars: &[(&str, &str)]) -> Result<TransportConfig> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        TransportConfig::from_lookup(|key| vars.get(key).cloned().ok_or(VarError::NotPresent))
+    }
+
+    #[test]
+    fn test_backoff_grows_within_jittered_bounds() {
+        let mut backoff = Backoff::new(Duration::from_millis(100), Duration::from_secs(1), 2.0);
+        let expected = [100, 200, 400, 800, 1000, 1000];
+
+        for ceiling in expected {
+            let ceiling = Duration::from_millis(ceiling);
+            assert_eq!(backoff.ceiling(), ceiling);
+            assert!(backoff.next_delay() <= ceiling);
+        }
+
+        backoff.reset();
+        assert_eq!(backoff.ceiling(), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_backoff_delays_are_jittered() {
+        let mut delays = std::collections::HashSet::new();
+        for _ in 0..20 {
+            let mut backoff = Backoff::default();
+            delays.insert(backoff.next_delay());
+        }
+        assert!(delays.len() > 1);
+    }
+
+    #[test]
+    fn test_decode_utf8_strict_and_lossy() {
+        assert_eq!(
+            decode_utf8("héllo".as_bytes().to_vec(), false).unwrap(),
+            "héllo"
+        );
+
+        let invalid = vec![b'a', 0xff, b'b'];
+        assert!(matches!(
+            decode_utf8(invalid.clone(), false),
+            Err(crate::Error::Protocol(_))
+        ));
+        assert_eq!(decode_utf8(invalid, true).unwrap(), "a\u{fffd}b");
+    }
+
+    #[test]
+    fn test_parse_bind_addr_accepts_bare_and_url_forms() {
+        let expected: std::net::SocketAddr = "127.0.0.1:3000".parse().unwrap();
+        for base_url in [
+            "127.0.0.1:3000",
+            "http://127.0.0.1:3000",
+            "https://127.0.0.1:3000/",
+            "http://127.0.0.1:3000/mcp",
+        ] {
+            assert_eq!(parse_bind_addr(base_url).unwrap(), expected, "{}", base_url);
+        }
+        assert_eq!(
+            parse_bind_addr("http://[::1]:8080").unwrap(),
+            "[::1]:8080".parse::<std::net::SocketAddr>().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_bind_addr_rejects_invalid_addresses() {
+        for base_url in ["", "http://", "localhost", "http://127.0.0.1"] {
+            assert!(matches!(
+                parse_bind_addr(base_url),
+                Err(crate::Error::Transport(message)) if message.contains("Invalid address")
+            ));
+        }
+    }
+
+    #[test]
+    fn test_server_factory_accepts_url_with_scheme() {
+        let config = TransportConfig {
+            transport_type: TransportType::Http {
+                base_url: "http://127.0.0.1:3000".to_string(),
+                auth_token: None,
+            },
+            parameters: None,
+        };
+        assert!(ServerTransportFactory.create(config).is_ok());
+    }
+
+    #[test]
+    fn test_from_env_defaults_to_stdio() {
+        let config = config_from(&[("MCP_SERVER_ARGS", "--verbose  run")]).unwrap();
+        match config.transport_type {
+            TransportType::Stdio {
+                server_path,
+                server_args,
+            } => {
+                assert!(server_path.is_none());
+                assert_eq!(server_args.unwrap(), vec!["--verbose", "run"]);
+            }
+            other => panic!("unexpected transport: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_from_env_http() {
+        let config = config_from(&[
+            ("MCP_TRANSPORT", "http"),
+            ("MCP_BASE_URL", "http://localhost:3000"),
+        ])
+        .unwrap();
+        assert!(matches!(
+            config.transport_type,
+            TransportType::Http { base_url, auth_token: None } if base_url == "http://localhost:3000"
+        ));
+    }
+
+    #[test]
+    fn test_from_uri_stdio() {
+        let config = TransportConfig::from_uri("stdio:///usr/bin/server --verbose run").unwrap();
+        match config.transport_type {
+            TransportType::Stdio {
+                server_path,
+                server_args,
+            } => {
+                assert_eq!(server_path.as_deref(), Some("/usr/bin/server"));
+                assert_eq!(server_args.unwrap(), vec!["--verbose", "run"]);
+            }
+            other => panic!("unexpected transport: {:?}", other),
+        }
+
+        let config = TransportConfig::from_uri("stdio://").unwrap();
+        assert!(matches!(
+            config.transport_type,
+            TransportType::Stdio {
+                server_path: None,
+                server_args: None
+            }
+        ));
+    }
+
+    #[test]
+    fn test_from_uri_http() {
+        let config = TransportConfig::from_uri("http://localhost:3000").unwrap();
+        assert!(matches!(
+            config.transport_type,
+            TransportType::Http { base_url, auth_token: None } if base_url == "http://localhost:3000"
+        ));
+
+        let config =
+            TransportConfig::from_uri("https://mcp.example.com/api/?token=s3cr%2Bt").unwrap();
+        assert!(matches!(
+            config.transport_type,
+            TransportType::Http { base_url, auth_token: Some(token) }
+                if base_url == "https://mcp.example.com/api" && token == "s3cr+t"
+        ));
+    }
+
+    #[test]
+    fn test_from_uri_grpc() {
+        let config = TransportConfig::from_uri("grpc://127.0.0.1:50051").unwrap();
+        assert!(matches!(
+            config.transport_type,
+            TransportType::Grpc { endpoint, tls: false } if endpoint == "http://127.0.0.1:50051"
+        ));
+
+        let config = TransportConfig::from_uri("grpcs://mcp.example.com").unwrap();
+        assert!(matches!(
+            config.transport_type,
+            TransportType::Grpc { endpoint, tls: true } if endpoint == "https://mcp.example.com"
+        ));
+    }
+
+    #[test]
+    fn test_from_uri_errors() {
+        for uri in [
+            "localhost:3000",
+            "ws://localhost:3000",
+            "ftp://localhost",
+            "http://",
+            "http://localhost:3000?verbose=1",
+            "grpc://localhost:50051?token=secret",
+        ] {
+            assert!(
+                matches!(
+                    TransportConfig::from_uri(uri),
+                    Err(crate::Error::Configuration(_))
+                ),
+                "{} should be rejected",
+                uri
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_env_configuration_errors() {
+        assert!(matches!(
+            config_from(&[("MCP_TRANSPORT", "http")]),
+            Err(crate::Error::Configuration(_))
+        ));
+        assert!(matches!(
+            config_from(&[("MCP_TRANSPORT", "carrier-pigeon")]),
+            Err(crate::Error::Configuration(_))
+        ));
+    }
+}
@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+use super::{middleware::MiddlewareTransport, Transport, TransportMiddleware};
+use crate::Result;
+
+/// Forward messages between two transports until either side closes
+///
+/// Both transports are initialized first. When receiving from one side fails
+/// (typically because its peer disconnected) or forwarding to the other side fails,
+/// both transports are closed.
+pub async fn proxy(a: Box<dyn Transport>, b: Box<dyn Transport>) -> Result<()> {
+    run(a, b).await
+}
+
+/// Like `proxy`, running `middleware` on every forwarded message
+///
+/// Messages from `a` to `b` pass through `on_receive`, messages from `b` to `a`
+/// through `on_send`, so each forwarded message is seen exactly once.
+pub async fn proxy_with_middleware(
+    a: Box<dyn Transport>,
+    b: Box<dyn Transport>,
+    middleware: Arc<dyn TransportMiddleware>,
+) -> Result<()> {
+    let a = MiddlewareTransport::new(a).with(middleware);
+    run(Box::new(a), b).await
+}
+
+async fn run(mut a: Box<dyn Transport>, mut b: Box<dyn Transport>) -> Result<()> {
+    a.initialize().await?;
+    b.initialize().await?;
+
+    tokio::select! {
+        reason = pump(&*a, &*b) => tracing::debug!("proxy a -> b stopped: {}", reason),
+        reason = pump(&*b, &*a) => tracing::debug!("proxy b -> a stopped: {}", reason),
+    }
+
+    let closed_a = a.close().await;
+    let closed_b = b.close().await;
+    closed_a.and(closed_b)
+}
+
+/// Forward messages from `from` to `to`, returning why forwarding stopped
+async fn pump(from: &dyn Transport, to: &dyn Transport) -> crate::Error {
+    loop {
+        let message = match from.receive().await {
+            Ok(message) => message,
+            Err(e) => return e,
+        };
+        if let Err(e) = to.send(message).await {
+            return e;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{Message, Method, Request, RequestId, Response};
+    use crate::transport::{LoggingMiddleware, MemoryTransport};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_request_and_response_cross_the_proxy() {
+        let (mut client, a) = MemoryTransport::pair();
+        let (b, server) = MemoryTransport::pair();
+        let proxying = tokio::spawn(proxy_with_middleware(
+            Box::new(a),
+            Box::new(b),
+            Arc::new(LoggingMiddleware::with_label("proxy")),
+        ));
+
+        let request = Request::new(Method::Ping, None, RequestId::Number(1));
+        client.send(Message::Request(request)).await.unwrap();
+        match server.receive().await.unwrap() {
+            Message::Request(request) => {
+                assert_eq!(request.method, "ping");
+                server
+                    .send(Message::Response(Response::success(json!({}), request.id)))
+                    .await
+                    .unwrap();
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+
+        match client.receive().await.unwrap() {
+            Message::Response(response) => assert_eq!(response.id, RequestId::Number(1)),
+            other => panic!("unexpected message: {:?}", other),
+        }
+
+        // Closing one side shuts the proxy down and closes the other side
+        client.close().await.unwrap();
+        proxying.await.unwrap().unwrap();
+        assert!(server.receive().await.is_err());
+    }
+}
@@ -1,6 +1,8 @@
+use super::signing::HmacConfig;
+use super::{read_line_limited, LineRead, DEFAULT_MAX_MESSAGE_BYTES};
 use crate::{protocol::Message, Result};
 use async_trait::async_trait;
-use std::{path::PathBuf, process::Stdio};
+use std::{collections::HashMap, path::PathBuf, process::Stdio, time::Duration};
 use tokio::{
     io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
     process::{Child, Command},
@@ -17,6 +19,22 @@ pub struct StdioClientConfig {
     pub buffer_size: usize,
     /// Whether to capture server logs
     pub capture_logs: bool,
+    /// Sign every outgoing line with an HMAC
+    pub signing: Option<HmacConfig>,
+    /// Replace invalid UTF-8 in server output with U+FFFD instead of failing
+    pub lossy_utf8: bool,
+    /// Largest accepted message; longer ones are skipped with an error
+    pub max_message_bytes: usize,
+    /// How long `close` waits for the server to exit before killing it
+    pub shutdown_timeout: Duration,
+    /// Directory to start the server in; defaults to the current directory
+    pub working_dir: Option<PathBuf>,
+    /// Extra environment variables for the server, on top of the inherited ones
+    pub env_vars: HashMap<String, String>,
+    /// Skip stdout lines that are not JSON-RPC messages instead of failing `receive`
+    ///
+    /// Skipped lines are treated as server logs.
+    pub skip_non_json_stdout: bool,
 }
 
 impl Default for StdioClientConfig {
@@ -26,16 +44,35 @@ impl Default for StdioClientConfig {
             server_args: vec![],
             buffer_size: 4096,
             capture_logs: true,
+            signing: None,
+            lossy_utf8: false,
+            max_message_bytes: DEFAULT_MAX_MESSAGE_BYTES,
+            shutdown_timeout: Duration::from_secs(5),
+            working_dir: None,
+            env_vars: HashMap::new(),
+            skip_non_json_stdout: false,
         }
     }
 }
 
+/// Server stdout, with the state of the line being read
+///
+/// The line state lives here rather than in `receive` so a cancelled `receive`
+/// resumes the frame it was reading instead of dropping it.
+struct Inbound {
+    reader: BufReader<tokio::process::ChildStdout>,
+    /// Bytes read so far that do not yet form a complete line
+    pending: Vec<u8>,
+    /// Whether the rest of an oversized line is being skipped
+    discarding: bool,
+}
+
 /// Stdio client implementation
 pub struct StdioClient {
     config: StdioClientConfig,
     child: Mutex<Option<Child>>,
     stdin: Mutex<Option<tokio::process::ChildStdin>>,
-    stdout: Mutex<Option<BufReader<tokio::process::ChildStdout>>>,
+    stdout: Mutex<Option<Inbound>>,
     stderr: Mutex<Option<BufReader<tokio::process::ChildStderr>>>,
 }
 
@@ -52,7 +89,7 @@ impl StdioClient {
     }
 
     /// Start log capture
-    async fn start_log_capture(&self, mut stderr: tokio::process::ChildStderr) {
+    async fn start_log_capture(&self, stderr: tokio::process::ChildStderr) {
         tokio::spawn(async move {
             let mut reader = BufReader::new(stderr);
             let mut line = String::new();
@@ -60,19 +97,29 @@ impl StdioClient {
                 if n == 0 {
                     break;
                 }
-                // Here you can handle logs as needed, such as forwarding to a specific logging system
-                eprintln!("[MCP Server] {}", line.trim());
+                log_server_line(&line);
                 line.clear();
             }
         });
     }
 }
 
+/// Forward a line of server output to the client's log
+fn log_server_line(line: &str) {
+    // Here you can handle logs as needed, such as forwarding to a specific logging system
+    eprintln!("[MCP Server] {}", line.trim());
+}
+
 #[async_trait]
 impl super::StdioTransport for StdioClient {
     async fn initialize(&mut self) -> Result<()> {
-        let mut child = Command::new(&self.config.server_path)
+        let mut command = Command::new(&self.config.server_path);
+        if let Some(dir) = &self.config.working_dir {
+            command.current_dir(dir);
+        }
+        let mut child = command
             .args(&self.config.server_args)
+            .envs(&self.config.env_vars)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(if self.config.capture_logs {
@@ -99,26 +146,34 @@ impl super::StdioTransport for StdioClient {
         }
 
         *self.stdin.lock().await = Some(stdin);
-        *self.stdout.lock().await = Some(BufReader::new(stdout));
+        *self.stdout.lock().await = Some(Inbound {
+            reader: BufReader::new(stdout),
+            pending: Vec::with_capacity(self.config.buffer_size),
+            discarding: false,
+        });
         *self.child.lock().await = Some(child);
 
         Ok(())
     }
 
     async fn send(&self, message: Message) -> Result<()> {
-        let mut stdin = self.stdin.lock().await;
-        let stdin = stdin
-            .as_mut()
-            .ok_or_else(|| crate::Error::Transport("Server process not initialized".into()))?;
+        self.send_raw(&serde_json::to_string(&message)?).await
+    }
 
-        let json = serde_json::to_string(&message)?;
+    async fn send_raw(&self, json: &str) -> Result<()> {
         if json.contains('\n') {
             return Err(crate::Error::Transport(
                 "Message contains embedded newlines".into(),
             ));
         }
 
-        stdin.write_all(json.as_bytes()).await?;
+        let mut stdin = self.stdin.lock().await;
+        let stdin = stdin.as_mut().ok_or(crate::Error::NotInitialized)?;
+
+        match &self.config.signing {
+            Some(signing) => stdin.write_all(signing.sign_line(json).as_bytes()).await?,
+            None => stdin.write_all(json.as_bytes()).await?,
+        }
         stdin.write_all(b"\n").await?;
         stdin.flush().await?;
         Ok(())
@@ -126,30 +181,56 @@ impl super::StdioTransport for StdioClient {
 
     async fn receive(&self) -> Result<Message> {
         let mut stdout = self.stdout.lock().await;
-        let stdout = stdout
-            .as_mut()
-            .ok_or_else(|| crate::Error::Transport("Server process not initialized".into()))?;
+        let stdout = stdout.as_mut().ok_or(crate::Error::NotInitialized)?;
 
-        let mut line = String::with_capacity(self.config.buffer_size);
-        stdout.read_line(&mut line).await?;
+        loop {
+            let Inbound {
+                reader,
+                pending,
+                discarding,
+            } = &mut *stdout;
+            let read =
+                read_line_limited(reader, pending, self.config.max_message_bytes, discarding)
+                    .await?;
+            let bytes = std::mem::replace(pending, Vec::with_capacity(self.config.buffer_size));
 
-        if line.is_empty() {
-            return Err(crate::Error::Transport("Server process terminated".into()));
-        }
+            if read == LineRead::TooLarge {
+                return Err(crate::Error::Protocol("message too large".into()));
+            }
+            if bytes.is_empty() {
+                return Err(crate::Error::Transport("Server process terminated".into()));
+            }
+
+            let line = crate::transport::decode_utf8(bytes, self.config.lossy_utf8)?;
 
-        let message = serde_json::from_str(&line)?;
-        Ok(message)
+            match Message::try_from(line.as_str()) {
+                Ok(message) => return Ok(message),
+                Err(e) if self.config.skip_non_json_stdout => {
+                    tracing::warn!("Skipping non-JSON line on server stdout: {}", e);
+                    if self.config.capture_logs {
+                        log_server_line(&line);
+                    }
+                }
+                Err(e) => return Err(e),
+            }
+        }
     }
 
     async fn close(&mut self) -> Result<()> {
         let mut child = self.child.lock().await;
         if let Some(mut child) = child.take() {
-            // First close stdin to let the server know there will be no more input
-            drop(self.stdin.lock().await.take());
+            // Flush pending writes, then close stdin to let the server know
+            // there will be no more input
+            if let Some(mut stdin) = self.stdin.lock().await.take() {
+                if let Err(e) = stdin.flush().await {
+                    tracing::warn!("Failed to flush server stdin: {}", e);
+                }
+            }
 
-            // Wait for the server process to end
-            match child.wait().await {
-                Ok(status) => {
+            // Wait for the server process to end, killing it if it takes too long
+            let waited = tokio::time::timeout(self.config.shutdown_timeout, child.wait()).await;
+            match waited {
+                Ok(Ok(status)) => {
                     if !status.success() {
                         return Err(crate::Error::Transport(format!(
                             "Server process exited with status: {}",
@@ -157,12 +238,20 @@ impl super::StdioTransport for StdioClient {
                         )));
                     }
                 }
-                Err(e) => {
+                Ok(Err(e)) => {
                     return Err(crate::Error::Transport(format!(
                         "Failed to wait for server process: {}",
                         e
                     )));
                 }
+                Err(_) => {
+                    tracing::warn!(
+                        "Server process did not exit within {:?}, killing it",
+                        self.config.shutdown_timeout
+                    );
+                    child.start_kill()?;
+                    child.wait().await?;
+                }
             }
         }
 
@@ -174,3 +263,131 @@ impl super::StdioTransport for StdioClient {
 
 /// Default Stdio client type
 pub type DefaultStdioClient = StdioClient;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::RequestId;
+    use crate::transport::stdio::StdioTransport;
+    use std::time::Instant;
+
+    #[tokio::test]
+    async fn test_server_runs_in_working_dir_with_env_vars() {
+        let dir = std::env::temp_dir().join(format!("mcp-workdir-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("reply.json"),
+            "{\"jsonrpc\":\"2.0\",\"method\":\"from-file\"}\n",
+        )
+        .unwrap();
+
+        let mut client = StdioClient::new(StdioClientConfig {
+            server_path: PathBuf::from("sh"),
+            server_args: vec![
+                "-c".to_string(),
+                r#"cat reply.json; printf '{"jsonrpc":"2.0","method":"%s"}\n' "$MCP_METHOD""#
+                    .to_string(),
+            ],
+            capture_logs: false,
+            working_dir: Some(dir.clone()),
+            env_vars: HashMap::from([("MCP_METHOD".to_string(), "from-env".to_string())]),
+            ..Default::default()
+        });
+        client.initialize().await.unwrap();
+
+        for expected in ["from-file", "from-env"] {
+            match client.receive().await.unwrap() {
+                Message::Notification(n) => assert_eq!(n.method, expected),
+                other => panic!("unexpected message: {:?}", other),
+            }
+        }
+        client.close().await.unwrap();
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_non_json_stdout_lines_are_skipped() {
+        let script = r#"echo 'server starting up'; echo '{"jsonrpc":"2.0","id":1,"result":{}}'"#;
+        let config = || StdioClientConfig {
+            server_path: PathBuf::from("sh"),
+            server_args: vec!["-c".to_string(), script.to_string()],
+            capture_logs: false,
+            ..Default::default()
+        };
+
+        let mut strict = StdioClient::new(config());
+        strict.initialize().await.unwrap();
+        assert!(matches!(
+            strict.receive().await,
+            Err(crate::Error::Serialization(_))
+        ));
+        strict.close().await.unwrap();
+
+        let mut lenient = StdioClient::new(StdioClientConfig {
+            skip_non_json_stdout: true,
+            ..config()
+        });
+        lenient.initialize().await.unwrap();
+        match lenient.receive().await.unwrap() {
+            Message::Response(response) => assert_eq!(response.id, RequestId::Number(1)),
+            other => panic!("unexpected message: {:?}", other),
+        }
+        lenient.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_receive_resumes_frame_after_receive_many_timed_out() {
+        let script = r#"printf '{"jsonrpc":"2.0",'; sleep 1; printf '"method":"split"}\n'"#;
+        let mut client = StdioClient::new(StdioClientConfig {
+            server_path: PathBuf::from("sh"),
+            server_args: vec!["-c".to_string(), script.to_string()],
+            capture_logs: false,
+            ..Default::default()
+        })
+        .into_transport();
+        client.initialize().await.unwrap();
+
+        // The deadline passes in the middle of the frame
+        let received = client
+            .receive_many(1, Duration::from_millis(200))
+            .await
+            .unwrap();
+        assert!(received.is_empty());
+
+        match client.receive().await.unwrap() {
+            Message::Notification(n) => assert_eq!(n.method, "split"),
+            other => panic!("unexpected message: {:?}", other),
+        }
+        client.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_use_before_initialize_fails() {
+        let client = StdioClient::new(StdioClientConfig::default());
+        assert!(matches!(
+            client.send_raw("{}").await,
+            Err(crate::Error::NotInitialized)
+        ));
+        assert!(matches!(
+            client.receive().await,
+            Err(crate::Error::NotInitialized)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_close_kills_server_after_shutdown_timeout() {
+        let mut client = StdioClient::new(StdioClientConfig {
+            server_path: PathBuf::from("sleep"),
+            server_args: vec!["600".to_string()],
+            capture_logs: false,
+            shutdown_timeout: Duration::from_millis(200),
+            ..Default::default()
+        });
+        client.initialize().await.unwrap();
+
+        let started = Instant::now();
+        client.close().await.unwrap();
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(client.child.lock().await.is_none());
+    }
+}
@@ -1,8 +1,10 @@
 use crate::{protocol::Message, Result};
 use async_trait::async_trait;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt};
 
 pub mod client;
 pub mod server;
+pub mod signing;
 
 /// Stdio transport trait
 #[async_trait]
@@ -10,13 +12,109 @@ pub trait StdioTransport: Send + Sync {
     /// Initialize the transport
     async fn initialize(&mut self) -> Result<()>;
     /// Send a message
+    #[must_use = "transport errors must be handled"]
     async fn send(&self, message: Message) -> Result<()>;
+    /// Send a pre-serialized JSON message
+    ///
+    /// The default implementation parses the message and calls `send`.
+    #[must_use = "transport errors must be handled"]
+    async fn send_raw(&self, json: &str) -> Result<()> {
+        self.send(serde_json::from_str(json)?).await
+    }
     /// Receive a message
+    #[must_use = "transport errors must be handled"]
     async fn receive(&self) -> Result<Message>;
     /// Close the connection
+    #[must_use = "transport errors must be handled"]
     async fn close(&mut self) -> Result<()>;
 }
 
+/// Default limit on the size of a single incoming message
+pub const DEFAULT_MAX_MESSAGE_BYTES: usize = 4 * 1024 * 1024;
+
+/// Outcome of `read_line_limited`
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum LineRead {
+    /// A full line, including its newline, was appended to the buffer
+    Line,
+    /// The input ended; the buffer may hold a final line without a newline
+    Eof,
+    /// The buffer would have grown past the limit; it was cleared and the rest of
+    /// the line skipped
+    TooLarge,
+}
+
+/// Append one line to `buf` without letting it grow past `limit` bytes
+///
+/// An oversized line is skipped up to its newline, so the next read starts at the
+/// next frame. `discarding` records an unfinished skip and must be kept across
+/// calls for the reader to stay cancellation safe.
+pub(crate) async fn read_line_limited<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+    buf: &mut Vec<u8>,
+    limit: usize,
+    discarding: &mut bool,
+) -> std::io::Result<LineRead> {
+    loop {
+        let available = reader.fill_buf().await?;
+        if available.is_empty() {
+            return Ok(if std::mem::take(discarding) {
+                LineRead::TooLarge
+            } else {
+                LineRead::Eof
+            });
+        }
+
+        let (len, complete) = match available.iter().position(|&b| b == b'\n') {
+            Some(i) => (i + 1, true),
+            None => (available.len(), false),
+        };
+        if !*discarding {
+            if buf.len() + len > limit {
+                *discarding = true;
+                buf.clear();
+            } else {
+                buf.extend_from_slice(&available[..len]);
+            }
+        }
+        reader.consume(len);
+
+        if complete {
+            return Ok(if std::mem::take(discarding) {
+                LineRead::TooLarge
+            } else {
+                LineRead::Line
+            });
+        }
+    }
+}
+
 // Re-export default implementations
 pub use self::client::DefaultStdioClient;
 pub use self::server::DefaultStdioServer;
+pub use self::signing::{HmacAlgorithm, HmacConfig};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_oversized_line_is_skipped() {
+        let input = format!("{}\nshort\n", "x".repeat(100));
+        let mut reader = input.as_bytes();
+        let mut buf = Vec::new();
+        let mut discarding = false;
+
+        let read = read_line_limited(&mut reader, &mut buf, 16, &mut discarding).await;
+        assert_eq!(read.unwrap(), LineRead::TooLarge);
+        assert!(buf.is_empty());
+
+        let read = read_line_limited(&mut reader, &mut buf, 16, &mut discarding).await;
+        assert_eq!(read.unwrap(), LineRead::Line);
+        assert_eq!(buf, b"short\n");
+
+        buf.clear();
+        let read = read_line_limited(&mut reader, &mut buf, 16, &mut discarding).await;
+        assert_eq!(read.unwrap(), LineRead::Eof);
+    }
+}
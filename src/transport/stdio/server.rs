@@ -1,52 +1,159 @@
+use super::signing::HmacConfig;
+use super::{read_line_limited, LineRead, DEFAULT_MAX_MESSAGE_BYTES};
 use crate::{protocol::Message, Result};
 use async_trait::async_trait;
 use tokio::{
-    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    io::{AsyncRead, AsyncWrite, AsyncWriteExt, BufReader},
     sync::Mutex,
 };
 
+/// Boxed input stream the server reads messages from
+type BoxedReader = Box<dyn AsyncRead + Send + Unpin>;
+/// Boxed output stream the server writes messages to
+type BoxedWriter = Box<dyn AsyncWrite + Send + Unpin>;
+
+/// Destination of the server's log lines
+///
+/// Logs never go to stdout, which carries the protocol messages.
+#[derive(Default)]
+pub enum LogSink {
+    /// Write to the process's stderr
+    #[default]
+    Stderr,
+    /// Discard log lines
+    Null,
+    /// Write to the given stream, e.g. a log file
+    Writer(Box<dyn AsyncWrite + Send + Sync + Unpin>),
+}
+
+impl LogSink {
+    /// Sink writing to `writer`
+    pub fn writer<W: AsyncWrite + Send + Sync + Unpin + 'static>(writer: W) -> Self {
+        Self::Writer(Box::new(writer))
+    }
+}
+
 /// Stdio server configuration
 pub struct StdioServerConfig {
     /// Buffer size
     pub buffer_size: usize,
+    /// Require every incoming line to carry a valid HMAC signature
+    ///
+    /// Signed messages must fit on a single line.
+    pub verify_signature: Option<HmacConfig>,
+    /// Largest accepted message; longer ones are skipped with an error
+    pub max_message_bytes: usize,
+    /// Where `StdioServer::log` writes
+    pub log_sink: LogSink,
 }
 
 impl Default for StdioServerConfig {
     fn default() -> Self {
-        Self { buffer_size: 4096 }
+        Self {
+            buffer_size: 4096,
+            verify_signature: None,
+            max_message_bytes: DEFAULT_MAX_MESSAGE_BYTES,
+            log_sink: LogSink::default(),
+        }
+    }
+}
+
+/// Input side of the server, with data buffered until a full message is available
+struct Inbound {
+    reader: BufReader<BoxedReader>,
+    /// Bytes read so far that do not yet form a complete message
+    pending: Vec<u8>,
+    /// Whether the rest of an oversized line is being skipped
+    discarding: bool,
+}
+
+impl Inbound {
+    /// Discard buffered data, releasing memory held by an oversized message
+    fn reset(&mut self, capacity: usize) {
+        self.pending.clear();
+        self.pending.shrink_to(capacity);
     }
 }
 
+/// Whether buffered input holds nothing but whitespace
+fn is_blank(bytes: &[u8]) -> bool {
+    bytes.iter().all(u8::is_ascii_whitespace)
+}
+
 /// Stdio server implementation
 pub struct StdioServer {
     config: StdioServerConfig,
-    stdin: Mutex<BufReader<tokio::io::Stdin>>,
-    stdout: Mutex<tokio::io::Stdout>,
+    stdin: Mutex<Inbound>,
+    stdout: Mutex<BoxedWriter>,
+    log_sink: Mutex<LogSink>,
 }
 
 impl StdioServer {
     /// Create a new Stdio server
     pub fn new(config: StdioServerConfig) -> Self {
-        let stdin = BufReader::new(tokio::io::stdin());
-        let stdout = tokio::io::stdout();
+        Self::with_io(config, tokio::io::stdin(), tokio::io::stdout())
+    }
+
+    /// Create a new Stdio server over custom input and output streams
+    pub fn with_io<R, W>(mut config: StdioServerConfig, reader: R, writer: W) -> Self
+    where
+        R: AsyncRead + Send + Unpin + 'static,
+        W: AsyncWrite + Send + Unpin + 'static,
+    {
+        let stdin = Inbound {
+            reader: BufReader::new(Box::new(reader) as BoxedReader),
+            pending: Vec::with_capacity(config.buffer_size),
+            discarding: false,
+        };
+
+        let log_sink = std::mem::take(&mut config.log_sink);
 
         Self {
             config,
             stdin: Mutex::new(stdin),
-            stdout: Mutex::new(stdout),
+            stdout: Mutex::new(Box::new(writer)),
+            log_sink: Mutex::new(log_sink),
+        }
+    }
+
+    /// Verify the signature of a line and parse the signed JSON
+    async fn verify_line(&self, config: &HmacConfig, line: &[u8]) -> Result<Message> {
+        let line = std::str::from_utf8(line)
+            .map_err(|e| crate::Error::Parse(format!("Invalid message format: {}", e)))?;
+        match config.verify_line(line) {
+            Some(json) => Message::try_from(json).map_err(|e| match e {
+                crate::Error::Serialization(e) => {
+                    crate::Error::Parse(format!("Invalid message format: {}", e))
+                }
+                other => other,
+            }),
+            None => {
+                self.log("Signature verification failed").await?;
+                Err(crate::Error::Transport(
+                    "Signature verification failed".into(),
+                ))
+            }
         }
     }
 
-    /// Log a message (using stderr)
+    /// Log a message to the configured `LogSink`
     pub async fn log(&self, message: &str) -> Result<()> {
-        let mut stderr = tokio::io::stderr();
-        stderr.write_all(message.as_bytes()).await?;
-        stderr.write_all(b"\n").await?;
-        stderr.flush().await?;
-        Ok(())
+        match &mut *self.log_sink.lock().await {
+            LogSink::Stderr => write_line(&mut tokio::io::stderr(), message).await,
+            LogSink::Null => Ok(()),
+            LogSink::Writer(writer) => write_line(writer, message).await,
+        }
     }
 }
 
+/// Write `message` and a newline, then flush
+async fn write_line<W: AsyncWrite + Unpin>(writer: &mut W, message: &str) -> Result<()> {
+    writer.write_all(message.as_bytes()).await?;
+    writer.write_all(b"\n").await?;
+    writer.flush().await?;
+    Ok(())
+}
+
 #[async_trait]
 impl super::StdioTransport for StdioServer {
     async fn initialize(&mut self) -> Result<()> {
@@ -55,9 +162,10 @@ impl super::StdioTransport for StdioServer {
     }
 
     async fn send(&self, message: Message) -> Result<()> {
-        let mut stdout = self.stdout.lock().await;
-        let json = serde_json::to_string(&message)?;
+        self.send_raw(&serde_json::to_string(&message)?).await
+    }
 
+    async fn send_raw(&self, json: &str) -> Result<()> {
         // Check if the message contains a newline
         if json.contains('\n') {
             self.log("Warning: Message contains embedded newlines")
@@ -67,6 +175,7 @@ impl super::StdioTransport for StdioServer {
             ));
         }
 
+        let mut stdout = self.stdout.lock().await;
         stdout.write_all(json.as_bytes()).await?;
         stdout.write_all(b"\n").await?;
         stdout.flush().await?;
@@ -75,21 +184,88 @@ impl super::StdioTransport for StdioServer {
 
     async fn receive(&self) -> Result<Message> {
         let mut stdin = self.stdin.lock().await;
-        let mut line = String::with_capacity(self.config.buffer_size);
+        let capacity = self.config.buffer_size;
+        let limit = self.config.max_message_bytes;
 
-        if stdin.read_line(&mut line).await? == 0 {
-            self.log("Client connection closed").await?;
-            return Err(crate::Error::Transport("Client connection closed".into()));
-        }
+        // Keep reading until the buffered data forms a complete message. Partial
+        // data stays in `pending`, so it survives a cancelled receive.
+        loop {
+            let Inbound {
+                reader,
+                pending,
+                discarding,
+            } = &mut *stdin;
+            match read_line_limited(reader, pending, limit, discarding).await? {
+                LineRead::Line => {}
+                LineRead::TooLarge => {
+                    stdin.reset(capacity);
+                    self.log("Skipping message larger than the size limit")
+                        .await?;
+                    return Err(crate::Error::Protocol("message too large".into()));
+                }
+                LineRead::Eof => {
+                    if is_blank(pending) {
+                        stdin.reset(capacity);
+                        self.log("Client connection closed").await?;
+                        return Err(crate::Error::Transport("Client connection closed".into()));
+                    }
+
+                    if let Some(config) = &self.config.verify_signature {
+                        let line = std::mem::take(pending);
+                        stdin.reset(capacity);
+                        return self.verify_line(config, &line).await;
+                    }
+
+                    // The last message may legitimately be missing its trailing newline
+                    let result = Message::from_bytes(pending);
+                    stdin.reset(capacity);
+                    return match result {
+                        Ok(message) => Ok(message),
+                        Err(crate::Error::Serialization(e)) => {
+                            self.log(&format!("Incomplete message at end of input: {}", e))
+                                .await?;
+                            Err(crate::Error::Transport(format!(
+                                "Connection closed with incomplete message: {}",
+                                e
+                            )))
+                        }
+                        Err(e) => Err(e),
+                    };
+                }
+            }
 
-        match serde_json::from_str(&line) {
-            Ok(message) => Ok(message),
-            Err(e) => {
-                self.log(&format!("Error parsing message: {}", e)).await?;
-                Err(crate::Error::Transport(format!(
-                    "Invalid message format: {}",
-                    e
-                )))
+            if is_blank(pending) {
+                pending.clear();
+                continue;
+            }
+
+            if let Some(config) = &self.config.verify_signature {
+                let line = std::mem::take(pending);
+                stdin.reset(capacity);
+                return self.verify_line(config, &line).await;
+            }
+
+            match Message::from_bytes(pending) {
+                Ok(message) => {
+                    stdin.reset(capacity);
+                    return Ok(message);
+                }
+                // The message is not complete yet, wait for more data
+                Err(crate::Error::Serialization(e)) if e.is_eof() => continue,
+                // Only the bad line is discarded, so the caller can report the
+                // error and keep receiving
+                Err(crate::Error::Serialization(e)) => {
+                    stdin.reset(capacity);
+                    self.log(&format!("Error parsing message: {}", e)).await?;
+                    return Err(crate::Error::Parse(format!(
+                        "Invalid message format: {}",
+                        e
+                    )));
+                }
+                Err(e) => {
+                    stdin.reset(capacity);
+                    return Err(e);
+                }
             }
         }
     }
@@ -102,3 +278,198 @@ impl super::StdioTransport for StdioServer {
 
 /// Default Stdio server type
 pub type DefaultStdioServer = StdioServer;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::stdio::StdioTransport;
+    use std::time::Duration;
+    use tokio::io::{duplex, sink};
+
+    #[tokio::test]
+    async fn test_send_raw_writes_bytes_unchanged() {
+        use tokio::io::AsyncBufReadExt;
+
+        let (server_io, client) = duplex(1024);
+        let server =
+            StdioServer::with_io(StdioServerConfig::default(), tokio::io::empty(), server_io)
+                .into_transport();
+
+        // Key order and spacing differ from what `Message` would serialize to
+        let json = r#"{"id": 7, "method": "ping", "jsonrpc": "2.0"}"#;
+        server.send_raw(json).await.unwrap();
+
+        let mut line = String::new();
+        tokio::io::BufReader::new(client)
+            .read_line(&mut line)
+            .await
+            .unwrap();
+        assert_eq!(line, format!("{}\n", json));
+
+        assert!(server.send_raw("{\"jsonrpc\":\n\"2.0\"}").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_logs_go_to_configured_sink() {
+        use tokio::io::AsyncReadExt;
+
+        let (log_writer, mut log_reader) = duplex(1024);
+        let config = StdioServerConfig {
+            log_sink: LogSink::writer(log_writer),
+            ..Default::default()
+        };
+        let mut server = StdioServer::with_io(config, tokio::io::empty(), sink());
+        server.initialize().await.unwrap();
+        server.log("custom line").await.unwrap();
+        drop(server);
+
+        let mut logs = String::new();
+        log_reader.read_to_string(&mut logs).await.unwrap();
+        assert_eq!(logs, "MCP server initialized\ncustom line\n");
+
+        let config = StdioServerConfig {
+            log_sink: LogSink::Null,
+            ..Default::default()
+        };
+        let server = StdioServer::with_io(config, tokio::io::empty(), sink());
+        server.log("dropped").await.unwrap();
+    }
+
+    fn signed_server(input: String) -> StdioServer {
+        let config = StdioServerConfig {
+            verify_signature: Some(HmacConfig::new("secret")),
+            ..Default::default()
+        };
+        StdioServer::with_io(config, std::io::Cursor::new(input), sink())
+    }
+
+    #[tokio::test]
+    async fn test_signed_message_is_accepted() {
+        let line =
+            HmacConfig::new("secret").sign_line(r#"{"jsonrpc":"2.0","method":"ping","id":1}"#);
+        let server = signed_server(format!("{}\n", line));
+
+        let message = server.receive().await.unwrap();
+        assert!(matches!(message, Message::Request(r) if r.method == "ping"));
+    }
+
+    #[tokio::test]
+    async fn test_tampered_and_unsigned_messages_are_rejected() {
+        let line =
+            HmacConfig::new("secret").sign_line(r#"{"jsonrpc":"2.0","method":"ping","id":1}"#);
+        let tampered = line.replace("ping", "exit");
+        let unsigned = r#"{"jsonrpc":"2.0","method":"ping","id":2}"#;
+        let server = signed_server(format!("{}\n{}\n{}\n", tampered, unsigned, line));
+
+        for _ in 0..2 {
+            match server.receive().await {
+                Err(crate::Error::Transport(message)) => {
+                    assert_eq!(message, "Signature verification failed")
+                }
+                other => panic!("unexpected result: {:?}", other),
+            }
+        }
+
+        // The stream stays usable after a rejected line
+        assert!(server.receive().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_message_split_across_writes() {
+        let (mut client, server_io) = duplex(1024);
+        let server = StdioServer::with_io(StdioServerConfig::default(), server_io, sink());
+
+        let json = r#"{"jsonrpc":"2.0","method":"ping","id":1}"#;
+        let (first, second) = json.split_at(json.len() / 2);
+
+        client.write_all(first.as_bytes()).await.unwrap();
+        let receive = tokio::spawn(async move { server.receive().await });
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!receive.is_finished());
+
+        client.write_all(second.as_bytes()).await.unwrap();
+        client.write_all(b"\n").await.unwrap();
+
+        let message = receive.await.unwrap().unwrap();
+        assert!(matches!(message, Message::Request(r) if r.method == "ping"));
+    }
+
+    #[tokio::test]
+    async fn test_message_spanning_multiple_lines() {
+        let input = "{\"jsonrpc\":\"2.0\",\n\"method\":\"ping\",\n\"id\":1}\n";
+        let server = StdioServer::with_io(StdioServerConfig::default(), input.as_bytes(), sink());
+
+        let message = server.receive().await.unwrap();
+        assert!(matches!(message, Message::Request(r) if r.method == "ping"));
+    }
+
+    #[tokio::test]
+    async fn test_final_message_without_newline() {
+        let input = r#"{"jsonrpc":"2.0","method":"initialized"}"#;
+        let server = StdioServer::with_io(StdioServerConfig::default(), input.as_bytes(), sink());
+
+        let message = server.receive().await.unwrap();
+        assert!(matches!(message, Message::Notification(_)));
+        assert!(server.receive().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_incomplete_message_at_eof() {
+        let input = r#"{"jsonrpc":"2.0","method":"#;
+        let server = StdioServer::with_io(StdioServerConfig::default(), input.as_bytes(), sink());
+
+        let err = server.receive().await.unwrap_err();
+        assert!(err.to_string().contains("incomplete message"));
+    }
+
+    #[tokio::test]
+    async fn test_receive_continues_after_garbage_line() {
+        let input = "not json\n{\"jsonrpc\":\"2.0\",\"method\":\"ping\",\"id\":1}\n";
+        let server = StdioServer::with_io(StdioServerConfig::default(), input.as_bytes(), sink());
+
+        assert!(matches!(
+            server.receive().await,
+            Err(crate::Error::Parse(_))
+        ));
+        let message = server.receive().await.unwrap();
+        assert!(matches!(message, Message::Request(r) if r.method == "ping"));
+    }
+
+    #[tokio::test]
+    async fn test_oversized_message_is_skipped() {
+        let config = StdioServerConfig {
+            max_message_bytes: 64,
+            ..Default::default()
+        };
+        let oversized = format!(
+            "{{\"jsonrpc\":\"2.0\",\"method\":\"ping\",\"params\":\"{}\",\"id\":1}}",
+            "x".repeat(1000)
+        );
+        let input = format!(
+            "{}\n{{\"jsonrpc\":\"2.0\",\"method\":\"ping\",\"id\":2}}\n",
+            oversized
+        );
+        let server = StdioServer::with_io(config, std::io::Cursor::new(input), sink());
+
+        match server.receive().await {
+            Err(crate::Error::Protocol(message)) => assert_eq!(message, "message too large"),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        let message = server.receive().await.unwrap();
+        assert!(
+            matches!(message, Message::Request(r) if r.id == crate::protocol::RequestId::Number(2))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_blank_lines_are_skipped() {
+        let input = "\n\n{\"jsonrpc\":\"2.0\",\"method\":\"ping\",\"id\":1}\n";
+        let server = StdioServer::with_io(StdioServerConfig::default(), input.as_bytes(), sink());
+
+        assert!(matches!(
+            server.receive().await.unwrap(),
+            Message::Request(_)
+        ));
+    }
+}
@@ -0,0 +1,135 @@
+use crate::transport::middleware::REDACTED;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use hmac::{Hmac, Mac};
+use sha2::{Sha256, Sha512};
+
+/// Hash function used for line signatures
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HmacAlgorithm {
+    /// HMAC-SHA256
+    #[default]
+    Sha256,
+    /// HMAC-SHA512
+    Sha512,
+}
+
+/// Shared-secret HMAC settings for signing stdio messages
+///
+/// A signed line has the form `<json>\t<base64 hmac>\n`, where the HMAC covers the
+/// JSON text exactly as written. `Debug` output shows the secret as `[REDACTED]`.
+#[derive(Clone)]
+pub struct HmacConfig {
+    /// Secret shared by client and server
+    pub secret_key: String,
+    /// Hash function
+    pub algorithm: HmacAlgorithm,
+}
+
+impl std::fmt::Debug for HmacConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HmacConfig")
+            .field("secret_key", &REDACTED)
+            .field("algorithm", &self.algorithm)
+            .finish()
+    }
+}
+
+impl HmacConfig {
+    /// Create a config using HMAC-SHA256
+    pub fn new(secret_key: &str) -> Self {
+        Self {
+            secret_key: secret_key.to_string(),
+            algorithm: HmacAlgorithm::default(),
+        }
+    }
+
+    /// Base64-encoded signature of a payload
+    pub fn sign(&self, payload: &str) -> String {
+        let tag = match self.algorithm {
+            HmacAlgorithm::Sha256 => {
+                let mut mac = Hmac::<Sha256>::new_from_slice(self.secret_key.as_bytes())
+                    .expect("HMAC accepts keys of any length");
+                mac.update(payload.as_bytes());
+                mac.finalize().into_bytes().to_vec()
+            }
+            HmacAlgorithm::Sha512 => {
+                let mut mac = Hmac::<Sha512>::new_from_slice(self.secret_key.as_bytes())
+                    .expect("HMAC accepts keys of any length");
+                mac.update(payload.as_bytes());
+                mac.finalize().into_bytes().to_vec()
+            }
+        };
+        STANDARD.encode(tag)
+    }
+
+    /// Check a base64-encoded signature in constant time
+    pub fn verify(&self, payload: &str, signature: &str) -> bool {
+        let Ok(tag) = STANDARD.decode(signature) else {
+            return false;
+        };
+        match self.algorithm {
+            HmacAlgorithm::Sha256 => {
+                let mut mac = Hmac::<Sha256>::new_from_slice(self.secret_key.as_bytes())
+                    .expect("HMAC accepts keys of any length");
+                mac.update(payload.as_bytes());
+                mac.verify_slice(&tag).is_ok()
+            }
+            HmacAlgorithm::Sha512 => {
+                let mut mac = Hmac::<Sha512>::new_from_slice(self.secret_key.as_bytes())
+                    .expect("HMAC accepts keys of any length");
+                mac.update(payload.as_bytes());
+                mac.verify_slice(&tag).is_ok()
+            }
+        }
+    }
+
+    /// Append the signature to a JSON line (without the trailing newline)
+    pub fn sign_line(&self, json: &str) -> String {
+        format!("{}\t{}", json, self.sign(json))
+    }
+
+    /// Split a signed line and verify it, returning the JSON part
+    pub fn verify_line<'a>(&self, line: &'a str) -> Option<&'a str> {
+        let line = line.trim_end_matches(['\r', '\n']);
+        let (json, signature) = line.rsplit_once('\t')?;
+        self.verify(json, signature).then_some(json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debug_redacts_the_secret() {
+        let debug = format!("{:?}", HmacConfig::new("hunter2"));
+        assert!(!debug.contains("hunter2"));
+        assert!(debug.contains(REDACTED));
+    }
+
+    #[test]
+    fn test_signed_line_round_trip() {
+        for algorithm in [HmacAlgorithm::Sha256, HmacAlgorithm::Sha512] {
+            let config = HmacConfig {
+                secret_key: "secret".to_string(),
+                algorithm,
+            };
+            let json = r#"{"jsonrpc":"2.0","method":"ping","id":1}"#;
+            let line = config.sign_line(json);
+            assert_eq!(config.verify_line(&format!("{}\n", line)), Some(json));
+        }
+    }
+
+    #[test]
+    fn test_tampered_or_unsigned_lines_are_rejected() {
+        let config = HmacConfig::new("secret");
+        let line = config.sign_line(r#"{"jsonrpc":"2.0","method":"ping","id":1}"#);
+
+        let tampered = line.replace("\"id\":1", "\"id\":2");
+        assert!(config.verify_line(&tampered).is_none());
+        assert!(HmacConfig::new("other").verify_line(&line).is_none());
+        assert!(config
+            .verify_line(r#"{"jsonrpc":"2.0","method":"ping","id":1}"#)
+            .is_none());
+    }
+}
@@ -0,0 +1,12 @@
+#![deny(unused_must_use)]
+use mcprotocol_rs::protocol::{Message, Request, RequestId};
+use mcprotocol_rs::transport::{MemoryTransport, Transport};
+
+fn start_sending(transport: &dyn Transport) {
+    transport.send(Message::Request(Request::ping(RequestId::Number(1))));
+}
+
+fn main() {
+    let (transport, _peer) = MemoryTransport::pair();
+    start_sending(&transport);
+}